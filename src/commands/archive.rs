@@ -0,0 +1,61 @@
+use std::path;
+
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::fs::ArchiveKind;
+use crate::types::io::{FileOperation, FileOperationOptions, IoTask};
+use crate::types::state::AppState;
+
+/// Returns the selected paths (or the current entry's, if none selected) of the current tab,
+/// along with its working directory.
+fn selected_paths(app_state: &AppState) -> AppResult<(Vec<path::PathBuf>, path::PathBuf)> {
+    let tab = app_state.state.tab_state_ref().curr_tab_ref();
+    let paths = tab
+        .curr_list_ref()
+        .map(|list| list.get_selected_paths())
+        .unwrap_or_default();
+    if paths.is_empty() {
+        let err_msg = "No files selected";
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            err_msg.to_string(),
+        ));
+    }
+    Ok((paths, tab.get_cwd().to_path_buf()))
+}
+
+/// Implements `extract`: queues a background task extracting the selected archives (or the
+/// current entry) into the current directory, each into a new directory named after it if
+/// `subdir`.
+pub fn extract(app_state: &mut AppState, subdir: bool, overwrite: bool) -> AppResult {
+    let (paths, cwd) = selected_paths(app_state)?;
+    if let Some(path) = paths.iter().find(|p| ArchiveKind::from_path(p).is_none()) {
+        let file_name = path.file_name().unwrap_or_default().to_string_lossy();
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            format!("{}: not a supported archive", file_name),
+        ));
+    }
+
+    let options = FileOperationOptions {
+        overwrite,
+        subdir,
+        ..Default::default()
+    };
+    let task = IoTask::new(FileOperation::Extract, paths, cwd, options);
+    app_state.state.worker_state_mut().push_task(task);
+    Ok(())
+}
+
+/// Implements `compress`: queues a background task creating the archive `name` in the current
+/// directory from the selected entries (or the current entry), in the format its extension
+/// names.
+pub fn compress(app_state: &mut AppState, name: &path::Path, overwrite: bool) -> AppResult {
+    let (paths, cwd) = selected_paths(app_state)?;
+    let options = FileOperationOptions {
+        overwrite,
+        ..Default::default()
+    };
+    let task = IoTask::new(FileOperation::Compress, paths, cwd.join(name), options);
+    app_state.state.worker_state_mut().push_task(task);
+    Ok(())
+}
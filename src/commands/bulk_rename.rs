@@ -11,6 +11,7 @@ use crate::types::state::remove_external_preview;
 use crate::types::state::AppState;
 use crate::ui::AppBackend;
 use crate::utils::format::clear_screen;
+use crate::utils::fs::rename_tags;
 use crate::utils::process::wait_for_enter;
 
 use super::reload;
@@ -118,7 +119,12 @@ pub fn _bulk_rename(app_state: &mut AppState) -> AppResult {
                     .arg(p.file_name())
                     .arg(q)
                     .spawn()?;
-                handle.wait()?;
+                // `mv -i` may have been told not to overwrite
+                let renamed =
+                    handle.wait()?.success() && fs::symlink_metadata(p.file_path()).is_err();
+                if let (true, Some(dir)) = (renamed, p.file_path().parent()) {
+                    rename_tags(p.file_path(), &dir.join(q));
+                }
             }
         }
         _ => {}
@@ -1,13 +1,15 @@
 use std::path::Path;
 
-use crate::commands::{reload, zoxide};
+use crate::commands::{dir_settings, reload, zoxide};
 use crate::error::AppResult;
+use crate::fs::VfsPath;
 use crate::history::{generate_entries_to_root, DirectoryHistory};
 use crate::types::state::AppState;
 use crate::utils::cwd;
 
-/// Sets the process and tab working directory to `path`, updating zoxide's database if enabled.
-/// Lower-level than [`change_directory`]: doesn't touch the directory-listing cache.
+/// Sets the process and tab working directory to `path`, updating zoxide's database if enabled,
+/// and applies the directory's view settings. Lower-level than [`change_directory`]: only
+/// touches the directory-listing cache if those settings change the tab's.
 pub fn cd(path: &Path, app_state: &mut AppState, history_update: bool) -> std::io::Result<()> {
     cwd::set_current_dir(path)?;
     app_state
@@ -15,7 +17,10 @@ pub fn cd(path: &Path, app_state: &mut AppState, history_update: bool) -> std::i
         .tab_state_mut()
         .curr_tab_mut()
         .set_cwd(path, history_update);
-    if app_state.config.zoxide_update {
+    if dir_settings::apply(app_state) {
+        reload::soft_reload_curr_tab(app_state)?;
+    }
+    if app_state.config.zoxide_update && !VfsPath::resolve(path).is_archive() {
         debug_assert!(path.is_absolute());
         zoxide::zoxide_add(path.to_str().expect("cannot convert path to string"))?;
     }
@@ -33,7 +38,13 @@ pub fn change_directory(app_state: &mut AppState, mut path: &Path) -> AppResult
             path = p;
         }
 
-        let mut new_cwd = std::env::current_dir()?;
+        // the tab's, as the process's can't be inside an archive
+        let mut new_cwd = app_state
+            .state
+            .tab_state_ref()
+            .curr_tab_ref()
+            .get_cwd()
+            .to_path_buf();
         new_cwd.push(path);
         new_cwd
     };
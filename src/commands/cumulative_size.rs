@@ -1,52 +1,55 @@
-use std::fs;
+use std::collections::HashMap;
 use std::path;
+use std::time;
 
 use crate::error::AppResult;
 use crate::types::state::AppState;
 use crate::utils::format::file_size_to_string;
 
-/// Recursively compute the total size in bytes of every regular file rooted
-/// at `path`. Symlinks are not followed; their own size is included instead.
-/// I/O errors on individual entries are silently skipped, matching `du`.
-fn compute_size(path: &path::Path) -> u64 {
-    let symlink_meta = match fs::symlink_metadata(path) {
-        Ok(m) => m,
-        Err(_) => return 0,
-    };
-
-    let file_type = symlink_meta.file_type();
-    if file_type.is_symlink() {
-        return symlink_meta.len();
-    }
-    if !file_type.is_dir() {
-        return symlink_meta.len();
-    }
-
-    let read_dir = match fs::read_dir(path) {
-        Ok(rd) => rd,
-        Err(_) => return symlink_meta.len(),
-    };
-
-    let mut total: u64 = 0;
-    for entry in read_dir.flatten() {
-        total = total.saturating_add(compute_size(&entry.path()));
+/// Formats the message reporting the sizes of the entries named `names`.
+fn size_message(names: &[String], total: u64) -> String {
+    if names.len() == 1 {
+        format!(
+            "Size of {}: {}",
+            names[0].trim(),
+            file_size_to_string(total).trim()
+        )
+    } else {
+        format!(
+            "Cumulative size of {} items: {}",
+            names.len(),
+            file_size_to_string(total).trim()
+        )
     }
-    total
 }
 
-/// Implements `calculate_cumulative_size`: computes and displays the total recursive size of
-/// the selected entries (or the current entry, if none selected).
-pub fn calculate_cumulative_size(app_state: &mut AppState) -> AppResult {
-    let targets: Vec<(path::PathBuf, String)> = match app_state
+/// Implements `calculate_cumulative_size`: computes the total recursive size of the selected
+/// entries (or the current entry, if none selected) in the background, reporting it once done.
+/// With `all`, computes the size of every directory in the listing instead, so that it can be
+/// sorted by them. Sizes computed before are reused unless `force` is set, or the entry was
+/// modified since.
+pub fn calculate_cumulative_size(app_state: &mut AppState, all: bool, force: bool) -> AppResult {
+    let targets: Vec<(path::PathBuf, time::SystemTime, String)> = match app_state
         .state
         .tab_state_ref()
         .curr_tab_ref()
         .curr_list_ref()
     {
+        Some(list) if all => list
+            .iter()
+            .filter(|e| e.metadata.is_dir())
+            .map(|e| {
+                let modified = e.metadata.modified();
+                (e.file_path_buf(), modified, e.file_name().to_string())
+            })
+            .collect(),
         Some(list) => list
             .selected_or_current()
             .into_iter()
-            .map(|e| (e.file_path().to_path_buf(), e.file_name().to_string()))
+            .map(|e| {
+                let modified = e.metadata.modified();
+                (e.file_path_buf(), modified, e.file_name().to_string())
+            })
             .collect(),
         None => Vec::new(),
     };
@@ -55,41 +58,77 @@ pub fn calculate_cumulative_size(app_state: &mut AppState) -> AppResult {
         return Ok(());
     }
 
-    let mut total: u64 = 0;
-    let mut sizes: Vec<(String, u64)> = Vec::with_capacity(targets.len());
-    for (path, name) in &targets {
-        let size = compute_size(path);
-        total = total.saturating_add(size);
-        sizes.push((name.clone(), size));
+    let names: Vec<String> = targets.iter().map(|(_, _, name)| name.clone()).collect();
+    let size_state = app_state.state.cumulative_size_state_ref();
+    let (cached, uncached): (Vec<_>, Vec<_>) = targets
+        .into_iter()
+        .map(|(path, modified, _)| {
+            let size = size_state.get(&path, modified).filter(|_| !force);
+            (path, modified, size)
+        })
+        .partition(|(_, _, size)| size.is_some());
+
+    let cached_total = cached
+        .iter()
+        .filter_map(|(_, _, size)| *size)
+        .fold(0u64, u64::saturating_add);
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
+    let sort_options = curr_tab.option_ref().sort_options_ref().clone();
+    if let Some(list) = curr_tab.curr_list_mut() {
+        let sizes: HashMap<path::PathBuf, u64> = cached
+            .iter()
+            .map(|(path, _, size)| (path.clone(), size.unwrap_or_default()))
+            .collect();
+        list.update_entries(
+            &sizes,
+            |entry, size| entry.metadata.update_cumulative_size(*size),
+            &sort_options,
+            &ui_state,
+            display_options,
+        );
     }
 
-    if let Some(list) = app_state
-        .state
-        .tab_state_mut()
-        .curr_tab_mut()
-        .curr_list_mut()
-    {
-        for (name, size) in &sizes {
-            if let Some(entry) = list.iter_mut().find(|e| e.file_name() == name.as_str()) {
-                entry.metadata.update_cumulative_size(*size);
-            }
-        }
+    if uncached.is_empty() {
+        app_state
+            .state
+            .message_queue_mut()
+            .push_info(size_message(&names, cached_total));
+        return Ok(());
     }
 
-    let msg = if sizes.len() == 1 {
-        format!(
-            "Size of {}: {}",
-            sizes[0].0.trim(),
-            file_size_to_string(total).trim()
-        )
-    } else {
-        format!(
-            "Cumulative size of {} items: {}",
-            sizes.len(),
-            file_size_to_string(total).trim()
-        )
-    };
-    app_state.state.message_queue_mut().push_info(msg);
+    let count = uncached.len();
+    let requests = uncached
+        .into_iter()
+        .map(|(path, modified, _)| (path, modified))
+        .collect();
+    app_state
+        .state
+        .cumulative_size_state_mut()
+        .request(requests, move |sizes| {
+            let total = sizes
+                .iter()
+                .fold(cached_total, |acc, s| acc.saturating_add(*s));
+            size_message(&names, total)
+        });
+    app_state
+        .state
+        .message_queue_mut()
+        .push_info(format!("Calculating size of {} items...", count));
+    Ok(())
+}
 
+/// Implements `cancel_cumulative_size`: stops computing sizes in the background.
+pub fn cancel_cumulative_size(app_state: &mut AppState) -> AppResult {
+    let count = app_state.state.cumulative_size_state_mut().cancel();
+    // sizes computed so far are sorted by now
+    app_state
+        .state
+        .sort_cumulative_sizes(&app_state.config.display_options);
+    app_state
+        .state
+        .message_queue_mut()
+        .push_info(format!("Cancelled calculating size of {} items", count));
     Ok(())
 }
@@ -1,15 +1,17 @@
 use super::change_directory::change_directory;
 use super::sub_process::current_files;
-use crate::commands::cursor_move;
+use crate::commands::{cursor_move, virtual_list};
 use crate::error::{AppError, AppErrorKind, AppResult};
 use crate::types::state::AppState;
 use crate::ui::AppBackend;
 use shell_words::split;
+use std::path::PathBuf;
 use std::process::{Command, Stdio};
 
 /// Implements `custom_search`/`custom_search_interactive`: runs the named user-configured
 /// command (substituting `%s` with selected files and `%text` with extra args), then changes
-/// into the directory of the path it prints to stdout.
+/// into the directory of the path it prints to stdout, or shows the paths in a virtual listing
+/// if it prints several.
 pub fn custom_search(
     app_state: &mut AppState,
     backend: &mut AppBackend,
@@ -76,6 +78,16 @@ pub fn custom_search(
             })?
             .trim_end();
 
+        // several paths are shown as one virtual listing
+        let paths: Vec<PathBuf> = returned_text
+            .lines()
+            .filter(|line| !line.is_empty())
+            .map(PathBuf::from)
+            .collect();
+        if paths.len() > 1 {
+            return virtual_list::open_virtual_list(app_state, paths);
+        }
+
         let path = std::path::Path::new(returned_text);
         change_directory(
             app_state,
@@ -14,7 +14,7 @@ use super::tab_ops;
 
 /// Prompts for confirmation before deleting `paths_len` files, with an extra confirmation for
 /// more than one file.
-fn prompt(app_state: &mut AppState, backend: &mut AppBackend, paths_len: usize) -> bool {
+pub fn prompt(app_state: &mut AppState, backend: &mut AppBackend, paths_len: usize) -> bool {
     let ch = {
         let prompt_str = format!("Delete {} files? (Y/n)", paths_len);
         let mut prompt = TuiPrompt::new(&prompt_str);
@@ -41,7 +41,7 @@ fn prompt(app_state: &mut AppState, backend: &mut AppBackend, paths_len: usize)
 
 /// Queues or immediately runs a delete operation on `paths`, purging any deleted directories
 /// from the listing cache.
-fn delete_files(
+pub fn delete_files(
     app_state: &mut AppState,
     paths: Vec<path::PathBuf>,
     background: bool,
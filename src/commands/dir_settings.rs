@@ -0,0 +1,172 @@
+use std::path::Path;
+
+use crate::error::AppResult;
+use crate::history::DirectoryHistory;
+use crate::types::option::dir_settings::DirSettings;
+use crate::types::option::line_mode::LineMode;
+use crate::types::option::sort::SortMethod;
+use crate::types::state::AppState;
+
+use super::show_hidden;
+
+/// Returns the current tab's settings that a directory can override.
+fn current_settings(app_state: &AppState) -> DirSettings {
+    let tab_options = app_state.state.tab_state_ref().curr_tab_ref().option_ref();
+    let sort_options = tab_options.sort_options_ref();
+    DirSettings {
+        sort_method: sort_options
+            .sort_methods
+            .list
+            .front()
+            .map(|m| m.to_string()),
+        reverse: Some(sort_options.reverse),
+        linemode: Some(tab_options.linemode.as_string()),
+        show_hidden: Some(app_state.config.display_options.show_hidden),
+        depth: None,
+    }
+}
+
+/// Applies the settings of the current directory, from the configured rules and the ones
+/// remembered from earlier sessions, to the current tab. Leaving such a directory for one
+/// without settings brings back the tab's own settings.
+///
+/// Returns `true` if anything changed, in which case the cached listings are out of date.
+pub fn apply(app_state: &mut AppState) -> bool {
+    let cwd = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    let mut overrides = app_state.config.dir_settings_options.settings_for(&cwd);
+    if let Some(remembered) = app_state.state.dir_settings_state_ref().get(&cwd) {
+        overrides.merge(remembered);
+    }
+
+    let base = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .option_ref()
+        .dir_settings_base
+        .clone();
+    let (settings, base) = if overrides.is_empty() {
+        match base {
+            Some(base) => (base, None),
+            None => return false,
+        }
+    } else {
+        let base = base.unwrap_or_else(|| current_settings(app_state));
+        let mut settings = base.clone();
+        settings.merge(&overrides);
+        (settings, Some(base))
+    };
+    app_state
+        .state
+        .tab_state_mut()
+        .curr_tab_mut()
+        .option_mut()
+        .dir_settings_base = base;
+    set_settings(app_state, &cwd, &settings)
+}
+
+fn set_settings(app_state: &mut AppState, cwd: &Path, settings: &DirSettings) -> bool {
+    let display_options = &app_state.config.display_options;
+    // settings naming unknown sort methods or linemodes are ignored
+    let sort_method = settings
+        .sort_method
+        .as_deref()
+        .and_then(SortMethod::from_str)
+        .filter(|method| match method {
+            SortMethod::Custom(name) => display_options.custom_column(name).is_some(),
+            _ => true,
+        });
+    let linemode = settings
+        .linemode
+        .as_deref()
+        .and_then(|s| LineMode::from_string(s).ok())
+        .filter(|linemode| {
+            linemode
+                .custom_columns()
+                .all(|name| display_options.custom_column(name).is_some())
+        });
+    let show_hidden = settings
+        .show_hidden
+        .filter(|show_hidden| *show_hidden != display_options.show_hidden);
+
+    let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
+    let mut changed = false;
+    let tab_options = curr_tab.option_mut();
+    if let Some(method) = sort_method {
+        if tab_options.sort_options.sort_methods.list.front() != Some(&method) {
+            tab_options.sort_options_mut().set_sort_method(method);
+            changed = true;
+        }
+    }
+    if let Some(reverse) = settings.reverse {
+        if tab_options.sort_options.reverse != reverse {
+            tab_options.sort_options_mut().reverse = reverse;
+            changed = true;
+        }
+    }
+    if let Some(linemode) = linemode {
+        if tab_options.linemode != linemode {
+            tab_options.linemode = linemode;
+            changed = true;
+        }
+    }
+    if let Some(depth) = settings.depth {
+        let dirlist_options = tab_options.dirlist_options_mut(&cwd.to_path_buf());
+        if dirlist_options.depth() != depth {
+            dirlist_options.set_depth(depth);
+            if let Some(list) = curr_tab.history_mut().get_mut(cwd) {
+                list.depreciate();
+            }
+            changed = true;
+        }
+    }
+    if changed {
+        curr_tab.history_mut().depreciate_all_entries();
+    }
+    if show_hidden.is_some() {
+        show_hidden::_toggle_hidden(app_state);
+        changed = true;
+    }
+    changed
+}
+
+/// Remembers a change to the current directory's settings made by `f`, if enabled. Called
+/// before the change is applied to the tab, so that the tab's own settings can be brought back
+/// when leaving the directory.
+pub fn remember<F>(app_state: &mut AppState, f: F) -> AppResult
+where
+    F: FnOnce(&mut DirSettings),
+{
+    if !app_state.config.dir_settings_options.remember {
+        return Ok(());
+    }
+    if app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .option_ref()
+        .dir_settings_base
+        .is_none()
+    {
+        let base = current_settings(app_state);
+        app_state
+            .state
+            .tab_state_mut()
+            .curr_tab_mut()
+            .option_mut()
+            .dir_settings_base = Some(base);
+    }
+    let cwd = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    app_state.state.dir_settings_state_mut().update(&cwd, f)?;
+    Ok(())
+}
@@ -0,0 +1,333 @@
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+use uuid::Uuid;
+
+use crate::error::AppResult;
+use crate::fs::DiskUsageNode;
+use crate::history::DirectoryHistory;
+use crate::run::process_event;
+use crate::types::command::Command;
+use crate::types::event::{AppEvent, AppEventSender};
+use crate::types::keybind::CommandKeybind;
+use crate::types::keymap::AppKeyMapping;
+use crate::types::state::remove_external_preview;
+use crate::types::state::AppState;
+use crate::ui::views::TuiDiskUsageView;
+use crate::ui::AppBackend;
+
+use super::{delete_files, reload};
+
+const PROGRESS_INTERVAL: Duration = Duration::from_millis(200);
+
+/// A scanned tree and where in it the user currently is.
+struct DiskUsageCursor {
+    tree: DiskUsageNode,
+    /// child indices leading from the root to the shown directory
+    indices: Vec<usize>,
+    /// index of the highlighted entry of the shown directory
+    index: usize,
+    /// number of entries scrolled past
+    skip: usize,
+}
+
+impl DiskUsageCursor {
+    fn new(tree: DiskUsageNode) -> Self {
+        Self {
+            tree,
+            indices: Vec::new(),
+            index: 0,
+            skip: 0,
+        }
+    }
+
+    fn node(&self) -> &DiskUsageNode {
+        self.tree.get(&self.indices).unwrap_or(&self.tree)
+    }
+
+    fn len(&self) -> usize {
+        self.node().children.len()
+    }
+
+    /// Moves the cursor by `offset` entries, clamped to the shown directory.
+    fn move_by(&mut self, offset: isize) {
+        let last = self.len().saturating_sub(1) as isize;
+        self.index = (self.index as isize + offset).clamp(0, last) as usize;
+    }
+
+    /// Returns the path of the shown directory, relative to the scanned directory.
+    fn dir_path(&self) -> PathBuf {
+        let mut path = PathBuf::new();
+        let mut node = &self.tree;
+        for &i in &self.indices {
+            match node.children.get(i) {
+                Some(child) => node = child,
+                None => break,
+            }
+            path.push(&node.name);
+        }
+        path
+    }
+
+    /// Returns the path of the highlighted entry, relative to the scanned directory.
+    fn curr_path(&self) -> Option<PathBuf> {
+        let child = self.node().children.get(self.index)?;
+        Some(self.dir_path().join(&child.name))
+    }
+
+    fn enter(&mut self) {
+        let is_dir = self
+            .node()
+            .children
+            .get(self.index)
+            .is_some_and(|c| c.is_dir);
+        if is_dir {
+            self.indices.push(self.index);
+            self.index = 0;
+            self.skip = 0;
+        }
+    }
+
+    fn leave(&mut self) {
+        if let Some(index) = self.indices.pop() {
+            self.index = index;
+            self.skip = 0;
+        }
+    }
+
+    /// Removes the highlighted entry from the tree, updating the totals above it.
+    fn remove_curr(&mut self) {
+        let mut indices = self.indices.clone();
+        indices.push(self.index);
+        if self.tree.remove(&indices).is_some() {
+            self.move_by(0);
+        }
+    }
+
+    /// Scrolls so that the cursor is within the `height` visible entries.
+    fn scroll(&mut self, height: usize) {
+        if self.index < self.skip {
+            self.skip = self.index;
+        } else if height > 0 && self.index >= self.skip + height {
+            self.skip = self.index + 1 - height;
+        }
+    }
+}
+
+/// Scans `path` on a background thread, reporting progress and the finished tree as
+/// [`AppEvent::DiskUsageProgress`] and [`AppEvent::DiskUsageScanned`] tagged with `id`.
+fn start_scan(path: PathBuf, id: Uuid, cancel: Arc<AtomicBool>, event_tx: AppEventSender) {
+    thread::spawn(move || {
+        let files = AtomicU64::new(0);
+        let done = AtomicBool::new(false);
+        let tree = thread::scope(|s| {
+            s.spawn(|| {
+                while !done.load(Ordering::Relaxed) {
+                    thread::sleep(PROGRESS_INTERVAL);
+                    let files = files.load(Ordering::Relaxed);
+                    let _ = event_tx.send(AppEvent::DiskUsageProgress { id, files });
+                }
+            });
+            let tree = DiskUsageNode::scan(&path, &cancel, &files);
+            done.store(true, Ordering::Relaxed);
+            tree
+        });
+        if let Some(tree) = tree {
+            let _ = event_tx.send(AppEvent::DiskUsageScanned {
+                id,
+                tree: Box::new(tree),
+            });
+        }
+    });
+}
+
+/// Deletes the highlighted entry (prompting unless `noconfirm`) and drops it from the tree.
+/// With `background`, the entry is dropped before it's actually deleted, and its path added
+/// to `pending` for checking once the deletion is done. Returns `true` if it was deleted.
+#[allow(clippy::too_many_arguments)]
+fn delete_curr(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    root: &Path,
+    cursor: &mut DiskUsageCursor,
+    pending: &mut Vec<PathBuf>,
+    background: bool,
+    permanently: bool,
+    noconfirm: bool,
+) -> AppResult<bool> {
+    let Some(path) = cursor.curr_path().map(|p| root.join(p)) else {
+        return Ok(false);
+    };
+    if !noconfirm && !delete_files::prompt(app_state, backend, 1) {
+        return Ok(false);
+    }
+    delete_files::delete_files(app_state, vec![path.clone()], background, permanently)?;
+    if background {
+        pending.push(path);
+    }
+    cursor.remove_curr();
+    Ok(true)
+}
+
+/// Implements `disk_usage`: scans the current directory's whole tree once, then runs a nested
+/// event loop for browsing it with the default keymap, largest entries first. Entries deleted
+/// from the view are subtracted from the totals without rescanning, unless deleting them in the
+/// background fails; `reload_dirlist` rescans.
+pub fn disk_usage(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    keymap_t: &AppKeyMapping,
+) -> AppResult {
+    app_state.flush_event();
+    remove_external_preview(app_state);
+
+    let root = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    let event_tx = app_state.events.event_tx.clone();
+
+    let mut scan_id = Uuid::new_v4();
+    let mut cancel = Arc::new(AtomicBool::new(false));
+    start_scan(root.clone(), scan_id, cancel.clone(), event_tx.clone());
+
+    let mut cursor: Option<DiskUsageCursor> = None;
+    let mut progress = 0;
+    let mut deleted = false;
+    // entries dropped from the tree while being deleted in the background
+    let mut pending_deletes = Vec::new();
+    let mut exit = false;
+
+    while !exit {
+        let height = backend
+            .terminal_ref()
+            .size()
+            .map_or(0, |s| s.height.saturating_sub(2) as usize);
+        if let Some(cursor) = cursor.as_mut() {
+            cursor.scroll(height);
+        }
+        let node = cursor.as_ref().map(|c| (c.node(), c.index, c.skip));
+        let path = match cursor.as_ref() {
+            Some(c) => root.join(c.dir_path()),
+            None => root.clone(),
+        };
+        backend.render(TuiDiskUsageView::new(app_state, &path, node, progress));
+
+        let event = match app_state.poll_event() {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+        let commands = match event {
+            AppEvent::DiskUsageProgress { id, files } if id == scan_id => {
+                progress = files;
+                continue;
+            }
+            AppEvent::DiskUsageScanned { id, tree } if id == scan_id => {
+                cursor = Some(DiskUsageCursor::new(*tree));
+                continue;
+            }
+            AppEvent::IoTaskResult(res) => {
+                let failed = res.is_err();
+                process_event::process_noninteractive(AppEvent::IoTaskResult(res), app_state);
+                pending_deletes.retain(|path: &PathBuf| path.symlink_metadata().is_ok());
+                if !failed || pending_deletes.is_empty() {
+                    continue;
+                }
+                // an entry dropped from the tree is still there, so the totals are off
+                pending_deletes.clear();
+                vec![Command::ReloadDirList]
+            }
+            AppEvent::TerminalEvent(key) => {
+                if app_state
+                    .state
+                    .message_queue_ref()
+                    .current_message()
+                    .is_some()
+                {
+                    app_state.state.message_queue_mut().pop_front();
+                }
+                let commands = match keymap_t.default_view.get(&key) {
+                    Some(CommandKeybind::SimpleKeybind { commands, .. }) => Some(commands.clone()),
+                    Some(CommandKeybind::CompositeKeybind(m)) => {
+                        process_event::poll_event_until_simple_keybind(app_state, backend, m)
+                            .cloned()
+                    }
+                    None => None,
+                };
+                app_state.flush_event();
+                commands.unwrap_or_default()
+            }
+            event => {
+                process_event::process_noninteractive(event, app_state);
+                continue;
+            }
+        };
+
+        for command in commands {
+            match command {
+                Command::Escape | Command::Quit(_) | Command::CloseTab | Command::DiskUsage => {
+                    exit = true
+                }
+                Command::ReloadDirList => {
+                    cancel.store(true, Ordering::Relaxed);
+                    cancel = Arc::new(AtomicBool::new(false));
+                    scan_id = Uuid::new_v4();
+                    cursor = None;
+                    progress = 0;
+                    start_scan(root.clone(), scan_id, cancel.clone(), event_tx.clone());
+                }
+                _ => {}
+            }
+            let Some(cursor) = cursor.as_mut() else {
+                continue;
+            };
+            let page = |f: f64| (height as f64 * f) as isize;
+            match command {
+                Command::CursorMoveUp { offset } => cursor.move_by(-(offset as isize)),
+                Command::CursorMoveDown { offset } => cursor.move_by(offset as isize),
+                Command::CursorMovePageUp(f) => cursor.move_by(-page(f)),
+                Command::CursorMovePageDown(f) => cursor.move_by(page(f)),
+                Command::CursorMoveHome => cursor.index = 0,
+                Command::CursorMoveEnd => cursor.index = cursor.len().saturating_sub(1),
+                Command::OpenFile => cursor.enter(),
+                Command::ParentDirectory => cursor.leave(),
+                Command::DeleteFiles {
+                    background,
+                    permanently,
+                    noconfirm,
+                } => match delete_curr(
+                    app_state,
+                    backend,
+                    &root,
+                    cursor,
+                    &mut pending_deletes,
+                    background,
+                    permanently,
+                    noconfirm,
+                ) {
+                    Ok(true) => deleted = true,
+                    Ok(false) => {}
+                    Err(e) => app_state
+                        .state
+                        .message_queue_mut()
+                        .push_error(e.to_string()),
+                },
+                _ => {}
+            }
+        }
+    }
+    cancel.store(true, Ordering::Relaxed);
+
+    if deleted {
+        for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+            tab.history_mut().depreciate_all_entries();
+        }
+        reload::soft_reload_curr_tab(app_state)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,90 @@
+use std::time::SystemTime;
+
+use crate::commands::case_sensitivity::SetType;
+use crate::error::AppResult;
+use crate::fs::{virtual_dirlist, FindFilters, FindQuery};
+use crate::types::state::{AppState, MatchState};
+
+/// Implements `find`: searches the tree below the current directory in the background for
+/// entries whose name matches `pattern` (a string, glob or regex depending on `pattern_type`)
+/// and `filters`. Results stream into a virtual listing shown in place of the current
+/// directory's. An empty pattern matches every name.
+pub fn find(
+    app_state: &mut AppState,
+    pattern: &str,
+    pattern_type: SetType,
+    filters: FindFilters,
+) -> AppResult {
+    let search_options = &app_state.config.search_options;
+    let pattern = match pattern_type {
+        _ if pattern.is_empty() => MatchState::None,
+        SetType::Glob => MatchState::new_glob(pattern, search_options.glob_case_sensitivity)?,
+        SetType::Regex => MatchState::new_regex(pattern, search_options.regex_case_sensitivity)?,
+        SetType::String | SetType::Fzf => {
+            MatchState::new_string(pattern, search_options.string_case_sensitivity)
+        }
+    };
+
+    let query = FindQuery { pattern, filters };
+    start_find(app_state, move |e, now| query.is_match(e, now))
+}
+
+/// Starts a background search of the tree below the current directory for entries matching
+/// `is_match`, whose results stream into a virtual listing shown in place of the current
+/// directory's.
+pub fn start_find<F>(app_state: &mut AppState, is_match: F) -> AppResult
+where
+    F: Fn(&walkdir::DirEntry, SystemTime) -> bool + Send + 'static,
+{
+    let root = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    let display_options = &app_state.config.display_options;
+    let tab_options = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .option_ref()
+        .clone();
+    let mut list = virtual_dirlist(
+        root.clone(),
+        Vec::new(),
+        None,
+        display_options,
+        &tab_options,
+    )?;
+
+    let id = app_state
+        .state
+        .find_state_mut()
+        .start(root, is_match, display_options, &tab_options);
+    list.load_id = Some(id);
+    app_state.state.tab_state_mut().curr_tab_mut().virtual_list = Some(list);
+    Ok(())
+}
+
+/// Implements `find_cancel`: stops the running `find`, keeping the results found so far.
+pub fn find_cancel(app_state: &mut AppState) -> AppResult {
+    let Some(id) = app_state.state.find_state_mut().cancel() else {
+        return Ok(());
+    };
+    let mut found = None;
+    for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+        if let Some(list) = tab.virtual_list.as_mut() {
+            if list.load_id == Some(id) {
+                list.load_id = None;
+                found = Some(list.len());
+            }
+        }
+    }
+    if let Some(found) = found {
+        app_state
+            .state
+            .message_queue_mut()
+            .push_info(format!("find cancelled, {} found", found));
+    }
+    Ok(())
+}
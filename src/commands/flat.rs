@@ -1,16 +1,23 @@
-use crate::error::AppResult;
+use crate::error::{AppError, AppErrorKind, AppResult};
 use crate::types::state::AppState;
 
-use super::reload;
+use super::{dir_settings, reload};
 
 /// Implements `flat`: sets how many levels deep the current directory listing is flattened.
 pub fn flatten(app_state: &mut AppState, depth: usize) -> AppResult {
+    let depth = u8::try_from(depth).map_err(|_| {
+        AppError::new(
+            AppErrorKind::InvalidParameters,
+            format!("flat: depth must be at most {}", u8::MAX),
+        )
+    })?;
+    dir_settings::remember(app_state, |settings| settings.depth = Some(depth))?;
     let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
     let path = curr_tab.get_cwd().to_path_buf();
     curr_tab
         .option_mut()
         .dirlist_options_mut(&path)
-        .set_depth(depth as u8);
+        .set_depth(depth);
 
     if let Some(list) = curr_tab.curr_list_mut() {
         list.depreciate();
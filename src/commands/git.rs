@@ -0,0 +1,164 @@
+use std::path::{Path, PathBuf};
+use std::process::{Command, Output, Stdio};
+
+use ratatui::termion::event::Key;
+
+use crate::commands::change_directory;
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::git;
+use crate::preview::preview_file::{FilePreview, PreviewFileState};
+use crate::types::state::AppState;
+use crate::ui::widgets::TuiPrompt;
+use crate::ui::AppBackend;
+
+use super::sub_process::current_files;
+
+/// Returns the current directory and the selected entries (or the current entry, if none
+/// selected), erroring if there are none.
+fn current_paths(app_state: &AppState) -> AppResult<(PathBuf, Vec<PathBuf>)> {
+    let cwd = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    let paths: Vec<PathBuf> = current_files(app_state)
+        .into_iter()
+        .map(|(_, path)| path.to_path_buf())
+        .collect();
+    if paths.is_empty() {
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            "No files selected".to_string(),
+        ));
+    }
+    Ok((cwd, paths))
+}
+
+/// Runs `git` in `dir` with `args` followed by `paths`.
+fn git_command(dir: &Path, args: &[&str], paths: &[PathBuf]) -> std::io::Result<Output> {
+    Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .arg("--")
+        .args(paths)
+        .stdin(Stdio::null())
+        .output()
+}
+
+/// Runs a git command that changes the state of `paths`, then reports the result through the
+/// message queue and refreshes the repository's status.
+fn run_git_action(
+    app_state: &mut AppState,
+    args: &[&str],
+    done: &str,
+    cwd: &Path,
+    paths: &[PathBuf],
+) -> AppResult {
+    let output = git_command(cwd, args, paths)?;
+    let message_queue = app_state.state.message_queue_mut();
+    if output.status.success() {
+        message_queue.push_success(format!("{} {} files", done, paths.len()));
+    } else {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        message_queue.push_error(stderr.trim_end().to_string());
+    }
+    app_state.state.git_state_mut().invalidate(cwd);
+    Ok(())
+}
+
+/// Implements `git_stage`: adds the selected entries to the index.
+pub fn git_stage(app_state: &mut AppState) -> AppResult {
+    let (cwd, paths) = current_paths(app_state)?;
+    run_git_action(app_state, &["add"], "Staged", &cwd, &paths)
+}
+
+/// Implements `git_unstage`: resets the index entries of the selected entries to `HEAD`,
+/// keeping their changes in the work tree.
+pub fn git_unstage(app_state: &mut AppState) -> AppResult {
+    let (cwd, paths) = current_paths(app_state)?;
+    run_git_action(app_state, &["reset", "-q"], "Unstaged", &cwd, &paths)
+}
+
+/// Implements `git_restore`: discards the unstaged changes of the selected entries, prompting
+/// first unless `noconfirm`.
+pub fn git_restore(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    noconfirm: bool,
+) -> AppResult {
+    let (cwd, paths) = current_paths(app_state)?;
+    if !noconfirm {
+        let prompt_str = format!("Discard changes to {} files? (y/N)", paths.len());
+        let mut prompt = TuiPrompt::new(&prompt_str);
+        if prompt.get_key(app_state, backend) != Key::Char('y') {
+            return Ok(());
+        }
+    }
+    run_git_action(app_state, &["restore"], "Restored", &cwd, &paths)
+}
+
+/// Implements `git_diff`: shows the changes of the selected entries against `HEAD` in the
+/// preview pane of the current entry. Running it again on the same entry brings back its
+/// regular preview.
+pub fn git_diff(app_state: &mut AppState) -> AppResult {
+    let (cwd, paths) = current_paths(app_state)?;
+    let Some(entry_path) = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+        .and_then(|list| list.curr_entry_ref())
+        .map(|entry| entry.file_path_buf())
+    else {
+        return Ok(());
+    };
+
+    let preview_state = app_state.state.preview_state_mut();
+    if let Some(prev) = preview_state.git_diff.take() {
+        preview_state.previews_mut().remove(&prev);
+        if prev == entry_path {
+            return Ok(());
+        }
+    }
+
+    let output = git_command(&cwd, &["diff", "--no-color", "HEAD"], &paths)?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        app_state
+            .state
+            .message_queue_mut()
+            .push_error(stderr.trim_end().to_string());
+        return Ok(());
+    }
+    if output.stdout.is_empty() {
+        app_state
+            .state
+            .message_queue_mut()
+            .push_info("No changes".to_string());
+        return Ok(());
+    }
+
+    let preview_state = app_state.state.preview_state_mut();
+    preview_state.set_image_preview(None);
+    preview_state.previews_mut().insert(
+        entry_path.clone(),
+        PreviewFileState::Success(FilePreview::from(output)),
+    );
+    preview_state.git_diff = Some(entry_path);
+    Ok(())
+}
+
+/// Implements `cd_git_root`: changes to the top level of the repository containing the
+/// current directory.
+pub fn cd_git_root(app_state: &mut AppState) -> AppResult {
+    let cwd = app_state.state.tab_state_ref().curr_tab_ref().get_cwd();
+    match git::toplevel(cwd)? {
+        Some(root) => change_directory::change_directory(app_state, &root),
+        None => Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            "Not inside a git repository".to_string(),
+        )),
+    }
+}
@@ -0,0 +1,281 @@
+use std::path::PathBuf;
+use std::process;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use regex::Regex;
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::error::AppResult;
+use crate::fs::{grep_file, read_lines, GrepMatch, IgnoreMatcher};
+use crate::run::process_event;
+use crate::types::command::Command;
+use crate::types::event::{AppEvent, AppEventSender};
+use crate::types::keybind::CommandKeybind;
+use crate::types::keymap::AppKeyMapping;
+use crate::types::option::display::DisplayOption;
+use crate::types::state::remove_external_preview;
+use crate::types::state::{AppState, MatchState};
+use crate::ui::views::TuiGrepView;
+use crate::ui::AppBackend;
+
+use super::sub_process::shell_quote;
+
+const ENV_EDITOR: &str = "EDITOR";
+/// How often matches found are sent to the main thread.
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// Searches the files below `root` for `regex` on a background thread, sending the matches in
+/// batches as [`AppEvent::GrepResults`] tagged with `id`, then an [`AppEvent::GrepDone`].
+/// Binary files, ignored files and git's own data are skipped, and so are hidden entries unless
+/// `display_options` show them.
+fn start_grep(
+    root: PathBuf,
+    regex: Regex,
+    display_options: DisplayOption,
+    id: Uuid,
+    cancel: Arc<AtomicBool>,
+    event_tx: AppEventSender,
+) {
+    thread::spawn(move || {
+        let ignore = IgnoreMatcher::new(&root);
+        let walker = WalkDir::new(&root)
+            .min_depth(1)
+            .into_iter()
+            .filter_entry(|e| {
+                let name = e.file_name().to_string_lossy();
+                name != ".git"
+                    && (display_options.show_hidden || !display_options.is_hidden(&name))
+                    && !ignore.is_ignored(e.path(), e.file_type().is_dir())
+            });
+
+        let mut matches = Vec::new();
+        let mut files = 0;
+        let mut last_sent = Instant::now();
+        for entry in walker.filter_map(|e| e.ok()) {
+            if cancel.load(Ordering::Relaxed) {
+                return;
+            }
+            if entry.file_type().is_file() {
+                files += 1;
+                let _ = grep_file(entry.path(), &regex, &mut matches);
+            }
+            if last_sent.elapsed() >= BATCH_INTERVAL {
+                let matches = std::mem::take(&mut matches);
+                if event_tx
+                    .send(AppEvent::GrepResults { id, matches, files })
+                    .is_err()
+                {
+                    return;
+                }
+                last_sent = Instant::now();
+            }
+        }
+        let _ = event_tx.send(AppEvent::GrepResults { id, matches, files });
+        let _ = event_tx.send(AppEvent::GrepDone { id, files });
+    });
+}
+
+/// Opens the file of `m` at its line with `$EDITOR`, giving it the terminal meanwhile.
+fn open_match(app_state: &mut AppState, backend: &mut AppBackend, m: &GrepMatch) -> AppResult {
+    let editor = std::env::var(ENV_EDITOR)?;
+    let command_line = format!(
+        "{} +{} {}",
+        editor,
+        m.line,
+        shell_quote(&m.path.to_string_lossy())
+    );
+
+    backend.terminal_drop();
+    let status = process::Command::new(&app_state.config.shell)
+        .arg("-c")
+        .arg(command_line)
+        .status();
+    backend.terminal_restore()?;
+    status?;
+    Ok(())
+}
+
+/// Implements `grep`: searches the contents of the files below the current directory for the
+/// regex `pattern` in the background, and runs a nested event loop for browsing the matches
+/// with the default keymap as they come in. `open` opens the highlighted match in `$EDITOR`,
+/// `escape` stops a running search, and then leaves; `reload_dirlist` searches again.
+pub fn grep(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    keymap_t: &AppKeyMapping,
+    pattern: &str,
+) -> AppResult {
+    let case_sensitivity = app_state.config.search_options.regex_case_sensitivity;
+    let regex = MatchState::build_regex(pattern, case_sensitivity)?;
+
+    app_state.flush_event();
+    remove_external_preview(app_state);
+
+    let root = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    let display_options = app_state.config.display_options.clone();
+    let event_tx = app_state.events.event_tx.clone();
+
+    let mut grep_id = Uuid::new_v4();
+    let mut cancel = Arc::new(AtomicBool::new(false));
+    start_grep(
+        root.clone(),
+        regex.clone(),
+        display_options.clone(),
+        grep_id,
+        cancel.clone(),
+        event_tx.clone(),
+    );
+
+    let mut matches: Vec<GrepMatch> = Vec::new();
+    let mut index = 0;
+    let mut skip = 0;
+    let mut files = 0;
+    let mut searching = true;
+    // the lines around the highlighted match, and the match and height they were read for
+    let mut context = Vec::new();
+    let mut context_key = None;
+    let mut exit = false;
+
+    while !exit {
+        let height = backend
+            .terminal_ref()
+            .size()
+            .map_or(0, |s| s.height.saturating_sub(2) as usize);
+        if index < skip {
+            skip = index;
+        } else if height > 0 && index >= skip + height {
+            skip = index + 1 - height;
+        }
+        if let Some(m) = matches.get(index) {
+            let key = Some((m.path.clone(), m.line, height));
+            if context_key != key {
+                let first = m.line.saturating_sub(height / 2).max(1);
+                let last = first + height.saturating_sub(1);
+                context = read_lines(&m.path, first, last).unwrap_or_default();
+                context_key = key;
+            }
+        }
+
+        backend.render(TuiGrepView::new(
+            app_state, &root, &regex, &matches, index, skip, &context, files, !searching,
+        ));
+
+        let event = match app_state.poll_event() {
+            Ok(event) => event,
+            Err(_) => break,
+        };
+        let commands = match event {
+            AppEvent::GrepResults {
+                id,
+                matches: found,
+                files: searched,
+            } if id == grep_id => {
+                matches.extend(found);
+                files = searched;
+                continue;
+            }
+            AppEvent::GrepDone {
+                id,
+                files: searched,
+            } if id == grep_id => {
+                files = searched;
+                searching = false;
+                continue;
+            }
+            AppEvent::TerminalEvent(key) => {
+                if app_state
+                    .state
+                    .message_queue_ref()
+                    .current_message()
+                    .is_some()
+                {
+                    app_state.state.message_queue_mut().pop_front();
+                }
+                let commands = match keymap_t.default_view.get(&key) {
+                    Some(CommandKeybind::SimpleKeybind { commands, .. }) => Some(commands.clone()),
+                    Some(CommandKeybind::CompositeKeybind(m)) => {
+                        process_event::poll_event_until_simple_keybind(app_state, backend, m)
+                            .cloned()
+                    }
+                    None => None,
+                };
+                app_state.flush_event();
+                commands.unwrap_or_default()
+            }
+            event => {
+                process_event::process_noninteractive(event, app_state);
+                continue;
+            }
+        };
+
+        let page = |f: f64| (height as f64 * f) as isize;
+        let last = matches.len().saturating_sub(1) as isize;
+        let move_by = |index: usize, offset: isize| (index as isize + offset).clamp(0, last);
+        for command in commands {
+            match command {
+                Command::Escape if searching => {
+                    cancel.store(true, Ordering::Relaxed);
+                    searching = false;
+                    app_state
+                        .state
+                        .message_queue_mut()
+                        .push_info(format!("grep cancelled, {} matches", matches.len()));
+                }
+                Command::Escape | Command::Quit(_) | Command::CloseTab | Command::Grep { .. } => {
+                    exit = true
+                }
+                Command::ReloadDirList => {
+                    cancel.store(true, Ordering::Relaxed);
+                    cancel = Arc::new(AtomicBool::new(false));
+                    grep_id = Uuid::new_v4();
+                    matches.clear();
+                    index = 0;
+                    files = 0;
+                    searching = true;
+                    start_grep(
+                        root.clone(),
+                        regex.clone(),
+                        display_options.clone(),
+                        grep_id,
+                        cancel.clone(),
+                        event_tx.clone(),
+                    );
+                }
+                Command::CursorMoveUp { offset } => {
+                    index = move_by(index, -(offset as isize)) as usize
+                }
+                Command::CursorMoveDown { offset } => {
+                    index = move_by(index, offset as isize) as usize
+                }
+                Command::CursorMovePageUp(f) => index = move_by(index, -page(f)) as usize,
+                Command::CursorMovePageDown(f) => index = move_by(index, page(f)) as usize,
+                Command::CursorMoveHome => index = 0,
+                Command::CursorMoveEnd => index = last as usize,
+                Command::OpenFile => {
+                    if let Some(m) = matches.get(index).cloned() {
+                        // the file may have been edited
+                        context_key = None;
+                        if let Err(e) = open_match(app_state, backend, &m) {
+                            app_state
+                                .state
+                                .message_queue_mut()
+                                .push_error(e.to_string());
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+    }
+    cancel.store(true, Ordering::Relaxed);
+    Ok(())
+}
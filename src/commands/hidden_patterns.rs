@@ -0,0 +1,49 @@
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::history::DirectoryHistory;
+use crate::types::option::hidden::HiddenPattern;
+use crate::types::state::AppState;
+
+use super::reload;
+
+/// Invalidates all cached listings and reloads the current tab with the changed patterns.
+fn apply(app_state: &mut AppState) -> AppResult {
+    for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+        tab.history_mut().depreciate_all_entries();
+        if let Some(s) = tab.curr_list_mut() {
+            s.depreciate();
+        }
+    }
+    reload::soft_reload_curr_tab(app_state)?;
+    Ok(())
+}
+
+/// Implements `hide_pattern`: hides the files whose names match `pattern`, a glob or a regex
+/// prefixed with `regex:`, until `toggle_hidden` reveals them.
+pub fn hide_pattern(app_state: &mut AppState, pattern: &str) -> AppResult {
+    let display_options = &mut app_state.config.display_options;
+    if display_options
+        .hidden_patterns
+        .iter()
+        .any(|p| p.pattern == pattern)
+    {
+        return Ok(());
+    }
+    let pattern = HiddenPattern::new(pattern)?;
+    display_options.hidden_patterns.push(pattern);
+    apply(app_state)
+}
+
+/// Implements `unhide_pattern`: stops hiding the files matched by `pattern`, which must be
+/// written as it was added.
+pub fn unhide_pattern(app_state: &mut AppState, pattern: &str) -> AppResult {
+    let hidden_patterns = &mut app_state.config.display_options.hidden_patterns;
+    let len = hidden_patterns.len();
+    hidden_patterns.retain(|p| p.pattern != pattern);
+    if hidden_patterns.len() == len {
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            format!("Not a hidden pattern: {pattern}"),
+        ));
+    }
+    apply(app_state)
+}
@@ -1,5 +1,5 @@
-use super::reload;
-use crate::error::AppResult;
+use super::{dir_settings, reload};
+use crate::error::{AppError, AppErrorKind, AppResult};
 use crate::history::DirectoryHistory;
 use crate::types::option::line_mode::LineMode;
 use crate::types::state::AppState;
@@ -7,6 +7,18 @@ use crate::types::state::AppState;
 /// Implements `linemode`: sets which metadata columns are shown and invalidates cached listings
 /// so they re-render with the new columns.
 pub fn set_linemode(app_state: &mut AppState, linemode: LineMode) -> AppResult {
+    let display_options = &app_state.config.display_options;
+    if let Some(name) = linemode
+        .custom_columns()
+        .find(|name| display_options.custom_column(name).is_none())
+    {
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            format!("Linemode '{}' unknown.", name),
+        ));
+    }
+    let linemode_str = linemode.as_string();
+    dir_settings::remember(app_state, |settings| settings.linemode = Some(linemode_str))?;
     let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
     curr_tab.option_mut().linemode = linemode;
     curr_tab.history_mut().depreciate_all_entries();
@@ -1,6 +1,7 @@
 //! Implementations of every built-in [`Command`](crate::types::command::Command) variant, one
 //! module per command family, dispatched from `types::command::impl_appexecute`.
 
+pub mod archive;
 pub mod bookmark;
 pub mod bulk_rename;
 pub mod case_sensitivity;
@@ -10,18 +11,25 @@ pub mod cumulative_size;
 pub mod cursor_move;
 pub mod custom_search;
 pub mod delete_files;
+pub mod dir_settings;
+pub mod disk_usage;
 pub mod escape;
 pub mod file_ops;
 pub mod filter;
 pub mod filter_glob;
 pub mod filter_regex;
 pub mod filter_string;
+pub mod find;
 pub mod flat;
 pub mod fzf;
+pub mod git;
+pub mod grep;
+pub mod hidden_patterns;
 pub mod history;
 pub mod line_nums;
 pub mod linemode;
 pub mod new_directory;
+pub mod new_from_template;
 pub mod numbered_command;
 pub mod open_file;
 pub mod parent_cursor_move;
@@ -41,15 +49,21 @@ pub mod select_regex;
 pub mod select_string;
 pub mod set_display_mode;
 pub mod set_mode;
+pub mod set_time;
 pub mod show_help;
 pub mod show_hidden;
+pub mod show_ignored;
 pub mod show_tasks;
 pub mod signal;
 pub mod sort;
 pub mod stdout;
 pub mod sub_process;
 pub mod subdir_fzf;
+pub mod symlink;
 pub mod tab_ops;
+pub mod tags;
 pub mod touch_file;
+pub mod tree;
 pub mod uimodes;
+pub mod virtual_list;
 pub mod zoxide;
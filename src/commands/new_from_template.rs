@@ -0,0 +1,270 @@
+use std::collections::HashSet;
+use std::fs;
+use std::io::{self, Write};
+use std::path;
+
+use crate::commands::cursor_move;
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::types::state::AppState;
+use crate::ui::views::{DummyListener, TuiTextField};
+use crate::ui::AppBackend;
+use crate::{CONFIG_HIERARCHY, USERNAME};
+
+use super::tab_ops;
+
+const TEMPLATES_DIR: &str = "templates";
+const PREFIX: &str = "new_from_template ";
+
+/// Lists the files in every `templates/` directory of the config hierarchy, sorted by name.
+/// A template in a higher-priority config directory hides one of the same name further down.
+fn list_templates() -> Vec<path::PathBuf> {
+    let mut seen = HashSet::new();
+    let mut templates = Vec::new();
+    for dir in CONFIG_HIERARCHY.iter() {
+        let Ok(entries) = fs::read_dir(dir.join(TEMPLATES_DIR)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            let path = entry.path();
+            if path.is_file() && seen.insert(entry.file_name()) {
+                templates.push(path);
+            }
+        }
+    }
+    templates.sort_by(|a, b| a.file_name().cmp(&b.file_name()));
+    templates
+}
+
+/// Looks up a template by its file name, or failing that by its index in `templates`.
+fn find_template<'a>(templates: &'a [path::PathBuf], name: &str) -> AppResult<&'a path::Path> {
+    let template = templates
+        .iter()
+        .find(|p| p.file_name().is_some_and(|s| s == name))
+        .or_else(|| name.parse::<usize>().ok().and_then(|i| templates.get(i)));
+    template.map(|p| p.as_path()).ok_or_else(|| {
+        AppError::new(
+            AppErrorKind::InvalidParameters,
+            format!("Template '{name}' does not exist"),
+        )
+    })
+}
+
+fn prompt(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    prefix: &str,
+    suffix: &str,
+    menu_options: &[String],
+) -> Option<Vec<String>> {
+    app_state.flush_event();
+    let mut listener = DummyListener {};
+    let user_input = TuiTextField::default()
+        .prompt(":")
+        .prefix(prefix)
+        .suffix(suffix)
+        .menu_items(menu_options.iter().map(|s| s.as_str()))
+        .get_input(app_state, backend, &mut listener)?;
+    let args = user_input.strip_prefix(PREFIX)?;
+    shell_words::split(args).ok()
+}
+
+/// Removes any `--raw` flags from prompted `args`, returning whether there were some.
+fn take_raw_flag(args: &mut Vec<String>) -> bool {
+    let len = args.len();
+    args.retain(|arg| arg != "--raw");
+    args.len() != len
+}
+
+/// Replaces `{{date}}`, `{{time}}`, `{{year}}`, `{{user}}`, `{{dirname}}`, `{{filename}}` and
+/// `{{basename}}` in a template's contents. `\{{` stands for a literal `{{`, and unknown
+/// placeholders are left as they are.
+fn substitute_placeholders(content: &str, dest: &path::Path) -> String {
+    let now = chrono::Local::now();
+    let name_of = |p: Option<&std::ffi::OsStr>| {
+        p.map(|s| s.to_string_lossy().to_string())
+            .unwrap_or_default()
+    };
+    let value_of = |placeholder: &str| match placeholder {
+        "date" => Some(now.format("%Y-%m-%d").to_string()),
+        "time" => Some(now.format("%H:%M:%S").to_string()),
+        "year" => Some(now.format("%Y").to_string()),
+        "user" => Some(USERNAME.to_string()),
+        "dirname" => Some(name_of(dest.parent().and_then(|p| p.file_name()))),
+        "filename" => Some(name_of(dest.file_name())),
+        "basename" => Some(name_of(dest.file_stem())),
+        _ => None,
+    };
+
+    let mut result = String::with_capacity(content.len());
+    let mut rest = content;
+    while let Some(start) = rest.find("{{") {
+        if rest[..start].ends_with('\\') {
+            result.push_str(&rest[..start - 1]);
+            result.push_str("{{");
+            rest = &rest[start + 2..];
+            continue;
+        }
+        result.push_str(&rest[..start]);
+        let after = &rest[start + 2..];
+        match after
+            .find("}}")
+            .and_then(|end| Some((end, value_of(&after[..end])?)))
+        {
+            Some((end, value)) => {
+                result.push_str(&value);
+                rest = &after[end + 2..];
+            }
+            None => {
+                result.push_str("{{");
+                rest = after;
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+/// Creates `dest` from `template`, keeping the template's permissions. Text templates have
+/// their placeholders substituted unless `raw` is set.
+fn create_from_template(template: &path::Path, dest: &path::Path, raw: bool) -> io::Result<()> {
+    let content = fs::read(template)?;
+    let content = match String::from_utf8(content) {
+        Ok(s) if !raw => substitute_placeholders(&s, dest).into_bytes(),
+        Ok(s) => s.into_bytes(),
+        Err(e) => e.into_bytes(),
+    };
+
+    let mut file = fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(dest)?;
+    file.write_all(&content)?;
+    file.set_permissions(fs::metadata(template)?.permissions())?;
+    Ok(())
+}
+
+/// Implements `new_from_template`: creates a new file in the current directory from one of the
+/// templates in the config hierarchy, prompting for the template and file name if not given.
+pub fn new_from_template(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    template: Option<&str>,
+    file_name: Option<&path::Path>,
+    mut raw: bool,
+) -> AppResult {
+    let templates = list_templates();
+    if templates.is_empty() {
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            format!("No templates found in any '{TEMPLATES_DIR}' config directory"),
+        ));
+    }
+
+    let mut file_name = file_name.map(|p| p.to_path_buf());
+    let template = match template {
+        Some(name) => find_template(&templates, name)?,
+        None => {
+            let menu_options: Vec<String> = templates
+                .iter()
+                .enumerate()
+                .map(|(i, p)| format!("  {} | {}", i, p.file_name().unwrap().to_string_lossy()))
+                .collect();
+            let mut args = match prompt(app_state, backend, PREFIX, "", &menu_options) {
+                Some(args) => args,
+                None => return Ok(()),
+            };
+            raw |= take_raw_flag(&mut args);
+            if args.is_empty() {
+                return Ok(());
+            }
+            if let Some(name) = args.get(1) {
+                file_name = Some(path::PathBuf::from(name));
+            }
+            find_template(&templates, &args[0])?
+        }
+    };
+    let template_name = template.file_name().unwrap().to_string_lossy().to_string();
+
+    let file_name = match file_name {
+        Some(file_name) => file_name,
+        None => {
+            let prefix = format!("{}{} ", PREFIX, shell_words::quote(&template_name));
+            let mut args = match prompt(app_state, backend, &prefix, &template_name, &[]) {
+                Some(args) => args,
+                None => return Ok(()),
+            };
+            raw |= take_raw_flag(&mut args);
+            match args.get(1) {
+                Some(name) => path::PathBuf::from(name),
+                None => return Ok(()),
+            }
+        }
+    };
+
+    let curr_path = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    let dest = curr_path.join(&file_name);
+    create_from_template(template, &dest, raw)?;
+
+    tab_ops::reload_all_tabs(app_state, curr_path.as_path())?;
+    if app_state.config.focus_on_create {
+        cursor_move::to_path(app_state, &file_name)?;
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_substitute_placeholders() {
+        let dest = path::Path::new("/home/user/project/main.rs");
+        let year = chrono::Local::now().format("%Y").to_string();
+        let subst = |s: &str| substitute_placeholders(s, dest);
+
+        assert_eq!(subst("{{dirname}}"), "project");
+        assert_eq!(subst("{{filename}}"), "main.rs");
+        assert_eq!(subst("{{basename}}"), "main");
+        assert_eq!(subst("{{user}}"), USERNAME.as_str());
+        assert_eq!(subst("(c) {{year}}"), format!("(c) {year}"));
+        assert!(subst("{{date}}").starts_with(&year));
+        assert_eq!(subst("{{date}}").len(), "2024-01-31".len());
+        assert_eq!(subst("{{time}}").len(), "12:00:00".len());
+
+        assert_eq!(
+            subst("mod {{basename}}; // {{filename}}"),
+            "mod main; // main.rs"
+        );
+        assert_eq!(subst("\\{{basename}} {{basename}}"), "{{basename}} main");
+        assert_eq!(subst("{{unknown}} {{basename"), "{{unknown}} {{basename");
+    }
+
+    #[test]
+    fn test_substituted_values_are_not_expanded() {
+        let dest = path::Path::new("/tmp/{{user}}.txt");
+        assert_eq!(substitute_placeholders("{{basename}}", dest), "{{user}}");
+    }
+
+    #[test]
+    fn test_find_template_prefers_names() {
+        let templates = [path::PathBuf::from("/t/1"), path::PathBuf::from("/t/a")];
+        assert_eq!(
+            find_template(&templates, "1").unwrap(),
+            path::Path::new("/t/1")
+        );
+        assert_eq!(
+            find_template(&templates, "a").unwrap(),
+            path::Path::new("/t/a")
+        );
+        assert_eq!(
+            find_template(&templates, "0").unwrap(),
+            path::Path::new("/t/1")
+        );
+        assert!(find_template(&templates, "2").is_err());
+    }
+}
@@ -1,8 +1,10 @@
+use std::ffi::OsString;
 use std::path;
 
 use crate::commands::{quit, reload};
 use crate::config::app::AppConfig;
 use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::fs::VfsPath;
 use crate::types::mimetype::ProgramEntry;
 use crate::types::state::AppState;
 use crate::ui::views::DummyListener;
@@ -171,7 +173,7 @@ pub fn open(app_state: &mut AppState, backend: &mut AppBackend) -> AppResult {
 
     match entry {
         None => (),
-        Some(entry) if entry.file_path().is_dir() => {
+        Some(entry) if VfsPath::resolve(entry.file_path()).is_dir() => {
             let path = entry.file_path().to_path_buf();
             change_directory::cd(path.as_path(), app_state, true)?;
             reload::soft_reload_curr_tab(app_state)?;
@@ -182,21 +184,25 @@ pub fn open(app_state: &mut AppState, backend: &mut AppBackend) -> AppResult {
             }
 
             let paths = curr_list.map_or_else(Vec::new, |s| s.iter_selected().cloned().collect());
-            let (path, files) = if paths.is_empty() {
-                (entry.file_path(), vec![entry.file_name()])
-            } else {
-                (
-                    paths.first().unwrap().file_path(),
-                    paths.iter().map(|e| e.file_name()).collect(),
-                )
-            };
-            let options = _get_options(path, &app_state.config);
+            let entries = if paths.is_empty() { vec![entry] } else { paths };
+            // entries of archives are opened as copies extracted from them
+            let files = entries
+                .iter()
+                .map(|e| match VfsPath::resolve(e.file_path()) {
+                    vfs_path if vfs_path.is_read_only() => {
+                        vfs_path.local_path().map(path::PathBuf::into_os_string)
+                    }
+                    _ => Ok(e.file_name().into()),
+                })
+                .collect::<std::io::Result<Vec<OsString>>>()?;
+            let path = VfsPath::resolve(entries[0].file_path()).local_path()?;
+            let options = _get_options(&path, &app_state.config);
             let option = options.iter().find(|option| option.program_exists());
 
             if let Some(option) = option {
                 _open_with_entry(app_state, backend, option, &files)?;
             } else if app_state.config.xdg_open {
-                _open_with_xdg(app_state, backend, path)?;
+                _open_with_xdg(app_state, backend, &path)?;
             } else {
                 _open_with_helper(app_state, backend, options, &files)?;
             }
@@ -1,6 +1,8 @@
 use std::path::PathBuf;
 
+use crate::commands::{dir_settings, reload};
 use crate::error::AppResult;
+use crate::fs::VfsPath;
 use crate::types::state::AppState;
 use crate::utils::cwd;
 
@@ -21,7 +23,7 @@ pub fn parent_cursor_move(app_state: &mut AppState, new_index: usize) -> AppResu
                     new_index = dir_len - 1;
                 }
                 let entry = &curr_list.contents[new_index];
-                if entry.file_path().is_dir() {
+                if VfsPath::resolve(entry.file_path()).is_dir() {
                     path = Some(entry.file_path().to_path_buf());
                     curr_list.set_index(Some(new_index), &ui_state, &display_options);
                 }
@@ -32,6 +34,9 @@ pub fn parent_cursor_move(app_state: &mut AppState, new_index: usize) -> AppResu
             curr_tab.set_cwd(path, true);
         }
     }
+    if path.is_some() && dir_settings::apply(app_state) {
+        reload::soft_reload_curr_tab(app_state)?;
+    }
     Ok(())
 }
 
@@ -1,26 +1,40 @@
 use crate::error::AppResult;
+use crate::fs::{virtual_dirlist, JoshutoDirList};
 use crate::history::{create_dirlist_with_history, DirectoryHistory};
+use crate::tab::TabDisplayOption;
+use crate::types::option::display::DisplayOption;
 use crate::types::state::AppState;
 
 use uuid::Uuid;
 
 /// Re-reads only the parent/current/child listings of tab `id` that are marked stale or have
-/// changed on disk.
+/// changed on disk. A virtual listing is rebuilt along with the listing of the directory it's
+/// shown in.
 pub fn soft_reload(app_state: &mut AppState, id: &Uuid) -> std::io::Result<()> {
     let mut dirlists = Vec::with_capacity(3);
+    let mut virtual_list = None;
     if let Some(curr_tab) = app_state.state.tab_state_ref().tab_ref(id) {
         let display_options = &app_state.config.display_options;
         let tab_options = app_state.state.tab_state_ref().curr_tab_ref().option_ref();
         let history = curr_tab.history_ref();
+        if let Some(list) = curr_tab.virtual_list.as_ref() {
+            let cwd_stale = history
+                .get(curr_tab.get_cwd())
+                .is_some_and(|cwd_list| cwd_list.need_update());
+            if list.need_update() || cwd_stale {
+                virtual_list = Some(reload_virtual(list, display_options, tab_options)?);
+            }
+        }
         for curr_list in [
             curr_tab.parent_list_ref(),
-            curr_tab.curr_list_ref(),
+            history.get(curr_tab.get_cwd()),
             curr_tab.child_list_ref(),
         ]
         .into_iter()
         .flatten()
         {
-            if curr_list.need_update() {
+            // listings still streaming in from a background load are left to finish
+            if curr_list.need_update() && curr_list.load_id.is_none() {
                 let new_dirlist = create_dirlist_with_history(
                     history,
                     curr_list.file_path(),
@@ -32,33 +46,55 @@ pub fn soft_reload(app_state: &mut AppState, id: &Uuid) -> std::io::Result<()> {
         }
     }
 
-    if let Some(history) = app_state
-        .state
-        .tab_state_mut()
-        .tab_mut(id)
-        .map(|t| t.history_mut())
-    {
-        history.insert_entries(dirlists);
+    if let Some(tab) = app_state.state.tab_state_mut().tab_mut(id) {
+        tab.history_mut().insert_entries(dirlists);
+        if virtual_list.is_some() {
+            tab.virtual_list = virtual_list;
+        }
     }
     Ok(())
 }
 
+/// Rebuilds the virtual listing `list` from the paths it was made of. A listing still being
+/// filled by `find` keeps receiving its results.
+fn reload_virtual(
+    list: &JoshutoDirList,
+    display_options: &DisplayOption,
+    tab_options: &TabDisplayOption,
+) -> std::io::Result<JoshutoDirList> {
+    let paths = list.virtual_paths.clone().unwrap_or_default();
+    let mut dirlist = virtual_dirlist(
+        list.file_path().to_path_buf(),
+        paths,
+        Some(list),
+        display_options,
+        tab_options,
+    )?;
+    dirlist.load_id = list.load_id;
+    Ok(dirlist)
+}
+
 /// Soft-reloads the currently active tab. See [`soft_reload`].
 pub fn soft_reload_curr_tab(app_state: &mut AppState) -> std::io::Result<()> {
     let curr_tab_id = app_state.state.tab_state_ref().curr_tab_id();
     soft_reload(app_state, &curr_tab_id)
 }
 
-/// Unconditionally re-reads the parent/current/child listings of tab `id` from disk.
+/// Unconditionally re-reads the parent/current/child listings of tab `id` from disk, and
+/// rebuilds its virtual listing, if any.
 pub fn reload(app_state: &mut AppState, id: &Uuid) -> std::io::Result<()> {
     let mut dirlists = Vec::with_capacity(3);
+    let mut virtual_list = None;
     if let Some(curr_tab) = app_state.state.tab_state_ref().tab_ref(id) {
         let display_options = &app_state.config.display_options;
         let tab_options = app_state.state.tab_state_ref().curr_tab_ref().option_ref();
         let history = curr_tab.history_ref();
+        if let Some(list) = curr_tab.virtual_list.as_ref() {
+            virtual_list = Some(reload_virtual(list, display_options, tab_options)?);
+        }
         for curr_list in [
             curr_tab.parent_list_ref(),
-            curr_tab.curr_list_ref(),
+            history.get(curr_tab.get_cwd()),
             curr_tab.child_list_ref(),
         ]
         .into_iter()
@@ -74,14 +110,13 @@ pub fn reload(app_state: &mut AppState, id: &Uuid) -> std::io::Result<()> {
         }
     }
 
-    if let Some(history) = app_state
-        .state
-        .tab_state_mut()
-        .tab_mut(id)
-        .map(|t| t.history_mut())
-    {
-        history.insert_entries(dirlists);
+    if let Some(tab) = app_state.state.tab_state_mut().tab_mut(id) {
+        tab.history_mut().insert_entries(dirlists);
+        if virtual_list.is_some() {
+            tab.virtual_list = virtual_list;
+        }
     }
+    app_state.state.git_state_mut().invalidate_all();
     app_state
         .state
         .message_queue_mut()
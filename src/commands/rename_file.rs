@@ -5,8 +5,28 @@ use crate::history::create_dirlist_with_history;
 use crate::types::keymap::AppKeyMapping;
 use crate::types::state::AppState;
 use crate::ui::AppBackend;
+use crate::utils::fs::rename_tags;
 
-use super::command_line;
+use super::{command_line, reload};
+
+/// Renames `src` to `dest` on disk, failing if `dest` already exists, and returns the new path.
+/// A relative `dest` is taken as relative to the directory of `src` (or `cwd`, for the root),
+/// whatever the process's directory is: the directory shown for a flat listing, and the entry's
+/// own one for an entry of an expanded tree or a virtual listing.
+fn rename_entry(
+    src: &path::Path,
+    dest: &path::Path,
+    cwd: &path::Path,
+) -> std::io::Result<path::PathBuf> {
+    let new_path = src.parent().unwrap_or(cwd).join(dest);
+    if new_path.exists() {
+        let err = std::io::Error::new(std::io::ErrorKind::AlreadyExists, "Filename already exists");
+        return Err(err);
+    }
+    std::fs::rename(src, &new_path)?;
+    rename_tags(src, &new_path);
+    Ok(new_path)
+}
 
 /// Renames `src` to `dest` on disk (failing if `dest` already exists) and refreshes the current
 /// directory's cached listing.
@@ -15,12 +35,19 @@ pub fn _rename_file(
     src: &path::Path,
     dest: &path::Path,
 ) -> std::io::Result<()> {
-    let new_path = dest;
-    if new_path.exists() {
-        let err = std::io::Error::new(std::io::ErrorKind::AlreadyExists, "Filename already exists");
-        return Err(err);
+    let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
+    let new_path = rename_entry(src, dest, curr_tab.get_cwd())?;
+
+    // a virtual listing keeps showing the entry under its new name
+    if let Some(list) = curr_tab.virtual_list.as_mut() {
+        for path in list.virtual_paths.iter_mut().flatten() {
+            if path == src {
+                *path = new_path.clone();
+            }
+        }
+        list.depreciate();
+        return reload::soft_reload_curr_tab(app_state);
     }
-    std::fs::rename(src, dest)?;
 
     let curr_tab = app_state.state.tab_state_ref().curr_tab_ref();
 
@@ -131,3 +158,32 @@ pub fn rename_file_keep_ext(
     }
     Ok(())
 }
+
+#[cfg(test)]
+mod tests {
+    use std::fs;
+
+    use super::*;
+
+    #[test]
+    fn test_rename_nested_entry() {
+        let dir = std::env::temp_dir().join(format!("joshuto-rename-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("sub")).unwrap();
+        fs::write(dir.join("a.txt"), "top").unwrap();
+        fs::write(dir.join("sub/a.txt"), "nested").unwrap();
+
+        let src = dir.join("sub/a.txt");
+        let new_path = rename_entry(&src, path::Path::new("a.txt.bak"), &dir).unwrap();
+        assert_eq!(new_path, dir.join("sub/a.txt.bak"));
+        assert_eq!(fs::read_to_string(&new_path).unwrap(), "nested");
+        assert!(!dir.join("a.txt.bak").exists());
+
+        // an existing name isn't replaced
+        let src = dir.join("sub/a.txt.bak");
+        assert!(rename_entry(&src, path::Path::new("../a.txt"), &dir).is_err());
+        assert_eq!(fs::read_to_string(dir.join("a.txt")).unwrap(), "top");
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
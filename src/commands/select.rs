@@ -1,4 +1,5 @@
 use crate::error::AppResult;
+use crate::fs::JoshutoDirEntry;
 use crate::types::state::{AppState, MatchState};
 
 use super::cursor_move;
@@ -84,12 +85,24 @@ fn select_without_pattern(app_state: &mut AppState, options: &SelectOption) -> A
     Ok(())
 }
 
-/// Applies `options` to every entry matching `pattern`.
+/// Applies `options` to every entry whose name matches `pattern`.
 fn select_with_pattern(
     app_state: &mut AppState,
     pattern: &MatchState,
     options: &SelectOption,
 ) -> AppResult {
+    select_matching(app_state, |e| pattern.is_match(e.file_name()), options)
+}
+
+/// Applies `options` to every entry for which `predicate` holds.
+pub fn select_matching<F>(
+    app_state: &mut AppState,
+    predicate: F,
+    options: &SelectOption,
+) -> AppResult
+where
+    F: Fn(&JoshutoDirEntry) -> bool,
+{
     if let Some(curr_list) = app_state
         .state
         .tab_state_mut()
@@ -97,19 +110,16 @@ fn select_with_pattern(
         .curr_list_mut()
     {
         let mut found = 0;
-        curr_list
-            .iter_mut()
-            .filter(|e| pattern.is_match(e.file_name()))
-            .for_each(|e| {
-                found += 1;
-                if options.reverse {
-                    e.set_permanent_selected(false);
-                } else if options.toggle {
-                    e.set_permanent_selected(!e.is_selected());
-                } else {
-                    e.set_permanent_selected(true);
-                }
-            });
+        curr_list.iter_mut().filter(|e| predicate(e)).for_each(|e| {
+            found += 1;
+            if options.reverse {
+                e.set_permanent_selected(false);
+            } else if options.toggle {
+                e.set_permanent_selected(!e.is_selected());
+            } else {
+                e.set_permanent_selected(true);
+            }
+        });
         app_state
             .state
             .message_queue_mut()
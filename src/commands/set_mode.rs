@@ -1,30 +1,57 @@
+use std::fs;
+use std::os::unix::fs::MetadataExt;
+
 use nix::fcntl::AT_FDCWD;
 use nix::sys::stat::{fchmodat, FchmodatFlags, Mode};
 
 use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::fs::JoshutoDirEntry;
+use crate::types::io::{FileOperation, FileOperationOptions, IoTask, ModeChangeOptions};
 use crate::types::state::AppState;
 use crate::ui::views::{DummyListener, TuiTextField};
 use crate::ui::AppBackend;
-use crate::utils::unix::{self, UNIX_PERMISSION_VALS};
+use crate::utils::unix;
 
 use super::cursor_move;
 
-/// Parses a `rwxrwxrwx`-style permission string into a Unix [`Mode`].
-pub fn str_to_mode(s: &str) -> Mode {
-    let mut mode = Mode::empty();
-    for (i, ch) in s.chars().enumerate().take(UNIX_PERMISSION_VALS.len()) {
-        if ch == UNIX_PERMISSION_VALS[i].1 {
-            let (val, _) = UNIX_PERMISSION_VALS[i];
-            mode = mode.union(val);
-        }
-    }
-    mode
+/// Applies `options` to the entry's current mode, read from the filesystem, as the cached one
+/// may not be loaded yet or be out of date.
+fn set_entry_mode(entry: &mut JoshutoDirEntry, options: &ModeChangeOptions) -> AppResult {
+    let metadata = fs::symlink_metadata(entry.file_path())?;
+    let is_dir = metadata.is_dir();
+    let current = Mode::from_bits_truncate(metadata.mode() as _);
+    entry.metadata.mode = current;
+    let change = match options.change_for(is_dir) {
+        Some(change) => change,
+        None => return Ok(()),
+    };
+    let mode = change.apply(current, is_dir);
+    fchmodat(
+        AT_FDCWD,
+        entry.file_path(),
+        mode,
+        FchmodatFlags::NoFollowSymlink,
+    )
+    .map_err(|err| {
+        let error_msg = format!("Failed to set file permissions: {err}");
+        AppError::new(AppErrorKind::Io, error_msg)
+    })?;
+    entry.metadata.mode = mode;
+    Ok(())
 }
 
-/// Implements `set_mode`: prompts for a new permission string and applies it to the selected
-/// entries (or the current entry, if none selected).
-pub fn set_mode(app_state: &mut AppState, backend: &mut AppBackend) -> AppResult {
-    const PREFIX: &str = "set_mode ";
+/// Prompts for a mode, pre-filled with the current entry's `rwxrwxrwx` string once its
+/// metadata is loaded, and parses the input on top of `options`.
+fn prompt_mode(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    options: ModeChangeOptions,
+) -> AppResult<Option<ModeChangeOptions>> {
+    let prefix = if options.recursive {
+        "set_mode --recursive "
+    } else {
+        "set_mode "
+    };
     let entry = app_state
         .state
         .tab_state_ref()
@@ -34,59 +61,123 @@ pub fn set_mode(app_state: &mut AppState, backend: &mut AppBackend) -> AppResult
 
     let user_input = match entry {
         Some(entry) => {
-            let mode_arr = unix::mode_to_char_array(entry.metadata.mode, entry.metadata.file_type);
+            let mode_str: String = if entry.metadata.is_loaded() {
+                let mode_arr =
+                    unix::mode_to_char_array(entry.metadata.mode, entry.metadata.file_type);
+                mode_arr[1..].iter().collect()
+            } else {
+                String::new()
+            };
             let mut listener = DummyListener {};
 
-            let mode_str: String = mode_arr[1..].iter().collect();
             app_state.flush_event();
             TuiTextField::default()
                 .prompt(":")
-                .prefix(PREFIX)
+                .prefix(prefix)
                 .suffix(&mode_str)
                 .get_input(app_state, backend, &mut listener)
         }
         None => None,
     };
 
-    if let Some(s) = user_input {
-        if let Some(stripped) = s.strip_prefix(PREFIX) {
-            let mode = str_to_mode(stripped);
-            if let Some(curr_list) = app_state
-                .state
-                .tab_state_mut()
-                .curr_tab_mut()
-                .curr_list_mut()
-            {
-                if curr_list.selected_count() > 0 {
-                    for entry in curr_list.iter_selected_mut() {
-                        fchmodat(
-                            AT_FDCWD,
-                            entry.file_path(),
-                            mode,
-                            FchmodatFlags::NoFollowSymlink,
-                        )
-                        .map_err(|err| {
-                            let error_msg = format!("Failed to set file permissions: {err}");
-                            AppError::new(AppErrorKind::Io, error_msg)
-                        })?;
-                        entry.metadata.mode = mode;
-                    }
-                } else if let Some(entry) = curr_list.curr_entry_mut() {
-                    fchmodat(
-                        AT_FDCWD,
-                        entry.file_path(),
-                        mode,
-                        FchmodatFlags::NoFollowSymlink,
-                    )
-                    .map_err(|err| {
-                        let error_msg = format!("Failed to set file permissions: {err}");
-                        AppError::new(AppErrorKind::Io, error_msg)
-                    })?;
-                    entry.metadata.mode = mode;
-                    cursor_move::down(app_state, 1)?;
-                }
+    match user_input
+        .as_deref()
+        .and_then(|s| s.strip_prefix("set_mode"))
+    {
+        Some(args) => Ok(Some(ModeChangeOptions::default().parse_args(args)?)),
+        None => Ok(None),
+    }
+}
+
+/// Implements `set_mode`: applies a permission change to the selected entries (or the current
+/// entry, if none selected), prompting for the mode if none was given. Recursive changes are
+/// queued as a background task.
+pub fn set_mode(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    options: ModeChangeOptions,
+) -> AppResult {
+    let options = if options.is_empty() {
+        match prompt_mode(app_state, backend, options)? {
+            Some(options) if !options.is_empty() => options,
+            _ => return Ok(()),
+        }
+    } else {
+        options
+    };
+
+    if options.recursive {
+        return set_mode_recursive(app_state, options);
+    }
+
+    if let Some(curr_list) = app_state
+        .state
+        .tab_state_mut()
+        .curr_tab_mut()
+        .curr_list_mut()
+    {
+        if curr_list.selected_count() > 0 {
+            for entry in curr_list.iter_selected_mut() {
+                set_entry_mode(entry, &options)?;
             }
+        } else if let Some(entry) = curr_list.curr_entry_mut() {
+            set_entry_mode(entry, &options)?;
+            cursor_move::down(app_state, 1)?;
         }
     }
     Ok(())
 }
+
+fn set_mode_recursive(app_state: &mut AppState, options: ModeChangeOptions) -> AppResult {
+    let tab = app_state.state.tab_state_ref().curr_tab_ref();
+    let paths = tab
+        .curr_list_ref()
+        .map(|list| list.get_selected_paths())
+        .unwrap_or_default();
+    if paths.is_empty() {
+        let err_msg = "No files selected";
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            err_msg.to_string(),
+        ));
+    }
+
+    let dest = tab.get_cwd().to_path_buf();
+    let file_options = FileOperationOptions {
+        mode_change: options,
+        ..Default::default()
+    };
+    let task = IoTask::new(FileOperation::SetMode, paths, dest, file_options);
+    app_state.state.worker_state_mut().push_task(task);
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use crate::fs::{FileType, JoshutoMetadata, LinkType};
+
+    use super::*;
+
+    #[test]
+    fn test_set_mode_of_unloaded_entry() {
+        let dir = std::env::temp_dir().join(format!("joshuto-set-mode-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("a.sh");
+        fs::write(&path, "").unwrap();
+        fs::set_permissions(&path, fs::Permissions::from_mode(0o644)).unwrap();
+
+        let metadata = JoshutoMetadata::unloaded(FileType::File, LinkType::Normal);
+        let mut entry = JoshutoDirEntry::from_metadata(path.clone(), &dir, metadata);
+        let options = ModeChangeOptions::default().parse_args(" u+x").unwrap();
+        set_entry_mode(&mut entry, &options).unwrap();
+
+        let mode = fs::metadata(&path).unwrap().permissions().mode() & 0o7777;
+        assert_eq!(mode, 0o744);
+        assert_eq!(entry.metadata.mode.bits() as u32, 0o744);
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,74 @@
+use std::fs;
+use std::path;
+
+use crate::commands::tab_ops;
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::types::io::{
+    FileOperation, FileOperationOptions, IoTask, TimeChange, TimeChangeOptions,
+};
+use crate::types::state::AppState;
+
+/// Where `set_time` takes the new timestamps from.
+#[derive(Clone, Debug)]
+pub enum TimeSource {
+    /// A datetime or relative offset given on the command line.
+    Change(TimeChange),
+    /// The access and modification times of an existing file.
+    Reference(path::PathBuf),
+}
+
+/// Implements `set_time`: sets the access and/or modification time of the selected entries (or
+/// the current entry, if none selected). Recursive changes are queued as a background task.
+pub fn set_time(
+    app_state: &mut AppState,
+    source: &TimeSource,
+    accessed: bool,
+    modified: bool,
+    recursive: bool,
+) -> AppResult {
+    let (atime_change, mtime_change) = match source {
+        TimeSource::Change(change) => (*change, *change),
+        TimeSource::Reference(path) => {
+            let metadata = fs::metadata(path)?;
+            (
+                TimeChange::Absolute(metadata.accessed()?),
+                TimeChange::Absolute(metadata.modified()?),
+            )
+        }
+    };
+    let options = TimeChangeOptions {
+        accessed: accessed.then_some(atime_change),
+        modified: modified.then_some(mtime_change),
+        recursive,
+    };
+
+    let tab = app_state.state.tab_state_ref().curr_tab_ref();
+    let paths = tab
+        .curr_list_ref()
+        .map(|list| list.get_selected_paths())
+        .unwrap_or_default();
+    if paths.is_empty() {
+        let err_msg = "No files selected";
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            err_msg.to_string(),
+        ));
+    }
+    let cwd = tab.get_cwd().to_path_buf();
+
+    if recursive {
+        let file_options = FileOperationOptions {
+            time_change: options,
+            ..Default::default()
+        };
+        let task = IoTask::new(FileOperation::SetTime, paths, cwd, file_options);
+        app_state.state.worker_state_mut().push_task(task);
+        return Ok(());
+    }
+
+    for path in paths.iter() {
+        options.apply_to(path)?;
+    }
+    tab_ops::reload_all_tabs(app_state, cwd.as_path())?;
+    Ok(())
+}
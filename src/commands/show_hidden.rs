@@ -2,7 +2,7 @@ use crate::error::AppResult;
 use crate::history::DirectoryHistory;
 use crate::types::state::AppState;
 
-use super::reload;
+use super::{dir_settings, reload};
 
 /// Flips the show-hidden-files setting and invalidates all cached listings, without reloading.
 pub fn _toggle_hidden(app_state: &mut AppState) {
@@ -19,6 +19,10 @@ pub fn _toggle_hidden(app_state: &mut AppState) {
 
 /// Implements `toggle_hidden`: toggles whether hidden files are shown and reloads the current tab.
 pub fn toggle_hidden(app_state: &mut AppState) -> AppResult {
+    let show_hidden = !app_state.config.display_options.show_hidden;
+    dir_settings::remember(app_state, |settings| {
+        settings.show_hidden = Some(show_hidden)
+    })?;
     _toggle_hidden(app_state);
     reload::soft_reload_curr_tab(app_state)?;
     Ok(())
@@ -0,0 +1,21 @@
+use crate::error::AppResult;
+use crate::history::DirectoryHistory;
+use crate::types::state::AppState;
+
+use super::reload;
+
+/// Implements `toggle_ignored`: toggles whether the current tab lists entries ignored by
+/// `.gitignore` and `.ignore` files, and reloads it.
+pub fn toggle_ignored(app_state: &mut AppState) -> AppResult {
+    let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
+    let option = curr_tab.option_mut();
+    option.show_ignored = !option.show_ignored;
+
+    curr_tab.history_mut().depreciate_all_entries();
+    if let Some(list) = curr_tab.curr_list_mut() {
+        list.depreciate();
+    }
+
+    reload::soft_reload_curr_tab(app_state)?;
+    Ok(())
+}
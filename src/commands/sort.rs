@@ -1,12 +1,40 @@
-use crate::error::AppResult;
+use ratatui::layout::Rect;
+use ratatui::termion::event::{Event, Key};
+use ratatui::widgets::Clear;
+
+use crate::error::{AppError, AppErrorKind, AppResult};
 use crate::history::DirectoryHistory;
+use crate::run::process_event;
+use crate::types::event::AppEvent;
 use crate::types::option::sort::SortMethod;
 use crate::types::state::AppState;
+use crate::ui::views::TuiView;
+use crate::ui::widgets::TuiMenu;
+use crate::ui::AppBackend;
 
-use super::reload;
+use super::{dir_settings, reload};
 
 /// Implements `sort`: sets the primary sort method (and optionally reverse) for the current tab.
 pub fn set_sort(app_state: &mut AppState, method: SortMethod, reverse: Option<bool>) -> AppResult {
+    if let SortMethod::Custom(name) = &method {
+        if app_state
+            .config
+            .display_options
+            .custom_column(name)
+            .is_none()
+        {
+            return Err(AppError::new(
+                AppErrorKind::InvalidParameters,
+                format!("Sort method '{}' unknown.", name),
+            ));
+        }
+    }
+    dir_settings::remember(app_state, |settings| {
+        settings.sort_method = Some(method.to_string());
+        if reverse.is_some() {
+            settings.reverse = reverse;
+        }
+    })?;
     let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
     curr_tab
         .option_mut()
@@ -23,13 +51,156 @@ pub fn set_sort(app_state: &mut AppState, method: SortMethod, reverse: Option<bo
 
 /// Implements `sort reverse`: toggles reverse sort order for the current tab.
 pub fn toggle_reverse(app_state: &mut AppState) -> AppResult {
+    let reversed = !app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .option_ref()
+        .sort_options_ref()
+        .reverse;
+    dir_settings::remember(app_state, |settings| settings.reverse = Some(reversed))?;
     let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
-    let reversed = !curr_tab.option_mut().sort_options_ref().reverse;
     curr_tab.option_mut().sort_options_mut().reverse = reversed;
     curr_tab.history_mut().depreciate_all_entries();
     refresh(app_state)
 }
 
+/// Implements `sort reshuffle`: picks a new random order and sorts by it.
+pub fn reshuffle(app_state: &mut AppState) -> AppResult {
+    let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
+    let sort_options = curr_tab.option_mut().sort_options_mut();
+    sort_options.reshuffle();
+    sort_options.set_sort_method(SortMethod::Random);
+    curr_tab.history_mut().depreciate_all_entries();
+    refresh(app_state)
+}
+
+/// Returns the key selecting a built-in sort method in `sort_menu`.
+fn menu_key(method: &SortMethod) -> char {
+    match method {
+        SortMethod::Natural => 'n',
+        SortMethod::Lexical => 'l',
+        SortMethod::Version => 'v',
+        SortMethod::Length => 'L',
+        SortMethod::Ext => 'e',
+        SortMethod::Type => 't',
+        SortMethod::Size => 's',
+        SortMethod::CumulativeSize => 'S',
+        SortMethod::Count => 'c',
+        SortMethod::Mtime => 'm',
+        SortMethod::Ctime => 'C',
+        SortMethod::Atime => 'a',
+        SortMethod::Btime => 'b',
+        SortMethod::Tag => 'T',
+        SortMethod::Random => 'R',
+        SortMethod::Custom(_) => ' ',
+    }
+}
+
+/// Implements `sort_menu`: shows the sort methods, marking the active ones with their priority
+/// and the current direction, and applies the one whose key is pressed. Choosing the primary
+/// method again, or `r`, reverses the order.
+pub fn sort_menu(app_state: &mut AppState, backend: &mut AppBackend) -> AppResult {
+    let custom_methods = app_state
+        .config
+        .display_options
+        .custom_columns
+        .iter()
+        .map(|column| SortMethod::Custom(column.name.clone()));
+    let methods: Vec<(char, SortMethod)> = SortMethod::BUILTIN
+        .into_iter()
+        .map(|method| (menu_key(&method), method))
+        .chain(('1'..='9').zip(custom_methods))
+        .collect();
+
+    let sort_options = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .option_ref()
+        .sort_options_ref();
+    let direction = if sort_options.reverse {
+        "descending"
+    } else {
+        "ascending"
+    };
+    let active: Vec<&SortMethod> = sort_options.sort_methods.active().collect();
+    let mut lines: Vec<String> = methods
+        .iter()
+        .map(|(key, method)| {
+            let status = match active.iter().position(|m| *m == method) {
+                Some(i) => format!("{}. {}", i + 1, direction),
+                None => String::new(),
+            };
+            format!("  {}    {:<12}{}", key, method.as_str(), status)
+        })
+        .collect();
+    lines.push(format!("  r    {:<12}", "reverse"));
+    let primary = active.first().map(|m| (*m).clone());
+
+    let key = poll_for_sort_key(app_state, backend, &lines);
+    match key {
+        Some(Event::Key(Key::Char('r'))) => toggle_reverse(app_state),
+        Some(Event::Key(Key::Char(c))) => match methods.into_iter().find(|(key, _)| *key == c) {
+            Some((_, method)) if Some(&method) == primary.as_ref() => toggle_reverse(app_state),
+            Some((_, method)) => set_sort(app_state, method, None),
+            None => Ok(()),
+        },
+        _ => Ok(()),
+    }
+}
+
+/// Renders the sort menu and blocks until the user presses a key, returning it.
+fn poll_for_sort_key(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    lines: &[String],
+) -> Option<Event> {
+    app_state.flush_event();
+
+    let lines_str: Vec<&str> = lines.iter().map(|s| s.as_str()).collect();
+    let terminal = backend.terminal_mut();
+    loop {
+        let _ = terminal.draw(|frame| {
+            let area = frame.area();
+            if area.height < 5 {
+                return;
+            }
+            // redraw view
+            {
+                let mut view = TuiView::new(app_state);
+                view.show_bottom_status = false;
+                frame.render_widget(view, area);
+            }
+
+            let (menu_widget, menu_y) = if lines_str.len() > area.height as usize - 1 {
+                (TuiMenu::new(&lines_str[0..area.height as usize - 1]), 0)
+            } else {
+                (
+                    TuiMenu::new(lines_str.as_slice()),
+                    (area.height as usize - lines_str.len() - 1) as u16,
+                )
+            };
+
+            let menu_rect = Rect {
+                x: 0,
+                y: menu_y,
+                width: area.width,
+                height: menu_widget.len() as u16 + 1,
+            };
+            frame.render_widget(Clear, menu_rect);
+            frame.render_widget(menu_widget, menu_rect);
+        });
+
+        if let Ok(event) = app_state.poll_event() {
+            match event {
+                AppEvent::TerminalEvent(key) => return Some(key),
+                event => process_event::process_noninteractive(event, app_state),
+            };
+        }
+    }
+}
+
 fn refresh(app_state: &mut AppState) -> AppResult {
     reload::soft_reload_curr_tab(app_state)?;
 
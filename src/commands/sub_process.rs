@@ -46,7 +46,8 @@ fn current_dir(app_state: &AppState) -> &Path {
     app_state.state.tab_state_ref().curr_tab_ref().get_cwd()
 }
 
-fn shell_quote(val: &str) -> String {
+/// Quotes `val` for use as a single word in a `sh` command line.
+pub fn shell_quote(val: &str) -> String {
     format!("'{}'", val.replace('\'', "'\\''"))
 }
 
@@ -0,0 +1,151 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::history::DirectoryHistory;
+use crate::types::keymap::AppKeyMapping;
+use crate::types::state::AppState;
+use crate::ui::AppBackend;
+use crate::utils::symlink;
+
+use super::change_directory::change_directory;
+use super::{command_line, find, reload};
+
+fn not_a_link(path: &Path) -> AppError {
+    AppError::new(
+        AppErrorKind::InvalidParameters,
+        format!("Not a symbolic link: {}", path.to_string_lossy()),
+    )
+}
+
+/// Returns the path of the entry under the cursor and where it points to, failing unless it's
+/// a link.
+fn curr_link(app_state: &AppState) -> AppResult<(PathBuf, PathBuf)> {
+    let path = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+        .and_then(|list| list.curr_entry_ref())
+        .map(|entry| entry.file_path_buf())
+        .ok_or_else(|| AppError::new(AppErrorKind::InvalidParameters, "No entry".to_string()))?;
+    let target = fs::read_link(&path).map_err(|_| not_a_link(&path))?;
+    Ok((path, target))
+}
+
+/// Changes into the directory containing `path`, with the cursor on it.
+fn select_path(app_state: &mut AppState, path: &Path) -> AppResult {
+    let (Some(parent), Some(file_name)) = (path.parent(), path.file_name()) else {
+        return change_directory(app_state, path);
+    };
+    change_directory(app_state, parent)?;
+
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    if let Some(curr_list) = app_state
+        .state
+        .tab_state_mut()
+        .curr_tab_mut()
+        .curr_list_mut()
+    {
+        let index = curr_list
+            .iter()
+            .position(|e| e.file_path().file_name() == Some(file_name));
+        if index.is_some() {
+            curr_list.set_index(index, &ui_state, display_options);
+        } else {
+            app_state
+                .state
+                .message_queue_mut()
+                .push_info(format!("{} isn't shown", file_name.to_string_lossy()));
+        }
+    }
+    Ok(())
+}
+
+/// Re-reads all listings after links were changed.
+fn reload_links(app_state: &mut AppState) -> AppResult {
+    for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+        tab.history_mut().depreciate_all_entries();
+    }
+    reload::soft_reload_curr_tab(app_state)?;
+    Ok(())
+}
+
+/// Implements `follow_link`: jumps to where the link under the cursor points to, with the cursor
+/// on the target.
+pub fn follow_link(app_state: &mut AppState) -> AppResult {
+    let (path, target) = curr_link(app_state)?;
+    let target = symlink::resolve_target(&path, &target);
+    if fs::symlink_metadata(&target).is_err() {
+        return Err(AppError::new(
+            AppErrorKind::Io,
+            format!("Broken link: {}", target.to_string_lossy()),
+        ));
+    }
+    select_path(app_state, &target)
+}
+
+/// Implements `edit_link`: points the link under the cursor to `target`. Without a target,
+/// opens the command line pre-filled with the current one.
+pub fn edit_link(
+    app_state: &mut AppState,
+    backend: &mut AppBackend,
+    keymap_t: &AppKeyMapping,
+    target: Option<&Path>,
+) -> AppResult {
+    let (path, curr_target) = curr_link(app_state)?;
+    match target {
+        Some(target) => {
+            symlink::retarget(&path, target)?;
+            reload_links(app_state)
+        }
+        None => {
+            let prefix = format!("edit_link {}", curr_target.to_string_lossy());
+            command_line::read_and_execute(app_state, backend, keymap_t, &prefix, "")
+        }
+    }
+}
+
+/// Implements `link_make_relative` and `link_make_absolute`: rewrites the targets of the
+/// selected links (or the one under the cursor) as relative or absolute paths. Other entries
+/// are skipped.
+pub fn link_convert(app_state: &mut AppState, relative: bool) -> AppResult {
+    let paths = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+        .map(|list| list.get_selected_paths())
+        .unwrap_or_default();
+
+    let mut converted = 0;
+    for path in paths {
+        let Ok(target) = fs::read_link(&path) else {
+            continue;
+        };
+        let absolute = symlink::resolve_target(&path, &target);
+        let new_target = match path.parent() {
+            Some(parent) if relative => symlink::relative_path(parent, &absolute),
+            _ => absolute,
+        };
+        if new_target != target {
+            symlink::retarget(&path, &new_target)?;
+            converted += 1;
+        }
+    }
+    app_state
+        .state
+        .message_queue_mut()
+        .push_success(format!("{} link(s) converted", converted));
+    reload_links(app_state)
+}
+
+/// Implements `find_broken_links`: searches the tree below the current directory in the
+/// background for links whose targets don't exist, showing them in a virtual listing as `find`
+/// does.
+pub fn find_broken_links(app_state: &mut AppState) -> AppResult {
+    find::start_find(app_state, |e, _| {
+        e.path_is_symlink() && fs::metadata(e.path()).is_err()
+    })
+}
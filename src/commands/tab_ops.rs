@@ -15,6 +15,7 @@ use crate::utils::{cwd, unix};
 use crate::HOME_DIR;
 
 use super::quit::{quit_with_action, QuitAction};
+use super::{dir_settings, reload};
 
 /// Makes the tab at `new_index` active: syncs the process cwd to it and loads listings for its
 /// current, parent, and cursor-entry directories.
@@ -27,6 +28,9 @@ fn _tab_switch(new_index: usize, app_state: &mut AppState) -> std::io::Result<()
         .get_cwd()
         .to_path_buf();
     cwd::set_current_dir(cwd.as_path())?;
+    if dir_settings::apply(app_state) {
+        reload::soft_reload_curr_tab(app_state)?;
+    }
 
     let entry_path = match app_state
         .state
@@ -37,7 +41,7 @@ fn _tab_switch(new_index: usize, app_state: &mut AppState) -> std::io::Result<()
     {
         Some(entry) => {
             let file_path = entry.file_path();
-            if file_path.is_dir() {
+            if entry.metadata.is_dir() {
                 Some(file_path.to_path_buf())
             } else {
                 None
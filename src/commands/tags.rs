@@ -0,0 +1,74 @@
+use crate::error::AppResult;
+use crate::fs::{JoshutoDirList, TagMatch, DEFAULT_TAG};
+use crate::types::state::AppState;
+use crate::TAGS_T;
+
+use super::reload;
+use super::select::{self, SelectOption};
+
+/// Implements `tag_toggle`: tags the selected entries (or the current one) with `tag`, or with
+/// `*` if not given. If all of them already have that tag, untags them instead.
+pub fn tag_toggle(app_state: &mut AppState, tag: Option<char>) -> AppResult {
+    let tag = tag.unwrap_or(DEFAULT_TAG);
+    let Some(curr_list) = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+    else {
+        return Ok(());
+    };
+    let entries = curr_list.selected_or_current();
+    let untag = entries.iter().all(|e| e.tag() == Some(tag));
+    let paths: Vec<_> = entries.iter().map(|e| e.file_path_buf()).collect();
+
+    if let Ok(mut tags) = TAGS_T.lock() {
+        for path in paths.iter() {
+            tags.set(path, (!untag).then_some(tag));
+        }
+        tags.save()?;
+
+        // update every listing showing the entries, virtual ones included
+        let update_tags = |list: &mut JoshutoDirList| {
+            for entry in list.iter_mut() {
+                if paths.iter().any(|p| p == entry.file_path()) {
+                    entry.set_tag(tags.get(entry.file_path()));
+                }
+            }
+        };
+        for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+            for (_, list) in tab.history_mut().iter_mut() {
+                update_tags(list);
+            }
+            if let Some(list) = tab.virtual_list.as_mut() {
+                update_tags(list);
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Implements `select_tag`: applies `options` to the entries of the current directory matching
+/// `tag`.
+pub fn select_tag(app_state: &mut AppState, tag: TagMatch, options: &SelectOption) -> AppResult {
+    select::select_matching(app_state, |e| tag.is_match(e.tag()), options)
+}
+
+/// Implements `filter_tag`: shows only the entries of the current directory matching `tag`, or
+/// all of them again if `None`.
+pub fn filter_tag(app_state: &mut AppState, tag: Option<TagMatch>) -> AppResult {
+    let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
+    let path = curr_tab.get_cwd().to_path_buf();
+
+    curr_tab
+        .option_mut()
+        .dirlist_options_mut(&path)
+        .set_tag_filter(tag);
+
+    if let Some(list) = curr_tab.curr_list_mut() {
+        list.depreciate();
+    }
+
+    reload::soft_reload_curr_tab(app_state)?;
+    Ok(())
+}
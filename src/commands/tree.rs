@@ -0,0 +1,136 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::history::create_dirlist_with_history;
+use crate::types::state::AppState;
+
+use super::reload;
+
+/// Returns the path of the entry under the cursor, failing outside of the `tree` display mode.
+fn curr_entry(app_state: &AppState) -> AppResult<Option<(PathBuf, bool)>> {
+    if !app_state.config.display_options.mode.is_tree() {
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            "Not in the tree display mode".to_string(),
+        ));
+    }
+    Ok(app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .curr_list_ref()
+        .and_then(|list| list.curr_entry_ref())
+        .map(|entry| (entry.file_path_buf(), entry.metadata.is_dir())))
+}
+
+/// Reads the current listing again to show the changed expansions, keeping the cursor on
+/// `cursor` if given.
+fn reload_tree(app_state: &mut AppState, cursor: Option<&Path>) -> AppResult {
+    if let Some(list) = app_state
+        .state
+        .tab_state_mut()
+        .curr_tab_mut()
+        .curr_list_mut()
+    {
+        list.depreciate();
+    }
+    reload::soft_reload_curr_tab(app_state)?;
+
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    if let (Some(list), Some(cursor)) = (
+        app_state
+            .state
+            .tab_state_mut()
+            .curr_tab_mut()
+            .curr_list_mut(),
+        cursor,
+    ) {
+        let index = list.iter().position(|e| e.file_path() == cursor);
+        if index.is_some() {
+            list.set_index(index, &ui_state, display_options);
+        }
+    }
+    Ok(())
+}
+
+/// Shows the contents of the directory `dir` inline, loading its listing into the tab's
+/// history unless it's cached there already.
+fn expand(app_state: &mut AppState, dir: PathBuf) -> AppResult {
+    let curr_tab = app_state.state.tab_state_ref().curr_tab_ref();
+    let history = curr_tab.history_ref();
+    if history.get(&dir).is_none_or(|list| list.need_update()) {
+        let dirlist = create_dirlist_with_history(
+            history,
+            &dir,
+            &app_state.config.display_options,
+            curr_tab.option_ref(),
+        )?;
+        app_state
+            .state
+            .tab_state_mut()
+            .curr_tab_mut()
+            .history_mut()
+            .insert(dir.clone(), dirlist);
+    }
+    app_state
+        .state
+        .tab_state_mut()
+        .curr_tab_mut()
+        .option_mut()
+        .expanded_dirs
+        .insert(dir.clone());
+    reload_tree(app_state, Some(&dir))
+}
+
+/// Hides the contents of the directory `dir`, moving the cursor onto it.
+fn collapse(app_state: &mut AppState, dir: PathBuf) -> AppResult {
+    app_state
+        .state
+        .tab_state_mut()
+        .curr_tab_mut()
+        .option_mut()
+        .expanded_dirs
+        .remove(&dir);
+    reload_tree(app_state, Some(&dir))
+}
+
+/// Implements `tree_expand`: shows the contents of the directory under the cursor inline.
+pub fn tree_expand(app_state: &mut AppState) -> AppResult {
+    match curr_entry(app_state)? {
+        Some((path, true)) => expand(app_state, path),
+        _ => Ok(()),
+    }
+}
+
+/// Implements `tree_collapse`: hides the contents of the directory under the cursor, or of the
+/// directory containing it if it isn't expanded.
+pub fn tree_collapse(app_state: &mut AppState) -> AppResult {
+    let Some((path, is_dir)) = curr_entry(app_state)? else {
+        return Ok(());
+    };
+    let curr_tab = app_state.state.tab_state_ref().curr_tab_ref();
+    if is_dir && curr_tab.option_ref().is_expanded(&path) {
+        return collapse(app_state, path);
+    }
+    match path.parent() {
+        Some(parent) if parent != curr_tab.get_cwd() => collapse(app_state, parent.to_path_buf()),
+        _ => Ok(()),
+    }
+}
+
+/// Implements `tree_toggle`: expands the directory under the cursor if collapsed, and collapses
+/// it otherwise.
+pub fn tree_toggle(app_state: &mut AppState) -> AppResult {
+    match curr_entry(app_state)? {
+        Some((path, true)) => {
+            let curr_tab = app_state.state.tab_state_ref().curr_tab_ref();
+            if curr_tab.option_ref().is_expanded(&path) {
+                collapse(app_state, path)
+            } else {
+                expand(app_state, path)
+            }
+        }
+        _ => Ok(()),
+    }
+}
@@ -0,0 +1,61 @@
+use std::path::PathBuf;
+
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::fs::{common_dir, virtual_dirlist};
+use crate::types::state::AppState;
+use crate::utils::symlink::normalize;
+
+use super::change_directory::change_directory;
+use super::reload;
+
+/// Shows `paths` as one listing in place of the current directory's, with names relative to
+/// the directory containing all of them, which becomes the current directory. Relative paths
+/// are taken as relative to the current directory.
+pub fn open_virtual_list(app_state: &mut AppState, paths: Vec<PathBuf>) -> AppResult {
+    let cwd = app_state
+        .state
+        .tab_state_ref()
+        .curr_tab_ref()
+        .get_cwd()
+        .to_path_buf();
+    let mut paths: Vec<PathBuf> = paths
+        .iter()
+        .map(|path| normalize(&cwd.join(path)))
+        .collect();
+    paths.sort();
+    paths.dedup();
+    if paths.is_empty() {
+        return Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            "No paths to list".to_string(),
+        ));
+    }
+
+    let base = common_dir(&paths);
+    change_directory(app_state, &base)?;
+
+    let display_options = &app_state.config.display_options;
+    let tab_options = app_state.state.tab_state_ref().curr_tab_ref().option_ref();
+    let list = virtual_dirlist(base, paths, None, display_options, tab_options)?;
+    let len = list.len();
+    app_state.state.tab_state_mut().curr_tab_mut().virtual_list = Some(list);
+    app_state
+        .state
+        .message_queue_mut()
+        .push_info(format!("{} path(s) listed", len));
+    Ok(())
+}
+
+/// Implements `close_virtual`: goes back from the virtual listing to the listing of the current
+/// directory, stopping the `find` filling it, if any.
+pub fn close_virtual_list(app_state: &mut AppState) -> AppResult {
+    let curr_tab = app_state.state.tab_state_mut().curr_tab_mut();
+    let Some(list) = curr_tab.virtual_list.take() else {
+        return Ok(());
+    };
+    if list.load_id.is_some() {
+        app_state.state.find_state_mut().cancel();
+    }
+    reload::soft_reload_curr_tab(app_state)?;
+    Ok(())
+}
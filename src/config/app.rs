@@ -4,12 +4,15 @@ use std::path::PathBuf;
 use super::{app_raw::AppConfigRaw, tab::TabOption};
 use crate::{
     constants::config::APP_CONFIG,
-    error::AppResult,
+    error::{AppError, AppResult},
     traits::config::TomlConfigFile,
     types::{
         config_type::ConfigType,
         custom_command::CustomCommand,
-        option::{display::DisplayOption, preview::PreviewOption, search::SearchOption},
+        option::{
+            dir_settings::DirSettingsOption, display::DisplayOption, line_mode::LineMode,
+            preview::PreviewOption, search::SearchOption, sort::SortMethod,
+        },
     },
 };
 
@@ -30,6 +33,7 @@ pub struct AppConfig {
     pub preview_options: PreviewOption,
     pub search_options: SearchOption,
     pub tab_options: TabOption,
+    pub dir_settings_options: DirSettingsOption,
     pub shell: PathBuf,
 }
 
@@ -37,7 +41,7 @@ impl AppConfig {
     /// Parses the built-in default `joshuto.toml`, returning an error only if it's malformed.
     pub fn default_res() -> AppResult<Self> {
         let raw: AppConfigRaw = toml::from_str(APP_CONFIG)?;
-        Ok(Self::from(raw))
+        Self::try_from(raw)
     }
 }
 
@@ -57,9 +61,27 @@ impl TomlConfigFile for AppConfig {
     }
 }
 
-impl From<AppConfigRaw> for AppConfig {
-    fn from(raw: AppConfigRaw) -> Self {
-        Self {
+impl TryFrom<AppConfigRaw> for AppConfig {
+    type Error = AppError;
+
+    fn try_from(raw: AppConfigRaw) -> AppResult<Self> {
+        let display_options = DisplayOption::try_from(raw.display_options)?;
+        let dir_settings_options = DirSettingsOption::from(raw.dir_settings_options);
+        for settings in dir_settings_options.rules.iter().map(|rule| &rule.settings) {
+            if let Some(SortMethod::Custom(name)) = settings
+                .sort_method
+                .as_deref()
+                .and_then(SortMethod::from_str)
+            {
+                display_options.check_custom_column(&name)?;
+            }
+            if let Some(linemode) = settings.linemode.as_deref() {
+                for name in LineMode::from_string(linemode)?.custom_columns() {
+                    display_options.check_custom_column(name)?;
+                }
+            }
+        }
+        Ok(Self {
             use_trash: raw.use_trash,
             xdg_open: raw.xdg_open,
             xdg_open_fork: raw.xdg_open_fork,
@@ -69,12 +91,13 @@ impl From<AppConfigRaw> for AppConfig {
             focus_on_create: raw.focus_on_create,
             mouse_support: raw.mouse_support,
             zoxide_update: raw.zoxide_update,
-            display_options: DisplayOption::from(raw.display_options),
-            preview_options: PreviewOption::from(raw.preview_options),
+            display_options,
+            preview_options: PreviewOption::try_from(raw.preview_options)?,
             search_options: raw.search_options,
             tab_options: raw.tab_options,
+            dir_settings_options,
             custom_commands: raw.custom_commands,
             shell: raw.shell,
-        }
+        })
     }
 }
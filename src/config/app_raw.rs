@@ -3,6 +3,7 @@ use std::path::PathBuf;
 
 use serde::Deserialize;
 
+use super::dir_settings_raw::DirSettingsOptionRaw;
 use super::display_raw::DisplayOptionRaw;
 use super::preview::preview_option_raw::PreviewOptionRaw;
 use super::tab::TabOption;
@@ -40,6 +41,8 @@ pub struct AppConfigRaw {
     pub search_options: SearchOption,
     #[serde(default, rename = "tab")]
     pub tab_options: TabOption,
+    #[serde(default, rename = "dir_settings")]
+    pub dir_settings_options: DirSettingsOptionRaw,
     #[serde(default)]
     pub custom_commands: Vec<CustomCommand>,
     #[serde(default = "default_shell")]
@@ -0,0 +1,22 @@
+use serde::Deserialize;
+
+use crate::types::option::dir_settings::DirSettings;
+
+/// TOML-deserializable form of
+/// [`DirSettingsOption`](crate::types::option::dir_settings::DirSettingsOption).
+#[derive(Clone, Debug, Default, Deserialize)]
+pub struct DirSettingsOptionRaw {
+    #[serde(default)]
+    pub remember: bool,
+    #[serde(default)]
+    pub rules: Vec<DirSettingsRuleRaw>,
+}
+
+/// TOML-deserializable form of
+/// [`DirSettingsRule`](crate::types::option::dir_settings::DirSettingsRule).
+#[derive(Clone, Debug, Deserialize)]
+pub struct DirSettingsRuleRaw {
+    pub glob: String,
+    #[serde(flatten)]
+    pub settings: DirSettings,
+}
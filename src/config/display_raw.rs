@@ -1,8 +1,11 @@
 use serde::Deserialize;
 
 use crate::{
-    types::option::line_mode::{LineMode, LineNumberStyle},
-    utils::serde::{default_mode, default_scroll_offset, default_true, deserialize_line_mode},
+    types::option::line_mode::{CustomColumn, LineMode, LineNumberStyle},
+    utils::serde::{
+        default_fs_usage_warning, default_mode, default_scroll_offset, default_true,
+        deserialize_line_mode,
+    },
 };
 
 use super::sort_option_raw::SortOptionRaw;
@@ -24,9 +27,19 @@ pub struct DisplayOptionRaw {
     pub show_borders: bool,
     #[serde(default = "default_true")]
     pub show_hostname: bool,
+    #[serde(default = "default_true")]
+    pub show_fs_info: bool,
+    #[serde(default = "default_true")]
+    pub show_git_status: bool,
+    #[serde(default = "default_fs_usage_warning")]
+    pub fs_usage_warning: u8,
     #[serde(default)]
     pub show_hidden: bool,
     #[serde(default)]
+    pub hidden_patterns: Vec<String>,
+    #[serde(default = "default_true")]
+    pub show_ignored: bool,
+    #[serde(default)]
     pub show_icons: bool,
     #[serde(default, rename = "sort")]
     pub sort_options: SortOptionRaw,
@@ -34,6 +47,8 @@ pub struct DisplayOptionRaw {
     pub line_number_style: LineNumberStyle,
     #[serde(default, deserialize_with = "deserialize_line_mode")]
     pub linemode: LineMode,
+    #[serde(default)]
+    pub custom_columns: Vec<CustomColumn>,
 }
 
 impl std::default::Default for DisplayOptionRaw {
@@ -46,11 +61,17 @@ impl std::default::Default for DisplayOptionRaw {
             scroll_offset: 4,
             show_borders: true,
             show_hostname: true,
+            show_fs_info: true,
+            show_git_status: true,
+            fs_usage_warning: default_fs_usage_warning(),
             show_hidden: false,
+            hidden_patterns: Vec::new(),
+            show_ignored: true,
             show_icons: false,
             sort_options: SortOptionRaw::default(),
             line_number_style: LineNumberStyle::default(),
             linemode: LineMode::default(),
+            custom_columns: Vec::new(),
         }
     }
 }
@@ -3,6 +3,7 @@
 pub mod app;
 pub mod app_raw;
 pub mod bookmarks;
+pub mod dir_settings_raw;
 pub mod display_raw;
 pub mod icon;
 pub mod icon_raw;
@@ -4,7 +4,7 @@ use toml::Value;
 
 use crate::types::option::preview::{PreviewProtocol, XDGThumbSizes};
 
-use crate::utils::serde::{default_max_preview_size, default_true};
+use crate::utils::serde::{default_max_preview_size, default_syntax_theme, default_true};
 
 /// TOML-deserializable form of [`PreviewOption`](crate::types::option::preview::PreviewOption).
 #[derive(Clone, Debug, Deserialize)]
@@ -18,6 +18,10 @@ pub struct PreviewOptionRaw {
     pub preview_protocol: PreviewProtocol,
     #[serde(default)]
     pub preview_script: Option<String>,
+    #[serde(default)]
+    pub preview_archives: bool,
+    #[serde(default = "default_syntax_theme")]
+    pub syntax_theme: String,
     #[serde(default = "default_true")]
     pub use_xdg_thumbs: bool,
     #[serde(default)]
@@ -35,6 +39,8 @@ impl std::default::Default for PreviewOptionRaw {
             max_preview_size: default_max_preview_size(),
             preview_protocol: PreviewProtocol::Auto,
             preview_script: None,
+            preview_archives: false,
+            syntax_theme: default_syntax_theme(),
             use_xdg_thumbs: true,
             xdg_thumb_size: XDGThumbSizes::XLarge,
             preview_shown_hook_script: None,
@@ -0,0 +1,49 @@
+use ratatui::style::{Color, Style};
+
+use crate::git::GitStatus;
+
+use super::git_raw::GitThemeRaw;
+use super::style_raw::AppStyleOptionsRaw;
+
+/// Style overrides applied to entries by their git status, on top of their file-type style.
+/// Unset fields leave the underlying style untouched.
+#[derive(Clone, Debug, Default)]
+pub struct GitTheme {
+    pub modified: Style,
+    pub staged: Style,
+    pub untracked: Style,
+    pub ignored: Style,
+    pub conflicted: Style,
+    /// style of the current branch in the footer
+    pub branch: Style,
+}
+
+impl GitTheme {
+    /// Returns the style override for entries with `status`.
+    pub fn style(&self, status: GitStatus) -> Style {
+        match status {
+            GitStatus::Modified => self.modified,
+            GitStatus::Staged => self.staged,
+            GitStatus::Untracked => self.untracked,
+            GitStatus::Ignored => self.ignored,
+            GitStatus::Conflicted => self.conflicted,
+        }
+    }
+}
+
+impl From<GitThemeRaw> for GitTheme {
+    fn from(raw: GitThemeRaw) -> Self {
+        let style = |s: Option<AppStyleOptionsRaw>| s.map(|s| s.as_style()).unwrap_or_default();
+        Self {
+            modified: style(raw.modified),
+            staged: style(raw.staged),
+            untracked: style(raw.untracked),
+            ignored: style(raw.ignored),
+            conflicted: style(raw.conflicted),
+            branch: raw
+                .branch
+                .map(|s| s.as_style())
+                .unwrap_or_else(|| Style::default().fg(Color::Magenta)),
+        }
+    }
+}
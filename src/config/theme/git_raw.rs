@@ -0,0 +1,14 @@
+use serde::{Deserialize, Serialize};
+
+use super::style_raw::AppStyleOptionsRaw;
+
+/// TOML-deserializable form of [`GitTheme`](super::git::GitTheme).
+#[derive(Clone, Debug, Deserialize, Serialize, Default)]
+pub struct GitThemeRaw {
+    pub modified: Option<AppStyleOptionsRaw>,
+    pub staged: Option<AppStyleOptionsRaw>,
+    pub untracked: Option<AppStyleOptionsRaw>,
+    pub ignored: Option<AppStyleOptionsRaw>,
+    pub conflicted: Option<AppStyleOptionsRaw>,
+    pub branch: Option<AppStyleOptionsRaw>,
+}
@@ -1,5 +1,7 @@
 //! Color and style theme, loaded from `theme.toml`.
 
+pub mod git;
+pub mod git_raw;
 pub mod style;
 pub mod style_raw;
 pub mod tab;
@@ -9,13 +11,14 @@ pub mod theme_raw;
 use std::collections::HashMap;
 
 use lscolors::LsColors;
-use ratatui::style::Color;
+use ratatui::style::{Color, Modifier, Style};
 
 use crate::constants::config::THEME_CONFIG;
 use crate::error::AppResult;
 use crate::traits::config::TomlConfigFile;
 use crate::types::config_type::ConfigType;
 
+use git::GitTheme;
 use style::AppStyle;
 use tab::TabTheme;
 use theme_raw::AppThemeRaw;
@@ -35,7 +38,14 @@ pub struct AppTheme {
     pub link_invalid: AppStyle,
     pub socket: AppStyle,
     pub border: AppStyle,
+    pub git: GitTheme,
+    /// style of the tag marker drawn left of tagged entries
+    pub tag: AppStyle,
+    /// style overrides of entries by tag character
+    pub tag_colors: HashMap<char, Style>,
     pub ext: HashMap<String, AppStyle>,
+    /// style of the free space in the footer once the filesystem is nearly full
+    pub fs_warning: Style,
     pub lscolors: Option<LsColors>,
     pub preview_background: Color,
 }
@@ -76,6 +86,19 @@ impl From<AppThemeRaw> for AppTheme {
         let link_invalid = raw.link_invalid.to_style_theme();
         let socket = raw.socket.to_style_theme();
         let border = raw.border.to_style_theme();
+        let git = GitTheme::from(raw.git);
+        let tag = raw.tag.to_style_theme();
+        let tag_colors: HashMap<char, Style> = raw
+            .tag_colors
+            .iter()
+            .filter_map(|(k, v)| {
+                let mut chars = k.chars();
+                match (chars.next(), chars.next()) {
+                    (Some(c), None) => Some((c, v.as_style())),
+                    _ => None,
+                }
+            })
+            .collect();
         let ext: HashMap<String, AppStyle> = raw
             .ext
             .iter()
@@ -91,6 +114,10 @@ impl From<AppThemeRaw> for AppTheme {
         } else {
             None
         };
+        let fs_warning = raw
+            .fs_warning
+            .map(|s| s.as_style())
+            .unwrap_or_else(|| Style::default().fg(Color::Red).add_modifier(Modifier::BOLD));
         let preview_background = AppStyleRaw::str_to_color(&raw.preview_background);
 
         Self {
@@ -103,7 +130,11 @@ impl From<AppThemeRaw> for AppTheme {
             link_invalid,
             socket,
             border,
+            git,
+            tag,
+            tag_colors,
             ext,
+            fs_warning,
             tabs: TabTheme::from(tabs),
             lscolors,
             preview_background,
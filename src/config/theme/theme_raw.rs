@@ -2,7 +2,11 @@ use std::collections::HashMap;
 
 use serde::{Deserialize, Serialize};
 
-use super::{style_raw::AppStyleRaw, tab_raw::TabThemeRaw};
+use super::{
+    git_raw::GitThemeRaw,
+    style_raw::{AppStyleOptionsRaw, AppStyleRaw},
+    tab_raw::TabThemeRaw,
+};
 
 /// TOML-deserializable form of [`AppTheme`](super::AppTheme).
 #[derive(Clone, Debug, Deserialize, Serialize, Default)]
@@ -28,8 +32,16 @@ pub struct AppThemeRaw {
     #[serde(default)]
     pub border: AppStyleRaw,
     #[serde(default)]
+    pub git: GitThemeRaw,
+    #[serde(default)]
+    pub tag: AppStyleRaw,
+    #[serde(default)]
+    pub tag_colors: HashMap<String, AppStyleOptionsRaw>,
+    #[serde(default)]
     pub ext: HashMap<String, AppStyleRaw>,
     #[serde(default)]
+    pub fs_warning: Option<AppStyleOptionsRaw>,
+    #[serde(default)]
     pub lscolors_enabled: bool,
     #[serde(default)]
     pub preview_background: String,
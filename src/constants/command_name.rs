@@ -40,7 +40,14 @@ cmd_constants![
     (CMD_COPY_DIRECTORY_PATH, "copy_dirpath"),
     (CMD_SYMLINK_FILES, "symlink_files"),
     (CMD_PASTE_FILES, "paste_files"),
+    (CMD_EXTRACT, "extract"),
+    (CMD_COMPRESS, "compress"),
     (CMD_DELETE_FILES, "delete_files"),
+    (CMD_GIT_STAGE, "git_stage"),
+    (CMD_GIT_UNSTAGE, "git_unstage"),
+    (CMD_GIT_RESTORE, "git_restore"),
+    (CMD_GIT_DIFF, "git_diff"),
+    (CMD_CD_GIT_ROOT, "cd_git_root"),
     (CMD_CURSOR_MOVE_UP, "cursor_move_up"),
     (CMD_CURSOR_MOVE_DOWN, "cursor_move_down"),
     (CMD_CURSOR_MOVE_HOME, "cursor_move_home"),
@@ -55,6 +62,7 @@ cmd_constants![
     (CMD_PREVIEW_CURSOR_MOVE_UP, "preview_cursor_move_up"),
     (CMD_PREVIEW_CURSOR_MOVE_DOWN, "preview_cursor_move_down"),
     (CMD_NEW_DIRECTORY, "mkdir"),
+    (CMD_NEW_FROM_TEMPLATE, "new_from_template"),
     (CMD_OPEN_FILE, "open"),
     (CMD_OPEN_FILE_WITH, "open_with"),
     (CMD_RELOAD_DIRECTORY_LIST, "reload_dirlist"),
@@ -74,8 +82,11 @@ cmd_constants![
     (CMD_SELECT_STRING, "select"),
     (CMD_SET_CASE_SENSITIVITY, "set_case_sensitivity"),
     (CMD_SET_MODE, "set_mode"),
+    (CMD_SET_TIME, "set_time"),
     (CMD_SORT, "sort"),
     (CMD_SORT_REVERSE, "sort reverse"),
+    (CMD_SORT_RESHUFFLE, "sort reshuffle"),
+    (CMD_SORT_MENU, "sort_menu"),
     (CMD_SUBPROCESS_INTERACTIVE, "shell"),
     (CMD_SUBPROCESS_SPAWN, "spawn"),
     (CMD_SUBPROCESS_CAPTURE, "capture"),
@@ -84,6 +95,21 @@ cmd_constants![
     (CMD_TAB_SWITCH, "tab_switch"),
     (CMD_TAB_SWITCH_INDEX, "tab_switch_index"),
     (CMD_TOGGLE_HIDDEN, "toggle_hidden"),
+    (CMD_TOGGLE_IGNORED, "toggle_ignored"),
+    (CMD_HIDE_PATTERN, "hide_pattern"),
+    (CMD_UNHIDE_PATTERN, "unhide_pattern"),
+    (CMD_FOLLOW_LINK, "follow_link"),
+    (CMD_EDIT_LINK, "edit_link"),
+    (CMD_LINK_MAKE_RELATIVE, "link_make_relative"),
+    (CMD_LINK_MAKE_ABSOLUTE, "link_make_absolute"),
+    (CMD_FIND_BROKEN_LINKS, "find_broken_links"),
+    (CMD_TAG_TOGGLE, "tag_toggle"),
+    (CMD_SELECT_TAG, "select_tag"),
+    (CMD_FILTER_TAG, "filter_tag"),
+    (CMD_CLOSE_VIRTUAL, "close_virtual"),
+    (CMD_FIND, "find"),
+    (CMD_FIND_CANCEL, "find_cancel"),
+    (CMD_GREP, "grep"),
     (CMD_TOGGLE_VISUAL, "toggle_visual"),
     (CMD_SWITCH_LINE_NUMBERS, "line_nums"),
     (CMD_SET_DISPLAY_MODE, "set_display_mode"),
@@ -107,4 +133,9 @@ cmd_constants![
     (CMD_CUSTOM_SEARCH_INTERACTIVE, "custom_search_interactive"),
     (CMD_SIGNAL_SUSPEND, "suspend"),
     (CMD_CALCULATE_CUMULATIVE_SIZE, "calculate_cumulative_size"),
+    (CMD_CANCEL_CUMULATIVE_SIZE, "cancel_cumulative_size"),
+    (CMD_DISK_USAGE, "disk_usage"),
+    (CMD_TREE_EXPAND, "tree_expand"),
+    (CMD_TREE_COLLAPSE, "tree_collapse"),
+    (CMD_TREE_TOGGLE, "tree_toggle"),
 ];
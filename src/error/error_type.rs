@@ -1,4 +1,4 @@
-use std::convert::From;
+use std::convert::{From, Infallible};
 use std::io;
 
 use super::AppErrorKind;
@@ -39,6 +39,12 @@ impl std::fmt::Display for AppError {
     }
 }
 
+impl From<Infallible> for AppError {
+    fn from(err: Infallible) -> Self {
+        match err {}
+    }
+}
+
 impl From<io::Error> for AppError {
     fn from(err: io::Error) -> Self {
         let cause = err.to_string();
@@ -0,0 +1,633 @@
+use std::collections::{BTreeMap, HashMap, HashSet};
+use std::ffi::OsStr;
+use std::fs;
+use std::io::{self, BufReader, Read, Seek};
+use std::ops::Bound;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::fs::{symlink, PermissionsExt};
+use std::path::{Component, Path, PathBuf};
+use std::process::{Child, ChildStdout, Command, Stdio};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use chrono::{Local, NaiveDate, TimeZone};
+use lazy_static::lazy_static;
+
+use super::FileType;
+
+lazy_static! {
+    /// Indexes of the archives read so far, by archive path.
+    static ref INDEXES: Mutex<HashMap<PathBuf, Arc<ArchiveIndex>>> = Mutex::new(HashMap::new());
+}
+
+/// The archive formats that can be browsed as directories.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveKind {
+    Zip,
+    Tar,
+    TarGz,
+    TarXz,
+    TarZst,
+}
+
+/// File name extensions of archives, and the formats they stand for.
+const EXTENSIONS: &[(&str, ArchiveKind)] = &[
+    (".zip", ArchiveKind::Zip),
+    (".tar", ArchiveKind::Tar),
+    (".tar.gz", ArchiveKind::TarGz),
+    (".tgz", ArchiveKind::TarGz),
+    (".tar.xz", ArchiveKind::TarXz),
+    (".txz", ArchiveKind::TarXz),
+    (".tar.zst", ArchiveKind::TarZst),
+    (".tzst", ArchiveKind::TarZst),
+];
+
+impl ArchiveKind {
+    /// Recognizes an archive by the extension of `path`'s file name, ignoring case.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        Self::split_file_name(path).map(|(_, kind)| kind)
+    }
+
+    /// Returns `path`'s file name without its archive extension, if it has one.
+    pub fn file_stem(path: &Path) -> Option<&OsStr> {
+        Self::split_file_name(path).map(|(stem, _)| stem)
+    }
+
+    fn split_file_name(path: &Path) -> Option<(&OsStr, Self)> {
+        let name = path.file_name()?.as_bytes();
+        EXTENSIONS.iter().find_map(|(ext, kind)| {
+            let stem_len = name.len().checked_sub(ext.len()).filter(|len| *len > 0)?;
+            name[stem_len..]
+                .eq_ignore_ascii_case(ext.as_bytes())
+                .then(|| (OsStr::from_bytes(&name[..stem_len]), *kind))
+        })
+    }
+}
+
+/// An entry of an archive.
+#[derive(Clone, Debug)]
+pub struct ArchiveEntry {
+    /// `Directory`, `File` or `Link`
+    pub file_type: FileType,
+    /// uncompressed size in bytes
+    pub len: u64,
+    pub modified: SystemTime,
+    /// permission bits
+    pub mode: u32,
+    /// the target, if a symlink and known
+    pub link_target: Option<PathBuf>,
+}
+
+impl ArchiveEntry {
+    fn directory(modified: SystemTime) -> Self {
+        Self {
+            file_type: FileType::Directory,
+            len: 0,
+            modified,
+            mode: 0o755,
+            link_target: None,
+        }
+    }
+}
+
+/// The entries of an archive, by their path inside it. Directories not stored in the archive
+/// themselves but containing entries are included.
+#[derive(Debug)]
+pub struct ArchiveIndex {
+    /// the modification time of the archive file when it was read
+    modified: SystemTime,
+    entries: BTreeMap<PathBuf, ArchiveEntry>,
+}
+
+impl ArchiveIndex {
+    /// Reads the index of the `kind` archive at `path`.
+    pub fn read(path: &Path, kind: ArchiveKind) -> io::Result<Self> {
+        let modified = fs::metadata(path)?.modified()?;
+        let mut index = Self {
+            modified,
+            entries: BTreeMap::new(),
+        };
+        match kind {
+            ArchiveKind::Zip => index.read_zip(fs::File::open(path)?)?,
+            _ => index.read_tar(open_tar(path, kind)?)?,
+        }
+        Ok(index)
+    }
+
+    fn read_zip<R: Read + Seek>(&mut self, reader: R) -> io::Result<()> {
+        let mut archive = zip::ZipArchive::new(reader)?;
+        for i in 0..archive.len() {
+            // raw, so listing doesn't need passwords of encrypted entries
+            let mut file = archive.by_index_raw(i)?;
+            let Some(path) = file.enclosed_name().and_then(|p| sanitize(&p)) else {
+                continue;
+            };
+            let read_link = file.compression() == zip::CompressionMethod::Stored;
+            let entry = zip_entry(&mut file, self.modified, read_link)?;
+            self.insert(path, entry);
+        }
+        Ok(())
+    }
+
+    fn read_tar<R: Read>(&mut self, reader: R) -> io::Result<()> {
+        let mut archive = tar::Archive::new(reader);
+        for entry in archive.entries()? {
+            let entry = entry?;
+            let Some(path) = sanitize(&entry.path()?) else {
+                continue;
+            };
+            if let Some(entry) = tar_entry(&entry, self.modified)? {
+                self.insert(path, entry);
+            }
+        }
+        Ok(())
+    }
+
+    /// Adds `entry` at `path`, along with its parent directories if not known yet.
+    fn insert(&mut self, path: PathBuf, entry: ArchiveEntry) {
+        for ancestor in path.ancestors().skip(1) {
+            if ancestor.as_os_str().is_empty() {
+                break;
+            }
+            self.entries
+                .entry(ancestor.to_path_buf())
+                .or_insert_with(|| ArchiveEntry::directory(entry.modified));
+        }
+        self.entries.insert(path, entry);
+    }
+
+    /// Returns the modification time of the archive file when it was read.
+    pub fn modified(&self) -> SystemTime {
+        self.modified
+    }
+
+    /// Returns the entry at `path` inside the archive, if any.
+    pub fn get(&self, path: &Path) -> Option<&ArchiveEntry> {
+        self.entries.get(path)
+    }
+
+    /// Iterates over the entries below `dir` (empty for the archive's root), in path order, down
+    /// to `depth` levels below its immediate contents.
+    pub fn entries_below<'a>(
+        &'a self,
+        dir: &'a Path,
+        depth: usize,
+    ) -> impl Iterator<Item = (&'a PathBuf, &'a ArchiveEntry)> + 'a {
+        let max_components = dir
+            .components()
+            .count()
+            .saturating_add(depth)
+            .saturating_add(1);
+        self.entries
+            .range::<Path, _>((Bound::Excluded(dir), Bound::Unbounded))
+            .take_while(move |(path, _)| path.starts_with(dir))
+            .filter(move |(path, _)| path.components().count() <= max_components)
+    }
+}
+
+/// Returns the index of the `kind` archive at `path`, reading it anew if the archive has been
+/// modified since it was last read.
+pub fn archive_index(path: &Path, kind: ArchiveKind) -> io::Result<Arc<ArchiveIndex>> {
+    let modified = fs::metadata(path)?.modified()?;
+    let cached = INDEXES
+        .lock()
+        .ok()
+        .and_then(|indexes| indexes.get(path).cloned());
+    if let Some(index) = cached.filter(|index| index.modified == modified) {
+        return Ok(index);
+    }
+
+    let index = Arc::new(ArchiveIndex::read(path, kind)?);
+    if let Ok(mut indexes) = INDEXES.lock() {
+        indexes.insert(path.to_path_buf(), index.clone());
+    }
+    Ok(index)
+}
+
+/// Progress of [`extract`] and [`compress`](super::compress), reported as each entry is
+/// written.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ArchiveProgress<'a> {
+    /// The entry at this path is started.
+    Entry(&'a Path),
+    /// This many more bytes of the entry were written. Directories and symlinks count as 1 byte.
+    Bytes(u64),
+    /// The entry is complete.
+    Done,
+}
+
+/// Number of bytes read by a [`ProgressReader`] between two reports.
+const PROGRESS_STEP: u64 = 1 << 20;
+
+/// Wraps a reader, reporting the bytes read through it as [`ArchiveProgress::Bytes`] every
+/// [`PROGRESS_STEP`] bytes and at the end of the input.
+pub struct ProgressReader<'a, R, F> {
+    inner: R,
+    progress: &'a mut F,
+    unreported: u64,
+}
+
+impl<'a, R: Read, F: FnMut(ArchiveProgress)> ProgressReader<'a, R, F> {
+    pub fn new(inner: R, progress: &'a mut F) -> Self {
+        Self {
+            inner,
+            progress,
+            unreported: 0,
+        }
+    }
+}
+
+impl<R: Read, F: FnMut(ArchiveProgress)> Read for ProgressReader<'_, R, F> {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.inner.read(buf)?;
+        self.unreported += len as u64;
+        if self.unreported > 0 && (len == 0 || self.unreported >= PROGRESS_STEP) {
+            (self.progress)(ArchiveProgress::Bytes(self.unreported));
+            self.unreported = 0;
+        }
+        Ok(len)
+    }
+}
+
+/// Extracts `inner`, the path of an entry inside the `kind` archive at `path` (empty for the
+/// whole archive), with everything below it, to `dest`. `progress` is called as each entry
+/// is extracted, with paths below `path`.
+///
+/// Entries below a symlink extracted are skipped, so that they can't be written outside `dest`.
+pub fn extract<F>(
+    path: &Path,
+    kind: ArchiveKind,
+    inner: &Path,
+    dest: &Path,
+    progress: F,
+) -> io::Result<()>
+where
+    F: FnMut(ArchiveProgress),
+{
+    let mut extraction = Extraction {
+        archive: path,
+        inner,
+        dest,
+        created: HashSet::new(),
+        links: Vec::new(),
+        progress,
+    };
+    let fallback_time = fs::metadata(path)?.modified()?;
+    match kind {
+        ArchiveKind::Zip => {
+            let mut archive = zip::ZipArchive::new(fs::File::open(path)?)?;
+            for i in 0..archive.len() {
+                let mut file = archive.by_index(i)?;
+                let Some(entry_path) = file.enclosed_name().and_then(|p| sanitize(&p)) else {
+                    continue;
+                };
+                if entry_path.starts_with(inner) {
+                    let entry = zip_entry(&mut file, fallback_time, true)?;
+                    extraction.write(&entry_path, &entry, &mut file)?;
+                }
+            }
+        }
+        _ => {
+            let mut archive = tar::Archive::new(open_tar(path, kind)?);
+            for entry in archive.entries()? {
+                let mut entry = entry?;
+                let Some(entry_path) = sanitize(&entry.path()?) else {
+                    continue;
+                };
+                if !entry_path.starts_with(inner) {
+                    continue;
+                }
+                if let Some(archive_entry) = tar_entry(&entry, fallback_time)? {
+                    extraction.write(&entry_path, &archive_entry, &mut entry)?;
+                }
+            }
+        }
+    }
+    Ok(())
+}
+
+/// The state of an [`extract`] in progress.
+struct Extraction<'a, F> {
+    archive: &'a Path,
+    inner: &'a Path,
+    dest: &'a Path,
+    /// the directories created so far, relative to `dest`
+    created: HashSet<PathBuf>,
+    /// the symlinks extracted so far, relative to `dest`
+    links: Vec<PathBuf>,
+    progress: F,
+}
+
+impl<F: FnMut(ArchiveProgress)> Extraction<'_, F> {
+    /// Writes the entry at `path` inside the archive, with `contents`, below `dest`, creating
+    /// its parent directories first.
+    fn write(
+        &mut self,
+        path: &Path,
+        entry: &ArchiveEntry,
+        contents: &mut dyn Read,
+    ) -> io::Result<()> {
+        let Ok(rel) = path.strip_prefix(self.inner) else {
+            return Ok(());
+        };
+        if self.links.iter().any(|link| rel.starts_with(link)) {
+            return Ok(());
+        }
+        let parents: Vec<&Path> = rel.ancestors().skip(1).collect();
+        for dir in parents.into_iter().rev() {
+            self.create_dir(dir)?;
+        }
+
+        let target = join(self.dest, rel);
+        match entry.file_type {
+            FileType::Directory => self.create_dir(rel)?,
+            FileType::Link => {
+                if let Some(link_target) = entry.link_target.as_ref() {
+                    symlink(link_target, &target)?;
+                }
+                self.links.push(rel.to_path_buf());
+                report_whole(&mut self.progress, &join(self.archive, path));
+            }
+            _ => {
+                let entry_path = join(self.archive, path);
+                (self.progress)(ArchiveProgress::Entry(&entry_path));
+                let mut file = fs::File::create(&target)?;
+                io::copy(
+                    &mut ProgressReader::new(contents, &mut self.progress),
+                    &mut file,
+                )?;
+                file.set_permissions(fs::Permissions::from_mode(entry.mode))?;
+                let _ = file.set_modified(entry.modified);
+                (self.progress)(ArchiveProgress::Done);
+            }
+        }
+        Ok(())
+    }
+
+    /// Creates the directory at `rel` below `dest`, unless already done.
+    fn create_dir(&mut self, rel: &Path) -> io::Result<()> {
+        if self.created.contains(rel) {
+            return Ok(());
+        }
+        let target = join(self.dest, rel);
+        match fs::create_dir(&target) {
+            Err(e) if !(e.kind() == io::ErrorKind::AlreadyExists && target.is_dir()) => {
+                return Err(e)
+            }
+            _ => {}
+        }
+        self.created.insert(rel.to_path_buf());
+        // the root of the archive isn't an entry
+        let path = join(self.inner, rel);
+        if !path.as_os_str().is_empty() {
+            report_whole(&mut self.progress, &join(self.archive, &path));
+        }
+        Ok(())
+    }
+}
+
+/// Reports the directory or symlink at `path` as written, counting as 1 byte.
+pub fn report_whole<F: FnMut(ArchiveProgress)>(progress: &mut F, path: &Path) {
+    progress(ArchiveProgress::Entry(path));
+    progress(ArchiveProgress::Bytes(1));
+    progress(ArchiveProgress::Done);
+}
+
+/// Joins `path` to `base`, without adding a trailing separator if `path` is empty.
+fn join(base: &Path, path: &Path) -> PathBuf {
+    if path.as_os_str().is_empty() {
+        base.to_path_buf()
+    } else {
+        base.join(path)
+    }
+}
+
+/// Makes the path of an archive entry relative, leaving out `.` components and a leading `/`.
+/// Returns `None` for paths escaping the archive with `..`, and empty ones.
+fn sanitize(path: &Path) -> Option<PathBuf> {
+    let mut clean = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::Normal(name) => clean.push(name),
+            Component::CurDir | Component::RootDir => {}
+            Component::ParentDir | Component::Prefix(_) => return None,
+        }
+    }
+    (!clean.as_os_str().is_empty()).then_some(clean)
+}
+
+/// Opens the tar archive at `path`, decompressing it as `kind` requires.
+fn open_tar(path: &Path, kind: ArchiveKind) -> io::Result<Box<dyn Read>> {
+    let file = BufReader::new(fs::File::open(path)?);
+    Ok(match kind {
+        ArchiveKind::TarGz => Box::new(flate2::bufread::MultiGzDecoder::new(file)),
+        ArchiveKind::TarXz => Box::new(XzDecoder::new(path)?),
+        ArchiveKind::TarZst => Box::new(zstd::stream::read::Decoder::with_buffer(file)?),
+        _ => Box::new(file),
+    })
+}
+
+/// Decompresses an xz file by running `xz`, as there is no decoder built in.
+struct XzDecoder {
+    child: Child,
+    stdout: ChildStdout,
+}
+
+impl XzDecoder {
+    fn new(path: &Path) -> io::Result<Self> {
+        let mut child = Command::new("xz")
+            .args(["--decompress", "--stdout", "--"])
+            .arg(path)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("xz: {e}")))?;
+        let stdout = child.stdout.take().expect("stdout is piped");
+        Ok(Self { child, stdout })
+    }
+}
+
+impl Read for XzDecoder {
+    fn read(&mut self, buf: &mut [u8]) -> io::Result<usize> {
+        let len = self.stdout.read(buf)?;
+        // the end of the output, make sure it wasn't cut short by an error
+        if len == 0 && !buf.is_empty() && !self.child.wait()?.success() {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidData,
+                "xz: failed to decompress",
+            ));
+        }
+        Ok(len)
+    }
+}
+
+impl Drop for XzDecoder {
+    fn drop(&mut self) {
+        // readers may stop before the end of the archive
+        let _ = self.child.kill();
+        let _ = self.child.wait();
+    }
+}
+
+/// Describes a tar entry. Returns `None` for hard links and special files, which aren't listed.
+fn tar_entry<R: Read>(
+    entry: &tar::Entry<'_, R>,
+    fallback_time: SystemTime,
+) -> io::Result<Option<ArchiveEntry>> {
+    let header = entry.header();
+    let entry_type = header.entry_type();
+    let file_type = if entry_type.is_dir() {
+        FileType::Directory
+    } else if entry_type.is_symlink() {
+        FileType::Link
+    } else if entry_type.is_file() {
+        FileType::File
+    } else {
+        return Ok(None);
+    };
+    let modified = header
+        .mtime()
+        .map_or(fallback_time, |t| UNIX_EPOCH + Duration::from_secs(t));
+    let len = match file_type {
+        FileType::File => entry.size(),
+        _ => 0,
+    };
+    Ok(Some(ArchiveEntry {
+        file_type,
+        len,
+        modified,
+        mode: header.mode().unwrap_or(0o644) & 0o7777,
+        link_target: entry.link_name()?.map(|p| p.into_owned()),
+    }))
+}
+
+/// Describes a zip entry, reading a symlink's target from its contents if `read_link`.
+fn zip_entry(
+    file: &mut zip::read::ZipFile<'_>,
+    fallback_time: SystemTime,
+    read_link: bool,
+) -> io::Result<ArchiveEntry> {
+    let file_type = if file.is_dir() {
+        FileType::Directory
+    } else if file.is_symlink() {
+        FileType::Link
+    } else {
+        FileType::File
+    };
+    let modified = file
+        .last_modified()
+        .and_then(zip_time)
+        .unwrap_or(fallback_time);
+    let default_mode = match file_type {
+        FileType::Directory => 0o755,
+        _ => 0o644,
+    };
+    let mode = file.unix_mode().map_or(default_mode, |mode| mode & 0o7777);
+    let link_target = if file_type == FileType::Link && read_link {
+        let mut target = String::new();
+        file.read_to_string(&mut target)?;
+        Some(PathBuf::from(target))
+    } else {
+        None
+    };
+    Ok(ArchiveEntry {
+        file_type,
+        len: if file_type == FileType::File {
+            file.size()
+        } else {
+            0
+        },
+        modified,
+        mode,
+        link_target,
+    })
+}
+
+/// Converts a zip timestamp, in local time, to a `SystemTime`.
+fn zip_time(time: zip::DateTime) -> Option<SystemTime> {
+    let date = NaiveDate::from_ymd_opt(time.year().into(), time.month().into(), time.day().into())?;
+    let date_time = date.and_hms_opt(
+        time.hour().into(),
+        time.minute().into(),
+        time.second().into(),
+    )?;
+    let secs = Local
+        .from_local_datetime(&date_time)
+        .earliest()?
+        .timestamp();
+    Some(UNIX_EPOCH + Duration::from_secs(u64::try_from(secs).ok()?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn tar_of(files: &[(&str, &str)]) -> Vec<u8> {
+        let mut builder = tar::Builder::new(Vec::new());
+        for (path, contents) in files {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o600);
+            header.set_mtime(1_000_000);
+            header.set_cksum();
+            builder
+                .append_data(&mut header, path, contents.as_bytes())
+                .unwrap();
+        }
+        builder.into_inner().unwrap()
+    }
+
+    #[test]
+    fn test_archive_kind() {
+        let kind = |name: &str| ArchiveKind::from_path(Path::new(name));
+        assert_eq!(kind("/a/b.zip"), Some(ArchiveKind::Zip));
+        assert_eq!(kind("b.TAR"), Some(ArchiveKind::Tar));
+        assert_eq!(kind("b.tar.gz"), Some(ArchiveKind::TarGz));
+        assert_eq!(kind("b.tgz"), Some(ArchiveKind::TarGz));
+        assert_eq!(kind("b.tar.zst"), Some(ArchiveKind::TarZst));
+        assert_eq!(kind("b.txz"), Some(ArchiveKind::TarXz));
+        assert_eq!(kind("b.gz"), None);
+        assert_eq!(kind(".zip"), None);
+
+        let stem = |name: &'static str| ArchiveKind::file_stem(Path::new(name));
+        assert_eq!(stem("/a/b.Tar.Gz"), Some(OsStr::new("b")));
+        assert_eq!(stem("b.c.zip"), Some(OsStr::new("b.c")));
+        assert_eq!(stem("b.txt"), None);
+    }
+
+    #[test]
+    fn test_sanitize() {
+        assert_eq!(sanitize(Path::new("./a/b")), Some(PathBuf::from("a/b")));
+        assert_eq!(sanitize(Path::new("/a")), Some(PathBuf::from("a")));
+        assert_eq!(sanitize(Path::new("a/../../b")), None);
+        assert_eq!(sanitize(Path::new("./")), None);
+    }
+
+    #[test]
+    fn test_tar_index() {
+        let mut index = ArchiveIndex {
+            modified: UNIX_EPOCH,
+            entries: BTreeMap::new(),
+        };
+        let data = tar_of(&[("a/b/c.txt", "hello"), ("a/d.txt", "hi"), ("e.txt", "")]);
+        index.read_tar(data.as_slice()).unwrap();
+
+        let names = |dir: &str, depth| -> Vec<String> {
+            index
+                .entries_below(Path::new(dir), depth)
+                .map(|(path, _)| path.to_string_lossy().to_string())
+                .collect()
+        };
+        assert_eq!(names("", 0), vec!["a", "e.txt"]);
+        assert_eq!(names("a", 0), vec!["a/b", "a/d.txt"]);
+        assert_eq!(names("a", 1), vec!["a/b", "a/b/c.txt", "a/d.txt"]);
+
+        let dir = index.get(Path::new("a/b")).unwrap();
+        assert_eq!(dir.file_type, FileType::Directory);
+        let file = index.get(Path::new("a/b/c.txt")).unwrap();
+        assert_eq!(file.len, 5);
+        assert_eq!(file.mode, 0o600);
+        assert_eq!(file.modified, UNIX_EPOCH + Duration::from_secs(1_000_000));
+    }
+}
@@ -0,0 +1,335 @@
+use std::fs;
+use std::io::{self, BufWriter, Read, Write};
+use std::os::unix::fs::MetadataExt;
+use std::path::{Path, PathBuf};
+use std::process::{Child, ChildStdin, Command, Stdio};
+use std::time::SystemTime;
+
+use chrono::{Datelike, Local, Timelike};
+use uuid::Uuid;
+
+use super::{report_whole, ArchiveKind, ArchiveProgress, ProgressReader};
+
+/// Creates the `kind` archive `dest` of `sources`, with everything below them, each stored under
+/// its file name. `progress` is called as each file is added. Special files are left out.
+///
+/// The archive is written under a temporary name and renamed to `dest` once complete, replacing
+/// any file there.
+pub fn compress<F>(
+    sources: &[PathBuf],
+    kind: ArchiveKind,
+    dest: &Path,
+    progress: F,
+) -> io::Result<()>
+where
+    F: FnMut(ArchiveProgress),
+{
+    let part = dest.with_file_name(format!(".{}.part", Uuid::new_v4()));
+    let res = write_archive(sources, kind, &part, progress).and_then(|_| fs::rename(&part, dest));
+    if res.is_err() {
+        let _ = fs::remove_file(&part);
+    }
+    res
+}
+
+fn write_archive<F>(
+    sources: &[PathBuf],
+    kind: ArchiveKind,
+    dest: &Path,
+    mut progress: F,
+) -> io::Result<()>
+where
+    F: FnMut(ArchiveProgress),
+{
+    let file = fs::File::create_new(dest)?;
+    match kind {
+        ArchiveKind::Zip => {
+            let mut zip = zip::ZipWriter::new(BufWriter::new(file));
+            append_sources(&mut zip, sources, &mut progress)?;
+            zip.finish()?.flush()
+        }
+        _ => {
+            let encoder = match kind {
+                ArchiveKind::TarGz => Encoder::Gz(flate2::write::GzEncoder::new(
+                    BufWriter::new(file),
+                    flate2::Compression::default(),
+                )),
+                ArchiveKind::TarXz => Encoder::Xz(XzEncoder::new(file)?),
+                ArchiveKind::TarZst => {
+                    Encoder::Zst(zstd::stream::write::Encoder::new(BufWriter::new(file), 0)?)
+                }
+                _ => Encoder::Plain(BufWriter::new(file)),
+            };
+            let mut tar = tar::Builder::new(encoder);
+            tar.follow_symlinks(false);
+            append_sources(&mut tar, sources, &mut progress)?;
+            tar.into_inner()?.finish()
+        }
+    }
+}
+
+fn append_sources<A, F>(archive: &mut A, sources: &[PathBuf], progress: &mut F) -> io::Result<()>
+where
+    A: AppendEntry,
+    F: FnMut(ArchiveProgress),
+{
+    for source in sources {
+        if let Some(name) = source.file_name() {
+            append_recursive(archive, source, Path::new(name), progress)?;
+        }
+    }
+    Ok(())
+}
+
+/// Adds `path` to `archive` as `name`, followed by everything below it if it's a directory.
+fn append_recursive<A, F>(
+    archive: &mut A,
+    path: &Path,
+    name: &Path,
+    progress: &mut F,
+) -> io::Result<()>
+where
+    A: AppendEntry,
+    F: FnMut(ArchiveProgress),
+{
+    let metadata = fs::symlink_metadata(path)?;
+    let file_type = metadata.file_type();
+    if !(file_type.is_dir() || file_type.is_file() || file_type.is_symlink()) {
+        return Ok(());
+    }
+    if file_type.is_file() {
+        progress(ArchiveProgress::Entry(path));
+        let file = fs::File::open(path)?.take(metadata.len());
+        archive.append_file(ProgressReader::new(file, progress), name, &metadata)?;
+        progress(ArchiveProgress::Done);
+        return Ok(());
+    }
+    archive.append(path, name, &metadata)?;
+    report_whole(progress, path);
+    if file_type.is_dir() {
+        let mut entries = fs::read_dir(path)?.collect::<io::Result<Vec<_>>>()?;
+        entries.sort_by_key(|entry| entry.file_name());
+        for entry in entries {
+            let entry_name = name.join(entry.file_name());
+            append_recursive(archive, &entry.path(), &entry_name, progress)?;
+        }
+    }
+    Ok(())
+}
+
+/// An archive being written, that directories, files and symlinks can be added to.
+trait AppendEntry {
+    /// Adds the directory or symlink at `path`, with `metadata`, as `name`. Directories are
+    /// added without their contents, and symlinks as symlinks.
+    fn append(&mut self, path: &Path, name: &Path, metadata: &fs::Metadata) -> io::Result<()>;
+
+    /// Adds a file with `metadata` and the `metadata.len()` bytes read from `contents` as
+    /// `name`.
+    fn append_file<R: Read>(
+        &mut self,
+        contents: R,
+        name: &Path,
+        metadata: &fs::Metadata,
+    ) -> io::Result<()>;
+}
+
+impl<W: Write + io::Seek> AppendEntry for zip::ZipWriter<W> {
+    fn append(&mut self, path: &Path, name: &Path, metadata: &fs::Metadata) -> io::Result<()> {
+        if metadata.is_dir() {
+            self.add_directory_from_path(name, zip_options(metadata))?;
+        } else {
+            self.add_symlink_from_path(name, fs::read_link(path)?, zip_options(metadata))?;
+        }
+        Ok(())
+    }
+
+    fn append_file<R: Read>(
+        &mut self,
+        mut contents: R,
+        name: &Path,
+        metadata: &fs::Metadata,
+    ) -> io::Result<()> {
+        self.start_file_from_path(name, zip_options(metadata))?;
+        io::copy(&mut contents, self)?;
+        Ok(())
+    }
+}
+
+/// Returns the options zip entries are written with, keeping the permissions and modification
+/// time in `metadata`.
+fn zip_options(metadata: &fs::Metadata) -> zip::write::SimpleFileOptions {
+    let mut options = zip::write::SimpleFileOptions::default()
+        .compression_method(zip::CompressionMethod::Deflated)
+        .unix_permissions(metadata.mode() & 0o7777)
+        .large_file(metadata.len() >= u64::from(u32::MAX));
+    if let Some(time) = metadata.modified().ok().and_then(to_zip_time) {
+        options = options.last_modified_time(time);
+    }
+    options
+}
+
+impl<W: Write> AppendEntry for tar::Builder<W> {
+    fn append(&mut self, path: &Path, name: &Path, _metadata: &fs::Metadata) -> io::Result<()> {
+        self.append_path_with_name(path, name)
+    }
+
+    fn append_file<R: Read>(
+        &mut self,
+        contents: R,
+        name: &Path,
+        metadata: &fs::Metadata,
+    ) -> io::Result<()> {
+        let mut header = tar::Header::new_gnu();
+        header.set_metadata(metadata);
+        self.append_data(&mut header, name, contents)
+    }
+}
+
+/// Converts a `SystemTime` to a zip timestamp, in local time. Returns `None` for times zip
+/// can't represent, before 1980 or after 2107.
+fn to_zip_time(time: SystemTime) -> Option<zip::DateTime> {
+    let time = chrono::DateTime::<Local>::from(time).naive_local();
+    zip::DateTime::from_date_and_time(
+        u16::try_from(time.year()).ok()?,
+        time.month() as u8,
+        time.day() as u8,
+        time.hour() as u8,
+        time.minute() as u8,
+        // zip only stores every other second
+        (time.second() as u8).min(58),
+    )
+    .ok()
+}
+
+/// The compression of a tar archive being written.
+enum Encoder {
+    Plain(BufWriter<fs::File>),
+    Gz(flate2::write::GzEncoder<BufWriter<fs::File>>),
+    Xz(XzEncoder),
+    Zst(zstd::stream::write::Encoder<'static, BufWriter<fs::File>>),
+}
+
+impl Encoder {
+    /// Writes out what is left of the compressed archive.
+    fn finish(self) -> io::Result<()> {
+        match self {
+            Self::Plain(mut file) => file.flush(),
+            Self::Gz(encoder) => encoder.finish()?.flush(),
+            Self::Xz(encoder) => encoder.finish(),
+            Self::Zst(encoder) => encoder.finish()?.flush(),
+        }
+    }
+}
+
+impl Write for Encoder {
+    fn write(&mut self, buf: &[u8]) -> io::Result<usize> {
+        match self {
+            Self::Plain(file) => file.write(buf),
+            Self::Gz(encoder) => encoder.write(buf),
+            Self::Xz(encoder) => encoder.stdin.write(buf),
+            Self::Zst(encoder) => encoder.write(buf),
+        }
+    }
+
+    fn flush(&mut self) -> io::Result<()> {
+        match self {
+            Self::Plain(file) => file.flush(),
+            Self::Gz(encoder) => encoder.flush(),
+            Self::Xz(encoder) => encoder.stdin.flush(),
+            Self::Zst(encoder) => encoder.flush(),
+        }
+    }
+}
+
+/// Compresses into a file by running `xz`, as there is no encoder built in.
+struct XzEncoder {
+    child: Child,
+    stdin: ChildStdin,
+}
+
+impl XzEncoder {
+    fn new(file: fs::File) -> io::Result<Self> {
+        let mut child = Command::new("xz")
+            .args(["--compress", "--stdout"])
+            .stdin(Stdio::piped())
+            .stdout(file)
+            .stderr(Stdio::null())
+            .spawn()
+            .map_err(|e| io::Error::new(e.kind(), format!("xz: {e}")))?;
+        let stdin = child.stdin.take().expect("stdin is piped");
+        Ok(Self { child, stdin })
+    }
+
+    /// Waits for `xz` to write out the rest of its input.
+    fn finish(self) -> io::Result<()> {
+        let Self { mut child, stdin } = self;
+        drop(stdin);
+        if !child.wait()?.success() {
+            return Err(io::Error::other("xz: failed to compress"));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::fs::{archive_index, extract, FileType};
+
+    #[test]
+    fn test_compress_and_extract() {
+        let dir = std::env::temp_dir().join(format!("joshuto-compress-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("src/sub")).unwrap();
+        fs::write(dir.join("src/a.txt"), "hello").unwrap();
+        fs::write(dir.join("src/sub/b.txt"), "world").unwrap();
+        std::os::unix::fs::symlink("a.txt", dir.join("src/link")).unwrap();
+
+        // .tar.xz archives need the `xz` program
+        let has_xz = Command::new("xz")
+            .arg("--version")
+            .stdout(Stdio::null())
+            .status()
+            .is_ok_and(|status| status.success());
+        let names = [
+            "out.zip",
+            "out.tar",
+            "out.tar.gz",
+            "out.tar.xz",
+            "out.tar.zst",
+        ];
+        for name in names
+            .into_iter()
+            .filter(|name| has_xz || !name.ends_with(".xz"))
+        {
+            let archive = dir.join(name);
+            let kind = ArchiveKind::from_path(&archive).unwrap();
+            let count = |total: &mut u64, progress: ArchiveProgress| {
+                if let ArchiveProgress::Bytes(bytes) = progress {
+                    *total += bytes;
+                }
+            };
+            let mut total = 0;
+            compress(&[dir.join("src")], kind, &archive, |p| count(&mut total, p)).unwrap();
+            // 2 directories and a symlink at 1 byte, and 2 files of 5 bytes
+            assert_eq!(total, 13, "{name}");
+
+            let index = archive_index(&archive, kind).unwrap();
+            let link = index.get(Path::new("src/link")).unwrap();
+            assert_eq!(link.file_type, FileType::Link, "{name}");
+            assert_eq!(link.link_target.as_deref(), Some(Path::new("a.txt")));
+
+            let dest = dir.join(format!("{name}.d"));
+            let mut total = 0;
+            extract(&archive, kind, Path::new(""), &dest, |p| {
+                count(&mut total, p)
+            })
+            .unwrap();
+            assert_eq!(total, 13, "{name}");
+            let contents = fs::read_to_string(dest.join("src/sub/b.txt")).unwrap();
+            assert_eq!(contents, "world", "{name}");
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
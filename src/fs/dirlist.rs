@@ -1,12 +1,29 @@
+use std::cell::Cell;
+use std::cmp;
+use std::collections::{HashMap, HashSet};
 use std::slice::{Iter, IterMut};
+use std::sync::atomic::{AtomicU64, Ordering};
 use std::{io, path};
 
-use crate::fs::{entry::JoshutoDirEntry, metadata::JoshutoMetadata};
-use crate::history::read_directory;
+use uuid::Uuid;
+
+use crate::fs::{
+    entry::JoshutoDirEntry, metadata::JoshutoMetadata, tree_depth, tree_guides, tree_order,
+    IgnoreMatcher,
+};
+use crate::history::{is_path_listed, read_directory, walk_directory};
 use crate::tab::TabDisplayOption;
 use crate::types::option::display::DisplayOption;
+use crate::types::option::sort::SortOption;
 use crate::types::state::UiState;
 
+/// Source of [`JoshutoDirList::version`]s, unique across all listings.
+static NEXT_VERSION: AtomicU64 = AtomicU64::new(0);
+
+fn next_version() -> u64 {
+    NEXT_VERSION.fetch_add(1, Ordering::Relaxed)
+}
+
 /// The contents of a single directory as displayed in a joshuto tab, along with cursor,
 /// viewport, and visual-mode-selection state.
 #[derive(Clone, Debug)]
@@ -21,6 +38,21 @@ pub struct JoshutoDirList {
     /// The index in this dir list where visual mode has started or None if not in visual mode
     pub visual_mode_anchor_index: Option<usize>,
     pub need_update: bool,
+    /// The background load still streaming entries into this dir list, if any
+    pub load_id: Option<Uuid>,
+    /// While a background load refreshes an existing listing, the names of the entries it has
+    /// yet to list again, see [`begin_reload`](Self::begin_reload)
+    stale_names: Option<HashSet<String>>,
+    /// Changed whenever entries are added or their metadata replaced
+    version: u64,
+    /// The indentation guides drawn before each entry in the `tree` display mode, see
+    /// [`update_tree_guides`](Self::update_tree_guides)
+    tree_guides: Vec<String>,
+    /// The number of entries left out for being hidden
+    pub hidden_count: usize,
+    /// The paths listed, if this is a virtual listing of arbitrary paths rather than the
+    /// contents of `path`, see [`virtual_dirlist`](super::virtual_dirlist)
+    pub virtual_paths: Option<Vec<path::PathBuf>>,
 }
 
 impl JoshutoDirList {
@@ -41,6 +73,12 @@ impl JoshutoDirList {
             viewport_index,
             visual_mode_anchor_index,
             need_update: false,
+            load_id: None,
+            stale_names: None,
+            version: next_version(),
+            tree_guides: Vec::new(),
+            hidden_count: 0,
+            virtual_paths: None,
         }
     }
 
@@ -51,7 +89,7 @@ impl JoshutoDirList {
         tab_options: &TabDisplayOption,
     ) -> io::Result<Self> {
         let filter_func = display_options.filter_func();
-        let mut contents =
+        let (mut contents, hidden_count) =
             read_directory(path.as_path(), filter_func, display_options, tab_options)?;
 
         contents.sort_by(|f1, f2| tab_options.sort_options.compare(f1, f2));
@@ -67,9 +105,57 @@ impl JoshutoDirList {
             index,
             viewport_index: index.unwrap_or_default(),
             visual_mode_anchor_index: None,
+            load_id: None,
+            stale_names: None,
+            version: next_version(),
+            tree_guides: Vec::new(),
+            hidden_count,
+            virtual_paths: None,
         })
     }
 
+    /// Returns a number that changes whenever entries are added to the list or have their
+    /// metadata replaced, for values computed lazily for the entries to be looked up again.
+    pub fn version(&self) -> u64 {
+        self.version
+    }
+
+    /// Computes the indentation guides of the entries, which must be in tree order.
+    pub fn update_tree_guides(&mut self) {
+        let depths: Vec<usize> = self.contents.iter().map(tree_depth).collect();
+        self.tree_guides = tree_guides(&depths);
+    }
+
+    /// Returns the indentation guides drawn before the entry at `index` in the `tree` display
+    /// mode, as of the last time the list was put in tree order.
+    pub fn tree_guides(&self, index: usize) -> &str {
+        self.tree_guides.get(index).map_or("", String::as_str)
+    }
+
+    /// Applies `update` to each entry whose path is a key of `values`, with its value, then
+    /// sorts the list once if an entry was updated.
+    pub fn update_entries<V, F>(
+        &mut self,
+        values: &HashMap<path::PathBuf, V>,
+        mut update: F,
+        sort_options: &SortOption,
+        ui_state: &UiState,
+        options: &DisplayOption,
+    ) where
+        F: FnMut(&mut JoshutoDirEntry, &V),
+    {
+        let mut updated = false;
+        for entry in self.contents.iter_mut() {
+            if let Some(value) = values.get(entry.file_path()) {
+                update(entry, value);
+                updated = true;
+            }
+        }
+        if updated {
+            self.sort(sort_options, ui_state, options);
+        }
+    }
+
     /// Returns the current cursor position, if any.
     pub fn get_index(&self) -> Option<usize> {
         self.index
@@ -189,6 +275,312 @@ impl JoshutoDirList {
         self.update_visual_mode_selection();
     }
 
+    /// Starts refreshing this listing from the background load `load_id`, with the directory's
+    /// new `metadata`. The entries already listed are kept, with their selection, as the load
+    /// lists them again, and those it doesn't list are removed by
+    /// [`finish_load`](Self::finish_load).
+    pub fn begin_reload(&mut self, load_id: Uuid, metadata: JoshutoMetadata) {
+        self.load_id = Some(load_id);
+        self.metadata = metadata;
+        self.need_update = false;
+        self.stale_names = Some(self.iter().map(|e| e.file_name().to_string()).collect());
+    }
+
+    /// Completes the background load of this listing: removes the entries it didn't list again
+    /// if it was a reload, and sorts the list now that all metadata is in.
+    pub fn finish_load(
+        &mut self,
+        hidden_count: usize,
+        sort_options: &SortOption,
+        ui_state: &UiState,
+        options: &DisplayOption,
+    ) {
+        self.load_id = None;
+        self.hidden_count = hidden_count;
+        if let Some(stale_names) = self.stale_names.take() {
+            let removed = self
+                .iter()
+                .enumerate()
+                .filter(|(_, e)| stale_names.contains(e.file_name()))
+                .map(|(i, _)| i)
+                .collect();
+            self.remove_indices(removed);
+        }
+        self.sort(sort_options, ui_state, options);
+    }
+
+    /// Merges a batch of newly read `entries` into this already sorted list, keeping it sorted.
+    /// The cursor and visual-mode anchor stay on the entries they were on, except that a cursor
+    /// at the top of a list being loaded for the first time stays at the top. While reloading,
+    /// entries already listed are kept as they are.
+    pub fn merge_sorted(
+        &mut self,
+        mut entries: Vec<JoshutoDirEntry>,
+        sort_options: &SortOption,
+        ui_state: &UiState,
+        options: &DisplayOption,
+    ) {
+        if let Some(stale_names) = self.stale_names.as_mut() {
+            entries.retain(|e| !stale_names.remove(e.file_name()));
+        }
+        entries.sort_by(|f1, f2| sort_options.compare(f1, f2));
+
+        let old_contents = std::mem::take(&mut self.contents);
+        let mut contents = Vec::with_capacity(old_contents.len() + entries.len());
+        let mut index = match self.stale_names {
+            Some(_) => None,
+            None => self.index.filter(|i| *i == 0),
+        };
+        let mut visual_mode_anchor_index = None;
+
+        let mut old_iter = old_contents.into_iter().enumerate().peekable();
+        let mut new_iter = entries.into_iter().peekable();
+        loop {
+            let take_old = match (old_iter.peek(), new_iter.peek()) {
+                (Some((_, f1)), Some(f2)) => sort_options.compare(f1, f2) != cmp::Ordering::Greater,
+                (Some(_), None) => true,
+                (None, Some(_)) => false,
+                (None, None) => break,
+            };
+            if take_old {
+                let (i, entry) = old_iter.next().unwrap();
+                if self.index == Some(i) && index.is_none() {
+                    index = Some(contents.len());
+                }
+                if self.visual_mode_anchor_index == Some(i) {
+                    visual_mode_anchor_index = Some(contents.len());
+                }
+                contents.push(entry);
+            } else {
+                contents.push(new_iter.next().unwrap());
+            }
+        }
+
+        self.contents = contents;
+        self.version = next_version();
+        self.index = match index {
+            None if !self.contents.is_empty() => Some(0),
+            index => index,
+        };
+        self.visual_mode_anchor_index = visual_mode_anchor_index;
+        if !ui_state.layout.is_empty() {
+            self.update_viewport(ui_state, options);
+        }
+        if self.visual_mode_anchor_index.is_some() {
+            self.update_visual_mode_selection();
+        }
+    }
+
+    /// Replaces the placeholder metadata of the entries named in `metadata`, then moves them to
+    /// their sorted position. Entries whose metadata couldn't be read (`None`) have disappeared
+    /// since they were listed and are removed.
+    pub fn update_metadata(
+        &mut self,
+        metadata: Vec<(String, Option<JoshutoMetadata>)>,
+        sort_options: &SortOption,
+        ui_state: &UiState,
+        options: &DisplayOption,
+    ) {
+        let updates: Vec<(usize, Option<JoshutoMetadata>)> = {
+            let positions: HashMap<&str, usize> = self
+                .iter()
+                .enumerate()
+                .map(|(i, entry)| (entry.file_name(), i))
+                .collect();
+            metadata
+                .into_iter()
+                .filter_map(|(name, metadata)| Some((*positions.get(name.as_str())?, metadata)))
+                .collect()
+        };
+
+        self.version = next_version();
+        let mut removed = Vec::new();
+        for (i, metadata) in updates {
+            match metadata {
+                Some(metadata) => self.contents[i].metadata = metadata,
+                None => removed.push(i),
+            }
+        }
+        self.remove_indices(removed);
+        self.sort(sort_options, ui_state, options);
+    }
+
+    /// Removes the entries at the `removed` indices, keeping the cursor and visual-mode anchor
+    /// on the entries they were on where these remain.
+    fn remove_indices(&mut self, mut removed: Vec<usize>) {
+        if removed.is_empty() {
+            return;
+        }
+
+        removed.sort_unstable();
+        let shift = |i: usize| i - removed.partition_point(|r| *r < i);
+        let mut i = 0;
+        self.contents.retain(|_| {
+            i += 1;
+            removed.binary_search(&(i - 1)).is_err()
+        });
+        self.index = match self.index.map(shift) {
+            Some(_) if self.contents.is_empty() => None,
+            Some(i) => Some(i.min(self.contents.len() - 1)),
+            None => None,
+        };
+        self.visual_mode_anchor_index = self
+            .visual_mode_anchor_index
+            .map(shift)
+            .filter(|i| *i < self.contents.len());
+        self.viewport_index = shift(self.viewport_index);
+    }
+
+    /// Re-sorts the list if it's out of order, e.g. because metadata the sort depends on was
+    /// filled in after the entries were merged. The cursor and visual-mode anchor stay on the
+    /// entries they were on.
+    pub fn sort(&mut self, sort_options: &SortOption, ui_state: &UiState, options: &DisplayOption) {
+        // in tree order, only siblings are ordered among each other
+        if !options.mode.is_tree()
+            && self
+                .contents
+                .is_sorted_by(|f1, f2| sort_options.compare(f1, f2) != cmp::Ordering::Greater)
+        {
+            return;
+        }
+        let curr_path = self.curr_entry_ref().map(|e| e.file_path_buf());
+        let anchor_path = self
+            .visual_mode_anchor_index
+            .and_then(|i| self.contents.get(i))
+            .map(|e| e.file_path_buf());
+
+        self.contents.sort_by(|f1, f2| sort_options.compare(f1, f2));
+        if options.mode.is_tree() {
+            tree_order(&self.path, &mut self.contents);
+            self.update_tree_guides();
+        }
+
+        let position = |contents: &[JoshutoDirEntry], path: Option<path::PathBuf>| {
+            let path = path?;
+            contents.iter().position(|e| e.file_path() == path)
+        };
+        self.index = position(&self.contents, curr_path).or(self.index);
+        self.visual_mode_anchor_index = position(&self.contents, anchor_path);
+        if !ui_state.layout.is_empty() {
+            self.update_viewport(ui_state, options);
+        }
+        self.update_visual_mode_selection();
+    }
+
+    /// Re-reads the entries at `paths` after a filesystem change: each is removed if it no
+    /// longer exists or isn't listed as [`read_directory`] would list it, and otherwise
+    /// (re-)inserted at its sorted position. The cursor and selections stay on the entries they
+    /// were on.
+    pub fn refresh_entries(
+        &mut self,
+        paths: &[path::PathBuf],
+        display_options: &DisplayOption,
+        tab_options: &TabDisplayOption,
+        ui_state: &UiState,
+    ) {
+        let dirlist_opts = tab_options
+            .dirlist_options_ref(&self.path)
+            .cloned()
+            .unwrap_or_default();
+        let ignore = (!tab_options.show_ignored).then(|| IgnoreMatcher::new(&self.path));
+        let mut hidden_changed = false;
+        for path in paths {
+            let Some(name) = path
+                .strip_prefix(self.file_path())
+                .ok()
+                .and_then(|p| p.to_str())
+            else {
+                continue;
+            };
+            let old_entry = self
+                .get_index_from_name(name)
+                .map(|i| (self.index == Some(i), self.remove_at(i)));
+            hidden_changed |= path
+                .file_name()
+                .and_then(|s| s.to_str())
+                .is_some_and(|name| display_options.is_hidden(name));
+
+            let is_dir = path.symlink_metadata().is_ok_and(|m| m.is_dir());
+            let is_listed = is_path_listed(
+                &self.path,
+                path,
+                is_dir,
+                display_options,
+                &dirlist_opts,
+                ignore.as_ref(),
+            );
+            if !is_listed {
+                continue;
+            }
+            let Ok(mut entry) =
+                JoshutoDirEntry::from_path(path, &self.path, display_options, tab_options)
+            else {
+                continue;
+            };
+            let mut is_curr = false;
+            if let Some((was_curr, old_entry)) = old_entry {
+                entry.set_permanent_selected(old_entry.is_permanent_selected());
+                entry.set_visual_mode_selected(old_entry.is_visual_mode_selected());
+                is_curr = was_curr;
+            }
+            let i = self.insert_sorted(entry, tab_options.sort_options_ref());
+            self.version = next_version();
+            if is_curr {
+                self.index = Some(i);
+            }
+        }
+        // whether a hidden entry was added or removed isn't known, so they're counted again
+        if hidden_changed && !display_options.show_hidden {
+            let hidden = Cell::new(0);
+            let filter_func = display_options.filter_func();
+            walk_directory(
+                &self.path,
+                filter_func,
+                display_options,
+                tab_options,
+                &hidden,
+            )
+            .for_each(drop);
+            self.hidden_count = hidden.get();
+        }
+        if display_options.mode.is_tree() {
+            self.sort(tab_options.sort_options_ref(), ui_state, display_options);
+        }
+
+        if !ui_state.layout.is_empty() {
+            self.update_viewport(ui_state, display_options);
+        }
+        if self.visual_mode_anchor_index.is_some() {
+            self.update_visual_mode_selection();
+        }
+    }
+
+    fn remove_at(&mut self, i: usize) -> JoshutoDirEntry {
+        let entry = self.contents.remove(i);
+        let shift = |index: usize| if index > i { index - 1 } else { index };
+        self.index = match self.index.map(shift) {
+            Some(_) if self.contents.is_empty() => None,
+            Some(index) => Some(index.min(self.contents.len() - 1)),
+            None => None,
+        };
+        self.visual_mode_anchor_index = match self.visual_mode_anchor_index {
+            Some(anchor) if anchor == i => self.index,
+            anchor => anchor.map(shift),
+        };
+        entry
+    }
+
+    fn insert_sorted(&mut self, entry: JoshutoDirEntry, sort_options: &SortOption) -> usize {
+        let i = self
+            .contents
+            .partition_point(|e| sort_options.compare(e, &entry) != cmp::Ordering::Greater);
+        self.contents.insert(i, entry);
+        let shift = |index: usize| if index >= i { index + 1 } else { index };
+        self.index = Some(self.index.map(shift).unwrap_or(0));
+        self.visual_mode_anchor_index = self.visual_mode_anchor_index.map(shift);
+        i
+    }
+
     /// Returns an iterator over the entries in this directory.
     pub fn iter<'a>(&'a self) -> Iter<'a, JoshutoDirEntry> {
         self.contents.iter()
@@ -295,3 +687,149 @@ impl JoshutoDirList {
         self.viewport_index
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use std::time::{Duration, SystemTime};
+
+    use super::*;
+    use crate::fs::{ArchiveEntry, FileType, LinkType};
+    use crate::types::option::sort::SortMethod;
+
+    fn entry(name: &str, modified: Option<u64>) -> JoshutoDirEntry {
+        let metadata = match modified {
+            Some(secs) => JoshutoMetadata::from_archive_entry(&ArchiveEntry {
+                file_type: FileType::File,
+                len: 0,
+                modified: SystemTime::UNIX_EPOCH + Duration::from_secs(secs),
+                mode: 0o644,
+                link_target: None,
+            }),
+            None => JoshutoMetadata::unloaded(FileType::File, LinkType::Normal),
+        };
+        let base = path::Path::new("/d");
+        JoshutoDirEntry::from_metadata(base.join(name), base, metadata)
+    }
+
+    fn names(dirlist: &JoshutoDirList) -> Vec<&str> {
+        dirlist.iter().map(|e| e.file_name()).collect()
+    }
+
+    fn sort_by(method: SortMethod) -> SortOption {
+        let mut sort_options = SortOption::default();
+        sort_options.set_sort_method(method);
+        sort_options
+    }
+
+    #[test]
+    fn test_merge_sorted() {
+        let ui_state = UiState { layout: Vec::new() };
+        let options = DisplayOption::default();
+        let sort_options = sort_by(SortMethod::Natural);
+        let metadata = JoshutoMetadata::unloaded(FileType::Directory, LinkType::Normal);
+        let mut dirlist = JoshutoDirList::new("/d".into(), Vec::new(), None, 0, None, metadata);
+
+        let batch = vec![entry("c", None), entry("a", None)];
+        dirlist.merge_sorted(batch, &sort_options, &ui_state, &options);
+        assert_eq!(names(&dirlist), ["a", "c"]);
+        // a cursor at the top stays there while loading
+        assert_eq!(dirlist.get_index(), Some(0));
+
+        dirlist.set_index(Some(1), &ui_state, &options);
+        let batch = vec![entry("d", None), entry("b", None)];
+        dirlist.merge_sorted(batch, &sort_options, &ui_state, &options);
+        assert_eq!(names(&dirlist), ["a", "b", "c", "d"]);
+        assert_eq!(dirlist.curr_entry_ref().unwrap().file_name(), "c");
+    }
+
+    #[test]
+    fn test_reload_keeps_cursor_and_selection() {
+        let ui_state = UiState { layout: Vec::new() };
+        let options = DisplayOption::default();
+        let sort_options = sort_by(SortMethod::Natural);
+        let metadata = JoshutoMetadata::unloaded(FileType::Directory, LinkType::Normal);
+        let contents = vec![
+            entry("a", Some(1)),
+            entry("b", Some(1)),
+            entry("c", Some(1)),
+        ];
+        let mut dirlist =
+            JoshutoDirList::new("/d".into(), contents, Some(0), 0, None, metadata.clone());
+        dirlist.contents[0].set_permanent_selected(true);
+
+        dirlist.begin_reload(Uuid::new_v4(), metadata);
+        let batch = vec![entry("0", None), entry("a", None), entry("c", None)];
+        dirlist.merge_sorted(batch, &sort_options, &ui_state, &options);
+        assert_eq!(names(&dirlist), ["0", "a", "b", "c"]);
+        assert_eq!(dirlist.curr_entry_ref().unwrap().file_name(), "a");
+        assert!(dirlist.contents[1].is_permanent_selected());
+
+        // "b" wasn't listed again
+        dirlist.finish_load(0, &sort_options, &ui_state, &options);
+        assert_eq!(names(&dirlist), ["0", "a", "c"]);
+        assert_eq!(dirlist.curr_entry_ref().unwrap().file_name(), "a");
+        assert!(dirlist.load_id.is_none());
+    }
+
+    #[test]
+    fn test_update_metadata() {
+        let ui_state = UiState { layout: Vec::new() };
+        let options = DisplayOption::default();
+        let sort_options = sort_by(SortMethod::Mtime);
+        let metadata = JoshutoMetadata::unloaded(FileType::Directory, LinkType::Normal);
+        let mut dirlist = JoshutoDirList::new("/d".into(), Vec::new(), None, 0, None, metadata);
+
+        let batch = vec![entry("a", None), entry("b", None), entry("c", None)];
+        dirlist.merge_sorted(batch, &sort_options, &ui_state, &options);
+        dirlist.set_index(Some(2), &ui_state, &options);
+
+        // loaded entries sort before the ones still waiting for their metadata
+        let modified = |secs| entry("", Some(secs)).metadata;
+        let update = vec![("c".to_string(), Some(modified(5)))];
+        dirlist.update_metadata(update, &sort_options, &ui_state, &options);
+        assert_eq!(names(&dirlist), ["c", "a", "b"]);
+        assert_eq!(dirlist.curr_entry_ref().unwrap().file_name(), "c");
+
+        let update = vec![
+            ("a".to_string(), Some(modified(9))),
+            ("b".to_string(), None),
+        ];
+        dirlist.update_metadata(update, &sort_options, &ui_state, &options);
+        assert_eq!(names(&dirlist), ["c", "a"]);
+        assert_eq!(dirlist.curr_entry_ref().unwrap().file_name(), "c");
+    }
+
+    #[test]
+    fn test_refresh_entries() {
+        let dir = std::env::temp_dir().join(format!("joshuto-refresh-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(&dir).unwrap();
+        std::fs::write(dir.join(".ignore"), "skipped\n").unwrap();
+        std::fs::write(dir.join("a"), "").unwrap();
+
+        let ui_state = UiState { layout: Vec::new() };
+        let options = DisplayOption::default();
+        let tab_options = TabDisplayOption {
+            show_ignored: false,
+            ..Default::default()
+        };
+        let mut dirlist = JoshutoDirList::from_path(dir.clone(), &options, &tab_options).unwrap();
+        assert_eq!(names(&dirlist), ["a"]);
+        assert_eq!(dirlist.hidden_count, 1);
+
+        for name in ["b", ".c", "skipped"] {
+            std::fs::write(dir.join(name), "").unwrap();
+        }
+        std::fs::remove_file(dir.join("a")).unwrap();
+        let paths: Vec<path::PathBuf> = ["a", "b", ".c", "skipped"]
+            .iter()
+            .map(|name| dir.join(name))
+            .collect();
+        dirlist.refresh_entries(&paths, &options, &tab_options, &ui_state);
+        // hidden and ignored files are left out as when reading the directory
+        assert_eq!(names(&dirlist), ["b"]);
+        assert_eq!(dirlist.hidden_count, 2);
+
+        let _ = std::fs::remove_dir_all(&dir);
+    }
+}
@@ -0,0 +1,219 @@
+use std::cmp::Reverse;
+use std::fs;
+use std::path::Path;
+use std::sync::atomic::{AtomicBool, AtomicU64, AtomicUsize, Ordering};
+use std::thread;
+
+/// Number of threads scanning a tree at once, including the calling thread.
+const SCAN_THREADS: usize = 8;
+
+/// Recursively compute the total size in bytes of every regular file rooted
+/// at `path`. Symlinks are not followed; their own size is included instead.
+/// I/O errors on individual entries are silently skipped, matching `du`.
+/// Returns `None` once `cancel` is set.
+pub fn compute_size(path: &Path, cancel: &AtomicBool) -> Option<u64> {
+    if cancel.load(Ordering::Relaxed) {
+        return None;
+    }
+    let symlink_meta = match fs::symlink_metadata(path) {
+        Ok(m) => m,
+        Err(_) => return Some(0),
+    };
+
+    let file_type = symlink_meta.file_type();
+    if file_type.is_symlink() {
+        return Some(symlink_meta.len());
+    }
+    if !file_type.is_dir() {
+        return Some(symlink_meta.len());
+    }
+
+    let read_dir = match fs::read_dir(path) {
+        Ok(rd) => rd,
+        Err(_) => return Some(symlink_meta.len()),
+    };
+
+    let mut total: u64 = 0;
+    for entry in read_dir.flatten() {
+        total = total.saturating_add(compute_size(&entry.path(), cancel)?);
+    }
+    Some(total)
+}
+
+/// A file or directory of a scanned tree, with the cumulative size and file count of
+/// everything below it. Children are ordered by size, largest first.
+#[derive(Clone, Debug, Default)]
+pub struct DiskUsageNode {
+    pub name: String,
+    pub size: u64,
+    /// number of non-directory entries at or below this node
+    pub files: u64,
+    pub is_dir: bool,
+    pub children: Vec<DiskUsageNode>,
+}
+
+impl DiskUsageNode {
+    /// Scans the tree rooted at `path` like [`compute_size`], but keeping every entry, and
+    /// spreading subdirectories over several threads. `progress` is incremented for each file
+    /// found. Returns `None` once `cancel` is set.
+    pub fn scan(path: &Path, cancel: &AtomicBool, progress: &AtomicU64) -> Option<Self> {
+        let idle = AtomicUsize::new(SCAN_THREADS - 1);
+        let scanner = Scanner {
+            cancel,
+            progress,
+            idle: &idle,
+        };
+        let name = path
+            .file_name()
+            .map(|s| s.to_string_lossy().into_owned())
+            .unwrap_or_else(|| path.to_string_lossy().into_owned());
+        scanner.scan(path, name)
+    }
+
+    /// Returns the node reached by following the child `indices` from this one.
+    pub fn get(&self, indices: &[usize]) -> Option<&Self> {
+        indices
+            .iter()
+            .try_fold(self, |node, &i| node.children.get(i))
+    }
+
+    /// Removes the node reached by following the child `indices` from this one, subtracting
+    /// its size and file count from every node above it.
+    pub fn remove(&mut self, indices: &[usize]) -> Option<Self> {
+        let (&last, rest) = indices.split_last()?;
+        let removed = match rest.split_first() {
+            None if last < self.children.len() => self.children.remove(last),
+            None => return None,
+            Some((&i, _)) => self.children.get_mut(i)?.remove(&indices[1..])?,
+        };
+        self.size = self.size.saturating_sub(removed.size);
+        self.files = self.files.saturating_sub(removed.files);
+        Some(removed)
+    }
+}
+
+struct Scanner<'a> {
+    cancel: &'a AtomicBool,
+    progress: &'a AtomicU64,
+    /// number of threads that may still be spawned
+    idle: &'a AtomicUsize,
+}
+
+impl Scanner<'_> {
+    fn scan(&self, path: &Path, name: String) -> Option<DiskUsageNode> {
+        if self.cancel.load(Ordering::Relaxed) {
+            return None;
+        }
+        let mut node = DiskUsageNode {
+            name,
+            ..Default::default()
+        };
+        let symlink_meta = match fs::symlink_metadata(path) {
+            Ok(m) => m,
+            Err(_) => return Some(node),
+        };
+        if !symlink_meta.file_type().is_dir() {
+            self.progress.fetch_add(1, Ordering::Relaxed);
+            node.size = symlink_meta.len();
+            node.files = 1;
+            return Some(node);
+        }
+
+        node.is_dir = true;
+        let read_dir = match fs::read_dir(path) {
+            Ok(rd) => rd,
+            Err(_) => {
+                node.size = symlink_meta.len();
+                return Some(node);
+            }
+        };
+        let entries: Vec<_> = read_dir
+            .flatten()
+            .map(|e| {
+                let is_dir = e.file_type().is_ok_and(|t| t.is_dir());
+                (
+                    e.path(),
+                    e.file_name().to_string_lossy().into_owned(),
+                    is_dir,
+                )
+            })
+            .collect();
+
+        node.children = thread::scope(|s| {
+            let mut handles = Vec::new();
+            let mut children = Vec::with_capacity(entries.len());
+            for (path, name, is_dir) in entries {
+                if is_dir && self.try_reserve_thread() {
+                    handles.push(s.spawn(move || {
+                        let child = self.scan(&path, name);
+                        self.idle.fetch_add(1, Ordering::Relaxed);
+                        child
+                    }));
+                } else {
+                    children.push(self.scan(&path, name));
+                }
+            }
+            children.extend(handles.into_iter().map(|h| h.join().ok().flatten()));
+            children.into_iter().collect::<Option<Vec<_>>>()
+        })?;
+
+        node.children
+            .sort_by_key(|c| (Reverse(c.size), c.name.clone()));
+        for child in &node.children {
+            node.size = node.size.saturating_add(child.size);
+            node.files = node.files.saturating_add(child.files);
+        }
+        Some(node)
+    }
+
+    fn try_reserve_thread(&self) -> bool {
+        self.idle
+            .fetch_update(Ordering::Relaxed, Ordering::Relaxed, |n| n.checked_sub(1))
+            .is_ok()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn file(name: &str, size: u64) -> DiskUsageNode {
+        DiskUsageNode {
+            name: name.to_string(),
+            size,
+            files: 1,
+            is_dir: false,
+            children: Vec::new(),
+        }
+    }
+
+    fn dir(name: &str, children: Vec<DiskUsageNode>) -> DiskUsageNode {
+        DiskUsageNode {
+            name: name.to_string(),
+            size: children.iter().map(|c| c.size).sum(),
+            files: children.iter().map(|c| c.files).sum(),
+            is_dir: true,
+            children,
+        }
+    }
+
+    #[test]
+    fn test_remove() {
+        let mut root = dir(
+            "root",
+            vec![dir("a", vec![file("x", 30), file("y", 20)]), file("b", 10)],
+        );
+        assert_eq!((root.size, root.files), (60, 3));
+
+        let removed = root.remove(&[0, 1]).unwrap();
+        assert_eq!(removed.name, "y");
+        assert_eq!((root.size, root.files), (40, 2));
+        let a = root.get(&[0]).unwrap();
+        assert_eq!((a.size, a.files, a.children.len()), (30, 1, 1));
+
+        assert!(root.remove(&[5]).is_none());
+        assert!(root.remove(&[]).is_none());
+        root.remove(&[1]).unwrap();
+        assert_eq!((root.size, root.files), (30, 1));
+    }
+}
@@ -1,8 +1,11 @@
 use std::{fs, io, path};
 
-use crate::{fs::metadata::JoshutoMetadata, types::option::display::DisplayOption};
+use crate::fs::metadata::JoshutoMetadata;
+use crate::tab::TabDisplayOption;
+use crate::types::option::display::DisplayOption;
+use crate::TAGS_T;
 
-use super::FileType;
+use super::{FileType, LinkType};
 
 /// A single file or directory entry as shown in a joshuto directory listing.
 #[derive(Clone, Debug)]
@@ -15,56 +18,121 @@ pub struct JoshutoDirEntry {
     permanent_selected: bool,
     /// Temporarily selected by the visual mode range
     visual_mode_selected: bool,
-    _marked: bool,
+    /// The character the entry is tagged with, see `tag_toggle`
+    tag: Option<char>,
 }
 
 impl JoshutoDirEntry {
-    /// Builds a `JoshutoDirEntry` from a `walkdir` entry, resolving metadata and, if enabled,
-    /// counting a directory's contents for display.
+    /// Builds a `JoshutoDirEntry` from a `walkdir` entry, resolving metadata and, if enabled
+    /// or shown or sorted by in the tab, counting a directory's contents.
     pub fn from(
         direntry: &walkdir::DirEntry,
         base: &path::Path,
         options: &DisplayOption,
+        tab_options: &TabDisplayOption,
     ) -> io::Result<Self> {
-        let path = direntry.path().to_path_buf();
-
-        let name = direntry
-            .path()
-            .strip_prefix(base)
-            .unwrap()
-            .to_string_lossy()
-            .to_string();
-
-        let ext = direntry
-            .path()
-            .extension()
-            .and_then(|s| s.to_str())
-            .map(|s| s.to_string());
-
-        let mut metadata = JoshutoMetadata::from(&path)?;
-
-        if options.automatically_count_files && metadata.file_type() == FileType::Directory {
-            if let Ok(size) = get_directory_size(path.as_path()) {
-                metadata.update_directory_size(size);
-            }
-        }
+        let mut entry = Self::from_unloaded(direntry, base);
+        entry.metadata = load_metadata(entry.file_path(), options, tab_options)?;
+        Ok(entry)
+    }
 
+    /// Builds a `JoshutoDirEntry` for `path`, named relative to `base`, resolving metadata as
+    /// [`JoshutoDirEntry::from`] does.
+    pub fn from_path(
+        path: &path::Path,
+        base: &path::Path,
+        options: &DisplayOption,
+        tab_options: &TabDisplayOption,
+    ) -> io::Result<Self> {
+        let metadata = load_metadata(path, options, tab_options)?;
+        let (name, ext) = name_and_ext(path, base);
         Ok(Self {
             name,
             ext,
-            path,
+            path: path.to_path_buf(),
             metadata,
             permanent_selected: false,
             visual_mode_selected: false,
-            _marked: false,
+            tag: tag_of(path),
         })
     }
 
+    /// Builds a `JoshutoDirEntry` for `path`, named relative to `base`, with already-read
+    /// `metadata`.
+    pub fn from_metadata(
+        path: path::PathBuf,
+        base: &path::Path,
+        metadata: JoshutoMetadata,
+    ) -> Self {
+        let (name, ext) = name_and_ext(&path, base);
+        let tag = tag_of(&path);
+        Self {
+            name,
+            ext,
+            path,
+            metadata,
+            permanent_selected: false,
+            visual_mode_selected: false,
+            tag,
+        }
+    }
+
+    /// Builds a `JoshutoDirEntry` from a `walkdir` entry without stat'ing it: only the name and
+    /// file type (from the directory listing itself) are filled in, see
+    /// [`JoshutoMetadata::unloaded`]. Symlinks are resolved so that links to directories sort
+    /// with directories.
+    pub fn from_unloaded(direntry: &walkdir::DirEntry, base: &path::Path) -> Self {
+        let path = direntry.path().to_path_buf();
+        let (name, ext) = name_and_ext(&path, base);
+
+        let (file_type, link_type) = if direntry.path_is_symlink() {
+            let file_type = if path.is_dir() {
+                FileType::Directory
+            } else {
+                FileType::File
+            };
+            let link_type = LinkType::Symlink {
+                target: String::new(),
+                valid: path.exists(),
+                hops: Vec::new(),
+            };
+            (file_type, link_type)
+        } else {
+            (FileType::from(direntry.file_type()), LinkType::Normal)
+        };
+
+        let tag = tag_of(&path);
+        Self {
+            name,
+            ext,
+            path,
+            metadata: JoshutoMetadata::unloaded(file_type, link_type),
+            permanent_selected: false,
+            visual_mode_selected: false,
+            tag,
+        }
+    }
+
     /// Returns the entry's file name (not the full path).
     pub fn file_name(&self) -> &str {
         self.name.as_str()
     }
 
+    /// Names the entry relative to `base`, for listing it as part of a directory further up.
+    pub fn rebase(&mut self, base: &path::Path) {
+        self.name = name_and_ext(&self.path, base).0;
+    }
+
+    /// Returns the character the entry is tagged with, if any.
+    pub fn tag(&self) -> Option<char> {
+        self.tag
+    }
+
+    /// Sets the character the entry is tagged with, without storing it.
+    pub fn set_tag(&mut self, tag: Option<char>) {
+        self.tag = tag;
+    }
+
     /// Returns the entry's file extension, if any.
     pub fn ext(&self) -> Option<&str> {
         self.ext.as_deref()
@@ -137,6 +205,41 @@ impl std::cmp::Ord for JoshutoDirEntry {
     }
 }
 
+fn name_and_ext(path: &path::Path, base: &path::Path) -> (String, Option<String>) {
+    let name = path
+        .strip_prefix(base)
+        .unwrap_or(path)
+        .to_string_lossy()
+        .to_string();
+    let ext = path
+        .extension()
+        .and_then(|s| s.to_str())
+        .map(|s| s.to_string());
+    (name, ext)
+}
+
+/// Reads the metadata of `path` and, if enabled or shown or sorted by in the tab, counts a
+/// directory's contents.
+pub fn load_metadata(
+    path: &path::Path,
+    options: &DisplayOption,
+    tab_options: &TabDisplayOption,
+) -> io::Result<JoshutoMetadata> {
+    let mut metadata = JoshutoMetadata::from(path)?;
+    let count_files = options.automatically_count_files || tab_options.needs_item_count();
+    if count_files && metadata.file_type() == FileType::Directory {
+        if let Ok(size) = get_directory_size(path) {
+            metadata.update_directory_size(size);
+        }
+    }
+    Ok(metadata)
+}
+
 fn get_directory_size(path: &path::Path) -> io::Result<usize> {
     fs::read_dir(path).map(|s| s.count())
 }
+
+/// Returns the stored tag of `path`.
+fn tag_of(path: &path::Path) -> Option<char> {
+    TAGS_T.lock().ok().and_then(|tags| tags.get(path))
+}
@@ -0,0 +1,189 @@
+use std::fmt;
+use std::time::SystemTime;
+
+use crate::types::state::MatchState;
+
+/// The kind of entry looked for by `find --type`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum FindType {
+    File,
+    Dir,
+    Link,
+}
+
+impl FindType {
+    /// Parses `f`, `d` or `l`, as for `find -type`.
+    pub fn from_str(s: &str) -> Option<Self> {
+        match s {
+            "f" => Some(Self::File),
+            "d" => Some(Self::Dir),
+            "l" => Some(Self::Link),
+            _ => None,
+        }
+    }
+
+    /// Returns the type's letter, see [`FindType::from_str`].
+    pub fn as_str(&self) -> &str {
+        match self {
+            Self::File => "f",
+            Self::Dir => "d",
+            Self::Link => "l",
+        }
+    }
+}
+
+/// A bound on a size or an age: more than, with a `+` prefix, or less than, with a `-` prefix.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Bound {
+    Above(u64),
+    Below(u64),
+}
+
+impl Bound {
+    /// Parses `+<n><unit>` or `-<n><unit>` with the value of each unit given by `unit`.
+    fn parse(s: &str, unit: impl Fn(&str) -> Option<u64>) -> Option<Self> {
+        let (above, rest) = match s.split_at_checked(1)? {
+            ("+", rest) => (true, rest),
+            ("-", rest) => (false, rest),
+            _ => return None,
+        };
+        let split = rest
+            .find(|c: char| !c.is_ascii_digit())
+            .unwrap_or(rest.len());
+        let (digits, suffix) = rest.split_at(split);
+        let n = digits.parse::<u64>().ok()?.checked_mul(unit(suffix)?)?;
+        Some(if above {
+            Self::Above(n)
+        } else {
+            Self::Below(n)
+        })
+    }
+
+    /// Parses a size like `+1M` (more than a mebibyte) or `-10k`. Without a unit, in bytes.
+    pub fn parse_size(s: &str) -> Option<Self> {
+        Self::parse(s, |unit| match unit {
+            "" => Some(1),
+            "k" | "K" => Some(1 << 10),
+            "M" => Some(1 << 20),
+            "G" => Some(1 << 30),
+            "T" => Some(1 << 40),
+            _ => None,
+        })
+    }
+
+    /// Parses an age like `-7d` (less than a week old) or `+1h`, in seconds. Without a unit,
+    /// in days.
+    pub fn parse_age(s: &str) -> Option<Self> {
+        Self::parse(s, |unit| match unit {
+            "s" => Some(1),
+            "m" => Some(60),
+            "h" => Some(60 * 60),
+            "" | "d" => Some(24 * 60 * 60),
+            "w" => Some(7 * 24 * 60 * 60),
+            _ => None,
+        })
+    }
+
+    /// Returns `true` if `n` is within the bound.
+    pub fn contains(&self, n: u64) -> bool {
+        match self {
+            Self::Above(bound) => n > *bound,
+            Self::Below(bound) => n < *bound,
+        }
+    }
+}
+
+impl fmt::Display for Bound {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Above(n) => write!(f, "+{n}"),
+            Self::Below(n) => write!(f, "-{n}"),
+        }
+    }
+}
+
+/// The predicates of `find` besides the name: type, size in bytes and age in seconds.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct FindFilters {
+    pub file_type: Option<FindType>,
+    pub size: Option<Bound>,
+    pub mtime: Option<Bound>,
+}
+
+/// What `find` looks for.
+#[derive(Clone, Debug)]
+pub struct FindQuery {
+    pub pattern: MatchState,
+    pub filters: FindFilters,
+}
+
+impl FindQuery {
+    /// Returns `true` if the walked `entry` matches, with ages relative to `now`.
+    pub fn is_match(&self, entry: &walkdir::DirEntry, now: SystemTime) -> bool {
+        let name_matches = entry
+            .file_name()
+            .to_str()
+            .is_some_and(|name| self.pattern.is_match(name));
+        if !name_matches {
+            return false;
+        }
+
+        let file_type = entry.file_type();
+        let type_matches = match self.filters.file_type {
+            Some(FindType::File) => file_type.is_file(),
+            Some(FindType::Dir) => file_type.is_dir(),
+            Some(FindType::Link) => file_type.is_symlink(),
+            None => true,
+        };
+        if !type_matches {
+            return false;
+        }
+
+        if self.filters.size.is_none() && self.filters.mtime.is_none() {
+            return true;
+        }
+        let Ok(metadata) = entry.metadata() else {
+            return false;
+        };
+        let size_matches = self
+            .filters
+            .size
+            .is_none_or(|bound| bound.contains(metadata.len()));
+        let mtime_matches = self.filters.mtime.is_none_or(|bound| {
+            metadata
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok())
+                .is_some_and(|age| bound.contains(age.as_secs()))
+        });
+        size_matches && mtime_matches
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_bounds() {
+        assert_eq!(Bound::parse_size("+1M"), Some(Bound::Above(1 << 20)));
+        assert_eq!(Bound::parse_size("-10k"), Some(Bound::Below(10 << 10)));
+        assert_eq!(Bound::parse_size("+100"), Some(Bound::Above(100)));
+        assert_eq!(Bound::parse_size("1M"), None);
+        assert_eq!(Bound::parse_size("+1X"), None);
+        assert_eq!(Bound::parse_size("+"), None);
+
+        assert_eq!(Bound::parse_age("-7d"), Some(Bound::Below(7 * 86400)));
+        assert_eq!(Bound::parse_age("+2"), Some(Bound::Above(2 * 86400)));
+        assert_eq!(Bound::parse_age("-30m"), Some(Bound::Below(1800)));
+        assert_eq!(Bound::parse_age("-30s"), Some(Bound::Below(30)));
+    }
+
+    #[test]
+    fn test_bound_contains() {
+        assert!(Bound::Above(10).contains(11));
+        assert!(!Bound::Above(10).contains(10));
+        assert!(Bound::Below(10).contains(9));
+        assert!(!Bound::Below(10).contains(10));
+    }
+}
@@ -0,0 +1,173 @@
+use std::fs;
+use std::io::{self, BufRead, BufReader};
+use std::path::{Path, PathBuf};
+
+use regex::Regex;
+
+/// Number of bytes at the start of a file checked for NUL bytes to tell binary files apart.
+const BINARY_CHECK_LEN: usize = 8192;
+/// Maximum length in characters of the line kept with a match.
+const MAX_SNIPPET_LEN: usize = 256;
+/// Maximum number of bytes of a line read into memory. Only the start of longer lines is
+/// searched.
+const MAX_LINE_LEN: usize = 64 * 1024;
+
+/// A line matched by `grep`.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct GrepMatch {
+    pub path: PathBuf,
+    /// 1-based line number
+    pub line: usize,
+    /// the matched line, without surrounding whitespace and shortened if very long
+    pub text: String,
+}
+
+/// Returns `true` if `buf`, the start of a file, looks binary: it contains a NUL byte.
+pub fn is_binary(buf: &[u8]) -> bool {
+    buf.contains(&0)
+}
+
+/// Searches the file at `path` for lines matching `regex`, appending them to `matches`. Binary
+/// files are skipped.
+pub fn grep_file(path: &Path, regex: &Regex, matches: &mut Vec<GrepMatch>) -> io::Result<()> {
+    let mut reader = BufReader::with_capacity(BINARY_CHECK_LEN, fs::File::open(path)?);
+    if is_binary(reader.fill_buf()?) {
+        return Ok(());
+    }
+    grep_reader(reader, path, regex, matches)
+}
+
+/// Reads a line from `reader` into `buf`, terminator included, keeping at most
+/// [`MAX_LINE_LEN`] bytes of it and skipping the rest. Returns the number of bytes read,
+/// 0 at the end of the input.
+fn read_line_capped<R: BufRead>(reader: &mut R, buf: &mut Vec<u8>) -> io::Result<usize> {
+    let mut read = 0;
+    loop {
+        let available = match reader.fill_buf() {
+            Ok(available) => available,
+            Err(e) if e.kind() == io::ErrorKind::Interrupted => continue,
+            Err(e) => return Err(e),
+        };
+        if available.is_empty() {
+            return Ok(read);
+        }
+        let (len, done) = match available.iter().position(|b| *b == b'\n') {
+            Some(i) => (i + 1, true),
+            None => (available.len(), false),
+        };
+        let kept = len.min(MAX_LINE_LEN.saturating_sub(buf.len()));
+        buf.extend_from_slice(&available[..kept]);
+        reader.consume(len);
+        read += len;
+        if done {
+            return Ok(read);
+        }
+    }
+}
+
+/// Searches the lines read from `reader` for `regex`, appending them to `matches` as lines of
+/// `path`. Lines that aren't valid UTF-8 are matched lossily, without their line terminator,
+/// and only the first [`MAX_LINE_LEN`] bytes of very long lines are searched.
+pub fn grep_reader<R: BufRead>(
+    mut reader: R,
+    path: &Path,
+    regex: &Regex,
+    matches: &mut Vec<GrepMatch>,
+) -> io::Result<()> {
+    let mut buf = Vec::new();
+    let mut line = 0;
+    loop {
+        buf.clear();
+        if read_line_capped(&mut reader, &mut buf)? == 0 {
+            return Ok(());
+        }
+        line += 1;
+        let text = String::from_utf8_lossy(&buf);
+        let text = text.strip_suffix('\n').unwrap_or(&text);
+        let text = text.strip_suffix('\r').unwrap_or(text);
+        if regex.is_match(text) {
+            matches.push(GrepMatch {
+                path: path.to_path_buf(),
+                line,
+                text: snippet(text),
+            });
+        }
+    }
+}
+
+/// Prepares a matched line for display: tabs expanded to spaces, surrounding whitespace
+/// trimmed, and shortened to [`MAX_SNIPPET_LEN`] characters.
+fn snippet(line: &str) -> String {
+    line.replace('\t', "    ")
+        .trim()
+        .chars()
+        .take(MAX_SNIPPET_LEN)
+        .collect()
+}
+
+/// Reads lines `first` to `last` (1-based, inclusive) of the file at `path`, with their
+/// numbers and tabs expanded to spaces. Returns fewer lines if the file is shorter.
+pub fn read_lines(path: &Path, first: usize, last: usize) -> io::Result<Vec<(usize, String)>> {
+    let mut reader = BufReader::new(fs::File::open(path)?);
+    let mut lines = Vec::with_capacity(last.saturating_sub(first) + 1);
+    let mut buf = Vec::new();
+    for n in 1..=last {
+        buf.clear();
+        if read_line_capped(&mut reader, &mut buf)? == 0 {
+            break;
+        }
+        if n >= first {
+            let text = String::from_utf8_lossy(&buf);
+            let text = text.trim_end_matches(['\n', '\r']).replace('\t', "    ");
+            lines.push((n, text));
+        }
+    }
+    Ok(lines)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_grep_reader() {
+        let regex = Regex::new("fo+").unwrap();
+        let input = "foo\nbar\n\tfoo bar  \nfo";
+        let mut matches = Vec::new();
+        grep_reader(input.as_bytes(), Path::new("a.txt"), &regex, &mut matches).unwrap();
+
+        let lines: Vec<(usize, &str)> = matches.iter().map(|m| (m.line, m.text.as_str())).collect();
+        assert_eq!(lines, vec![(1, "foo"), (3, "foo bar"), (4, "fo")]);
+    }
+
+    #[test]
+    fn test_grep_reader_line_ends() {
+        // `$` matches at the end of lines ending in `\n` or `\r\n`
+        let regex = Regex::new("o$").unwrap();
+        let input = "foo\r\nbar\nfoo\nfoo bar\nfo";
+        let mut matches = Vec::new();
+        grep_reader(input.as_bytes(), Path::new("a.txt"), &regex, &mut matches).unwrap();
+
+        let lines: Vec<usize> = matches.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![1, 3, 5]);
+    }
+
+    #[test]
+    fn test_grep_reader_long_lines() {
+        let regex = Regex::new("^(foo|end)$").unwrap();
+        let long_line = "x".repeat(3 * MAX_LINE_LEN);
+        let input = format!("foo\n{long_line}foo\nend");
+        let mut matches = Vec::new();
+        grep_reader(input.as_bytes(), Path::new("a.txt"), &regex, &mut matches).unwrap();
+
+        // only the start of the long line is searched, and line numbers carry on after it
+        let lines: Vec<usize> = matches.iter().map(|m| m.line).collect();
+        assert_eq!(lines, vec![1, 3]);
+    }
+
+    #[test]
+    fn test_is_binary() {
+        assert!(is_binary(b"ELF\0\x01"));
+        assert!(!is_binary("plain text ✓".as_bytes()));
+    }
+}
@@ -0,0 +1,234 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::OnceLock;
+
+use globset::{GlobBuilder, GlobSet, GlobSetBuilder};
+
+/// Names of the files of ignore patterns read in each directory, lowest precedence first.
+/// `.gitignore` files only apply inside a git repository.
+const IGNORE_FILES: [&str; 2] = [".gitignore", ".ignore"];
+
+/// Decides which entries below a directory are ignored by `.gitignore` and `.ignore` files,
+/// the repository's `info/exclude` file and git's global excludes file.
+pub struct IgnoreMatcher {
+    dir: PathBuf,
+    in_repo: bool,
+    /// rules applying to everything below `dir`, lowest precedence first
+    base: Vec<IgnoreFile>,
+    /// rules of the subdirectories of `dir` met so far, by directory
+    nested: RefCell<HashMap<PathBuf, Vec<IgnoreFile>>>,
+}
+
+impl IgnoreMatcher {
+    /// Reads the rules applying to the contents of `dir`, from it and its ancestors.
+    pub fn new(dir: &Path) -> Self {
+        let root = dir
+            .ancestors()
+            .find(|d| d.join(".git").exists())
+            .map(Path::to_path_buf);
+
+        let mut base = Vec::new();
+        if let Some(root) = root.as_ref() {
+            if let Some(excludes) = global_excludes() {
+                base.extend(IgnoreFile::parse(root, excludes));
+            }
+            base.extend(IgnoreFile::read(root, &root.join(".git/info/exclude")));
+        }
+        let mut ancestors: Vec<&Path> = dir.ancestors().collect();
+        ancestors.reverse();
+        for ancestor in ancestors {
+            let in_repo = root.as_ref().is_some_and(|root| ancestor.starts_with(root));
+            base.extend(read_ignore_files(ancestor, in_repo));
+        }
+
+        Self {
+            dir: dir.to_path_buf(),
+            in_repo: root.is_some(),
+            base,
+            nested: RefCell::new(HashMap::new()),
+        }
+    }
+
+    /// Returns `true` if `path`, somewhere below the matcher's directory, is ignored. Rules of
+    /// the directories in between are read as they're met.
+    pub fn is_ignored(&self, path: &Path, is_dir: bool) -> bool {
+        if !path.starts_with(&self.dir) {
+            return false;
+        }
+        // directories between the matcher's and `path`, innermost first
+        let dirs: Vec<&Path> = path
+            .ancestors()
+            .skip(1)
+            .take_while(|d| *d != self.dir)
+            .collect();
+
+        let mut nested = self.nested.borrow_mut();
+        for dir in &dirs {
+            if !nested.contains_key(*dir) {
+                nested.insert(dir.to_path_buf(), read_ignore_files(dir, self.in_repo));
+            }
+        }
+        dirs.iter()
+            .flat_map(|d| nested[*d].iter().rev())
+            .chain(self.base.iter().rev())
+            .find_map(|file| file.matched(path, is_dir))
+            .unwrap_or(false)
+    }
+}
+
+/// The patterns of one ignore file, matched against paths relative to `dir`.
+#[derive(Debug)]
+struct IgnoreFile {
+    dir: PathBuf,
+    set: GlobSet,
+    rules: Vec<IgnoreRule>,
+}
+
+#[derive(Clone, Copy, Debug)]
+struct IgnoreRule {
+    /// whether the pattern starts with `!`, re-including what it matches
+    negated: bool,
+    /// whether the pattern ends with `/`, matching directories only
+    dir_only: bool,
+}
+
+impl IgnoreFile {
+    fn read(dir: &Path, file: &Path) -> Option<Self> {
+        let contents = fs::read_to_string(file).ok()?;
+        Self::parse(dir, &contents)
+    }
+
+    /// Parses gitignore-style `contents`, returning `None` if there are no patterns.
+    fn parse(dir: &Path, contents: &str) -> Option<Self> {
+        let mut builder = GlobSetBuilder::new();
+        let mut rules = Vec::new();
+        for (glob, rule) in contents.lines().filter_map(parse_line) {
+            let glob = GlobBuilder::new(&glob)
+                .literal_separator(true)
+                .backslash_escape(true)
+                .build();
+            if let Ok(glob) = glob {
+                builder.add(glob);
+                rules.push(rule);
+            }
+        }
+        if rules.is_empty() {
+            return None;
+        }
+        Some(Self {
+            dir: dir.to_path_buf(),
+            set: builder.build().ok()?,
+            rules,
+        })
+    }
+
+    /// Returns whether the last pattern matching `path` ignores or re-includes it, or `None` if
+    /// no pattern matches.
+    fn matched(&self, path: &Path, is_dir: bool) -> Option<bool> {
+        let path = path.strip_prefix(&self.dir).ok()?;
+        self.set
+            .matches(path)
+            .into_iter()
+            .filter(|i| is_dir || !self.rules[*i].dir_only)
+            .max()
+            .map(|i| !self.rules[i].negated)
+    }
+}
+
+/// Turns a line of an ignore file into a glob matching paths relative to the file's directory.
+fn parse_line(line: &str) -> Option<(String, IgnoreRule)> {
+    let line = line.strip_suffix('\r').unwrap_or(line);
+    if line.is_empty() || line.starts_with('#') {
+        return None;
+    }
+    // trailing spaces are dropped unless escaped
+    let trimmed = line.trim_end_matches(' ');
+    let line = if trimmed.ends_with('\\') && trimmed.len() < line.len() {
+        &line[..trimmed.len() + 1]
+    } else {
+        trimmed
+    };
+
+    let (negated, pattern) = match line.strip_prefix('!') {
+        Some(rest) => (true, rest),
+        None => (false, line),
+    };
+    let (dir_only, pattern) = match pattern.strip_suffix('/') {
+        Some(rest) => (true, rest),
+        None => (false, pattern),
+    };
+    if pattern.is_empty() {
+        return None;
+    }
+    // patterns without a slash match at any depth, others relative to the file's directory
+    let glob = if pattern.contains('/') {
+        pattern.trim_start_matches('/').to_string()
+    } else {
+        format!("**/{pattern}")
+    };
+    Some((glob, IgnoreRule { negated, dir_only }))
+}
+
+fn read_ignore_files(dir: &Path, in_repo: bool) -> Vec<IgnoreFile> {
+    IGNORE_FILES
+        .iter()
+        .filter(|name| in_repo || **name != ".gitignore")
+        .filter_map(|name| IgnoreFile::read(dir, &dir.join(name)))
+        .collect()
+}
+
+/// Returns the contents of git's global excludes file, read once: `core.excludesFile`, or
+/// `$XDG_CONFIG_HOME/git/ignore` if unset.
+fn global_excludes() -> Option<&'static str> {
+    static EXCLUDES: OnceLock<Option<String>> = OnceLock::new();
+    EXCLUDES
+        .get_or_init(|| {
+            let configured = Command::new("git")
+                .args(["config", "--path", "--get", "core.excludesFile"])
+                .output()
+                .ok()
+                .filter(|output| output.status.success())
+                .and_then(|output| String::from_utf8(output.stdout).ok())
+                .map(|s| PathBuf::from(s.trim_end()))
+                .filter(|path| !path.as_os_str().is_empty());
+            let path = configured.or_else(|| {
+                env::var_os("XDG_CONFIG_HOME")
+                    .map(PathBuf::from)
+                    .or_else(|| dirs::home_dir().map(|home| home.join(".config")))
+                    .map(|config| config.join("git/ignore"))
+            })?;
+            fs::read_to_string(path).ok()
+        })
+        .as_deref()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_ignore_file() {
+        let file = IgnoreFile::parse(
+            Path::new("/repo"),
+            "# build output\n/target\nnode_modules/\n*.log\n!keep.log\ndocs/*.html\n\\#notes\n",
+        )
+        .unwrap();
+        let matched = |p: &str, is_dir| file.matched(Path::new(p), is_dir);
+
+        assert_eq!(matched("/repo/target", true), Some(true));
+        assert_eq!(matched("/repo/sub/target", true), None);
+        assert_eq!(matched("/repo/web/node_modules", true), Some(true));
+        assert_eq!(matched("/repo/web/node_modules", false), None);
+        assert_eq!(matched("/repo/a/b/debug.log", false), Some(true));
+        assert_eq!(matched("/repo/a/keep.log", false), Some(false));
+        assert_eq!(matched("/repo/docs/index.html", false), Some(true));
+        assert_eq!(matched("/repo/docs/api/index.html", false), None);
+        assert_eq!(matched("/repo/#notes", false), Some(true));
+        assert_eq!(matched("/repo/src/main.rs", false), None);
+        assert_eq!(matched("/elsewhere/debug.log", false), None);
+    }
+}
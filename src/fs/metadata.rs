@@ -1,7 +1,11 @@
+use std::collections::HashMap;
 use std::{fs, io, path, time};
 
 use nix::sys::stat::{mode_t, Mode, SFlag};
 
+use super::{archive_index, ArchiveEntry, VfsPath};
+use crate::utils::symlink;
+
 /// The kind of filesystem object an entry represents.
 #[derive(Copy, Clone, Debug, PartialEq, Eq)]
 pub enum FileType {
@@ -21,6 +25,28 @@ impl From<SFlag> for FileType {
     }
 }
 
+impl From<fs::FileType> for FileType {
+    fn from(value: fs::FileType) -> Self {
+        use std::os::unix::fs::FileTypeExt;
+
+        if value.is_dir() {
+            FileType::Directory
+        } else if value.is_symlink() {
+            FileType::Link
+        } else if value.is_socket() {
+            FileType::Socket
+        } else if value.is_block_device() {
+            FileType::Block
+        } else if value.is_char_device() {
+            FileType::Character
+        } else if value.is_fifo() {
+            FileType::Pipe
+        } else {
+            FileType::File
+        }
+    }
+}
+
 impl FileType {
     /// Maps a Unix `st_mode` file-type flag to a [`FileType`].
     pub fn from_mode(mode: SFlag) -> Self {
@@ -40,7 +66,13 @@ impl FileType {
 #[derive(Clone, Debug)]
 pub enum LinkType {
     Normal,
-    Symlink { target: String, valid: bool },
+    Symlink {
+        target: String,
+        valid: bool,
+        /// the paths a link to a link resolves through after `target`, see
+        /// [`link_chain`](crate::utils::symlink::link_chain)
+        hops: Vec<path::PathBuf>,
+    },
 }
 
 /// Filesystem metadata for a [`JoshutoDirEntry`](super::JoshutoDirEntry).
@@ -51,6 +83,9 @@ pub struct JoshutoMetadata {
     pub cumulative_size: Option<u64>,
     pub modified: time::SystemTime,
     pub accessed: time::SystemTime,
+    pub changed: time::SystemTime,
+    /// Creation time, if the filesystem records it
+    pub created: Option<time::SystemTime>,
     pub mode: Mode,
     pub file_type: FileType,
     pub link_type: LinkType,
@@ -58,12 +93,40 @@ pub struct JoshutoMetadata {
     pub uid: u32,
     #[cfg(unix)]
     pub gid: u32,
+    #[cfg(unix)]
+    pub inode: u64,
+    #[cfg(unix)]
+    pub nlink: u64,
+    /// Detected MIME type, only filled in while the `mime` linemode column is shown
+    pub mimetype: Option<String>,
+    /// Values of the custom linemode columns computed so far, by column name
+    pub custom_columns: HashMap<String, String>,
+    loaded: bool,
 }
 
 impl JoshutoMetadata {
-    /// Reads metadata for `path`, following symlinks where possible and falling back to
-    /// symlink metadata if the target is broken.
+    /// Reads metadata for `path`, from the index of the archive it is in (see [`VfsPath`]) or
+    /// else from the filesystem.
     pub fn from(path: &path::Path) -> io::Result<Self> {
+        match VfsPath::resolve(path) {
+            VfsPath::Archive {
+                archive,
+                kind,
+                inner,
+            } if !inner.as_os_str().is_empty() => {
+                let index = archive_index(archive, kind)?;
+                let entry = index
+                    .get(inner)
+                    .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+                Ok(Self::from_archive_entry(entry))
+            }
+            _ => Self::from_local(path),
+        }
+    }
+
+    /// Reads metadata for `path` from the filesystem, following symlinks where possible and
+    /// falling back to symlink metadata if the target is broken.
+    fn from_local(path: &path::Path) -> io::Result<Self> {
         #[cfg(unix)]
         use std::os::unix::fs::MetadataExt;
 
@@ -77,6 +140,10 @@ impl JoshutoMetadata {
                 symlink_metadata.accessed()?,
             ),
         };
+        let resolved = metadata.as_ref().unwrap_or(&symlink_metadata);
+        let changed = time::UNIX_EPOCH
+            + time::Duration::new(resolved.ctime() as u64, resolved.ctime_nsec() as u32);
+        let created = resolved.created().ok();
 
         let directory_size = None;
         let cumulative_size = None;
@@ -104,6 +171,7 @@ impl JoshutoMetadata {
             LinkType::Symlink {
                 target: link,
                 valid: exists,
+                hops: symlink::link_chain(path).into_iter().skip(1).collect(),
             }
         } else {
             LinkType::Normal
@@ -113,6 +181,10 @@ impl JoshutoMetadata {
         let uid = symlink_metadata.uid();
         #[cfg(unix)]
         let gid = symlink_metadata.gid();
+        #[cfg(unix)]
+        let inode = resolved.ino();
+        #[cfg(unix)]
+        let nlink = resolved.nlink();
 
         Ok(Self {
             len,
@@ -120,6 +192,8 @@ impl JoshutoMetadata {
             cumulative_size,
             modified,
             accessed,
+            changed,
+            created,
             mode,
             file_type,
             link_type,
@@ -127,9 +201,78 @@ impl JoshutoMetadata {
             uid,
             #[cfg(unix)]
             gid,
+            #[cfg(unix)]
+            inode,
+            #[cfg(unix)]
+            nlink,
+            mimetype: None,
+            custom_columns: HashMap::new(),
+            loaded: true,
         })
     }
 
+    /// Builds metadata for an entry of an archive. Times other than the modification time are
+    /// set to it, and ownership is the user's own.
+    pub fn from_archive_entry(entry: &ArchiveEntry) -> Self {
+        let link_type = match entry.link_target.as_ref() {
+            Some(target) => LinkType::Symlink {
+                target: target.to_string_lossy().to_string(),
+                valid: false,
+                hops: Vec::new(),
+            },
+            None => LinkType::Normal,
+        };
+        Self {
+            len: entry.len,
+            modified: entry.modified,
+            accessed: entry.modified,
+            changed: entry.modified,
+            mode: Mode::from_bits_truncate(entry.mode as mode_t),
+            #[cfg(unix)]
+            uid: nix::unistd::getuid().as_raw(),
+            #[cfg(unix)]
+            gid: nix::unistd::getgid().as_raw(),
+            #[cfg(unix)]
+            nlink: 1,
+            loaded: true,
+            ..Self::unloaded(entry.file_type, link_type)
+        }
+    }
+
+    /// Builds placeholder metadata for an entry whose name and type are known but which hasn't
+    /// been stat'ed yet. Sizes, times, permissions and ownership are zeroed until the real
+    /// metadata replaces it.
+    pub fn unloaded(file_type: FileType, link_type: LinkType) -> Self {
+        Self {
+            len: 0,
+            directory_size: None,
+            cumulative_size: None,
+            modified: time::UNIX_EPOCH,
+            accessed: time::UNIX_EPOCH,
+            changed: time::UNIX_EPOCH,
+            created: None,
+            mode: Mode::empty(),
+            file_type,
+            link_type,
+            #[cfg(unix)]
+            uid: 0,
+            #[cfg(unix)]
+            gid: 0,
+            #[cfg(unix)]
+            inode: 0,
+            #[cfg(unix)]
+            nlink: 0,
+            mimetype: None,
+            custom_columns: HashMap::new(),
+            loaded: false,
+        }
+    }
+
+    /// Returns `false` if this is placeholder metadata from [`JoshutoMetadata::unloaded`].
+    pub fn is_loaded(&self) -> bool {
+        self.loaded
+    }
+
     /// Returns the entry's size in bytes, as reported by the filesystem.
     pub fn len(&self) -> u64 {
         self.len
@@ -166,6 +309,16 @@ impl JoshutoMetadata {
         self.accessed
     }
 
+    /// Returns the time the entry's status (permissions, ownership, contents) last changed.
+    pub fn changed(&self) -> time::SystemTime {
+        self.changed
+    }
+
+    /// Returns the entry's creation time, if the filesystem records it.
+    pub fn created(&self) -> Option<time::SystemTime> {
+        self.created
+    }
+
     /// Returns the kind of filesystem object this entry is.
     pub fn file_type(&self) -> FileType {
         self.file_type
@@ -1,11 +1,31 @@
 //! Filesystem entry, directory listing, and metadata types shared across joshuto.
 
+mod archive;
+mod compress;
 mod dirlist;
+mod disk_usage;
 mod entry;
+mod find;
+mod grep;
+mod ignore;
 mod metadata;
 mod options;
+mod tags;
+mod tree;
+mod vfs;
+mod virtual_list;
 
+pub use archive::*;
+pub use compress::*;
 pub use dirlist::*;
+pub use disk_usage::*;
 pub use entry::*;
+pub use find::*;
+pub use grep::*;
+pub use ignore::*;
 pub use metadata::*;
 pub use options::*;
+pub use tags::*;
+pub use tree::*;
+pub use vfs::*;
+pub use virtual_list::*;
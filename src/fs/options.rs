@@ -1,10 +1,14 @@
 use crate::types::state::MatchState;
 
+use super::TagMatch;
+
 /// Display options valid pre JoshutoDirList in a JoshutoTab
 #[derive(Clone, Debug)]
 pub struct DirListDisplayOptions {
     pub filter_state: MatchState,
     pub depth: u8,
+    /// the tags of the entries shown, see `filter_tag`
+    pub tag_filter: Option<TagMatch>,
 }
 
 impl DirListDisplayOptions {
@@ -27,6 +31,16 @@ impl DirListDisplayOptions {
     pub fn depth(&self) -> u8 {
         self.depth
     }
+
+    /// Sets which tags the entries of this directory list must have to be shown.
+    pub fn set_tag_filter(&mut self, tag_filter: Option<TagMatch>) {
+        self.tag_filter = tag_filter;
+    }
+
+    /// Returns which tags the entries of this directory list must have to be shown, if any.
+    pub fn tag_filter(&self) -> Option<TagMatch> {
+        self.tag_filter
+    }
 }
 
 impl std::default::Default for DirListDisplayOptions {
@@ -34,6 +48,7 @@ impl std::default::Default for DirListDisplayOptions {
         Self {
             filter_state: MatchState::None,
             depth: 0,
+            tag_filter: None,
         }
     }
 }
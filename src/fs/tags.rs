@@ -0,0 +1,173 @@
+use std::collections::BTreeMap;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::PROGRAM_NAME;
+
+/// The tag given by `tag_toggle` without a tag character.
+pub const DEFAULT_TAG: char = '*';
+
+const TAGS_FILE: &str = "tags";
+
+/// Tag characters of files by absolute path, stored in `$XDG_DATA_HOME/joshuto/tags` in
+/// ranger's format: one path per line, prefixed with `<tag>:` unless the tag is the default.
+#[derive(Debug, Default)]
+pub struct Tags {
+    /// where the tags are stored, `None` if there's no data directory
+    file: Option<PathBuf>,
+    tags: BTreeMap<PathBuf, char>,
+}
+
+impl Tags {
+    /// Reads the stored tags. A missing or unreadable file starts out empty.
+    pub fn load() -> Self {
+        let file = data_file();
+        let tags = file
+            .as_ref()
+            .and_then(|file| fs::read_to_string(file).ok())
+            .map(|s| parse(&s))
+            .unwrap_or_default();
+        Self { file, tags }
+    }
+
+    /// Returns the tag of `path`, if tagged.
+    pub fn get(&self, path: &Path) -> Option<char> {
+        self.tags.get(path).copied()
+    }
+
+    /// Tags `path` with `tag`, or untags it if `None`.
+    pub fn set(&mut self, path: &Path, tag: Option<char>) {
+        match tag {
+            Some(tag) => self.tags.insert(path.to_path_buf(), tag),
+            None => self.tags.remove(path),
+        };
+    }
+
+    /// Moves the tags of `from` and of everything below it to `to`. Returns `true` if there
+    /// were any.
+    pub fn rename(&mut self, from: &Path, to: &Path) -> bool {
+        let moved: Vec<PathBuf> = self
+            .tags
+            .range(from.to_path_buf()..)
+            .map(|(path, _)| path)
+            .take_while(|path| path.starts_with(from))
+            .cloned()
+            .collect();
+        for path in moved.iter() {
+            if let (Some(tag), Ok(rest)) = (self.tags.remove(path), path.strip_prefix(from)) {
+                self.tags.insert(to.join(rest), tag);
+            }
+        }
+        !moved.is_empty()
+    }
+
+    /// Writes the tags to disk.
+    pub fn save(&self) -> io::Result<()> {
+        let Some(file) = self.file.as_ref() else {
+            return Ok(());
+        };
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(file, serialize(&self.tags))
+    }
+}
+
+/// Which tags an entry must have to be shown by `filter_tag` or selected by `select_tag`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TagMatch {
+    Any,
+    Tag(char),
+}
+
+impl TagMatch {
+    /// Returns `true` if an entry tagged `tag` matches.
+    pub fn is_match(&self, tag: Option<char>) -> bool {
+        match self {
+            Self::Any => tag.is_some(),
+            Self::Tag(c) => tag == Some(*c),
+        }
+    }
+}
+
+impl fmt::Display for TagMatch {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Self::Any => write!(f, "any"),
+            Self::Tag(c) => write!(f, "{c}"),
+        }
+    }
+}
+
+fn parse(s: &str) -> BTreeMap<PathBuf, char> {
+    s.lines()
+        .filter(|line| !line.is_empty())
+        .map(|line| {
+            let mut chars = line.chars();
+            match (chars.next(), chars.next()) {
+                (Some(tag), Some(':')) => (PathBuf::from(chars.as_str()), tag),
+                _ => (PathBuf::from(line), DEFAULT_TAG),
+            }
+        })
+        .collect()
+}
+
+fn serialize(tags: &BTreeMap<PathBuf, char>) -> String {
+    let mut s = String::new();
+    for (path, tag) in tags {
+        if *tag != DEFAULT_TAG {
+            s.push(*tag);
+            s.push(':');
+        }
+        s.push_str(&path.to_string_lossy());
+        s.push('\n');
+    }
+    s
+}
+
+fn data_file() -> Option<PathBuf> {
+    let mut path = dirs::data_dir().or_else(|| {
+        let mut home = dirs::home_dir()?;
+        home.push(".local/share");
+        Some(home)
+    })?;
+    path.push(PROGRAM_NAME);
+    path.push(TAGS_FILE);
+    Some(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_serialize() {
+        let s = "/home/user/a\nr:/home/user/b c\n*:/home/user/d\n";
+        let tags = parse(s);
+        assert_eq!(tags.get(Path::new("/home/user/a")), Some(&'*'));
+        assert_eq!(tags.get(Path::new("/home/user/b c")), Some(&'r'));
+        assert_eq!(tags.get(Path::new("/home/user/d")), Some(&'*'));
+        assert_eq!(
+            serialize(&tags),
+            "/home/user/a\nr:/home/user/b c\n/home/user/d\n"
+        );
+    }
+
+    #[test]
+    fn test_rename() {
+        let mut tags = Tags::default();
+        tags.set(Path::new("/a/dir"), Some('*'));
+        tags.set(Path::new("/a/dir/file"), Some('r'));
+        tags.set(Path::new("/a/dir2"), Some('g'));
+
+        assert!(tags.rename(Path::new("/a/dir"), Path::new("/b/moved")));
+        assert_eq!(tags.get(Path::new("/b/moved")), Some('*'));
+        assert_eq!(tags.get(Path::new("/b/moved/file")), Some('r'));
+        assert_eq!(tags.get(Path::new("/a/dir/file")), None);
+        assert_eq!(tags.get(Path::new("/a/dir2")), Some('g'));
+
+        assert!(!tags.rename(Path::new("/a/none"), Path::new("/b/none")));
+    }
+}
@@ -0,0 +1,130 @@
+use std::collections::HashMap;
+use std::path::Path;
+
+use super::JoshutoDirEntry;
+
+/// Rearranges `contents`, a listing of `base` including the contents of expanded
+/// subdirectories, into tree order: every directory directly followed by its own contents.
+/// Siblings keep their relative order, so sorting `contents` first sorts every directory.
+pub fn tree_order(base: &Path, contents: &mut Vec<JoshutoDirEntry>) {
+    let order = tree_permutation(base, contents.iter().map(|e| e.file_path()));
+    let mut entries: Vec<Option<JoshutoDirEntry>> = contents.drain(..).map(Some).collect();
+    contents.extend(order.into_iter().filter_map(|i| entries[i].take()));
+}
+
+/// Returns the indices of `paths` in tree order below `base`. Entries whose parent isn't
+/// listed come last, in their original order.
+fn tree_permutation<'a>(base: &Path, paths: impl Iterator<Item = &'a Path>) -> Vec<usize> {
+    let paths: Vec<&Path> = paths.collect();
+    let mut children: HashMap<&Path, Vec<usize>> = HashMap::new();
+    for (i, path) in paths.iter().enumerate() {
+        if let Some(parent) = path.parent() {
+            children.entry(parent).or_default().push(i);
+        }
+    }
+
+    let mut order = Vec::with_capacity(paths.len());
+    let mut visited = vec![false; paths.len()];
+    let mut stack: Vec<usize> = children
+        .remove(base)
+        .unwrap_or_default()
+        .into_iter()
+        .rev()
+        .collect();
+    while let Some(i) = stack.pop() {
+        order.push(i);
+        visited[i] = true;
+        if let Some(c) = children.remove(paths[i]) {
+            stack.extend(c.into_iter().rev());
+        }
+    }
+    order.extend((0..paths.len()).filter(|i| !visited[*i]));
+    order
+}
+
+/// Returns how many directories deep below the listed directory `entry` is.
+pub fn tree_depth(entry: &JoshutoDirEntry) -> usize {
+    entry.file_name().matches('/').count()
+}
+
+/// Returns the name of `entry` within its own directory.
+pub fn tree_name(entry: &JoshutoDirEntry) -> &str {
+    let name = entry.file_name();
+    name.rsplit('/').next().unwrap_or(name)
+}
+
+/// Returns the indentation guides drawn before each entry of a listing in tree order, given
+/// how many directories deep each entry is.
+pub fn tree_guides(depths: &[usize]) -> Vec<String> {
+    // whether an entry at each depth follows before the subtree at that depth ends, for the
+    // entries after the current one
+    let mut continues: Vec<bool> = Vec::new();
+    let mut guides = vec![String::new(); depths.len()];
+    for (i, &depth) in depths.iter().enumerate().rev() {
+        if continues.len() <= depth {
+            continues.resize(depth + 1, false);
+        }
+        if depth > 0 {
+            let mut line: String = (1..depth)
+                .map(|d| if continues[d] { "│ " } else { "  " })
+                .collect();
+            line.push_str(if continues[depth] { "├ " } else { "└ " });
+            guides[i] = line;
+        }
+        continues[depth] = true;
+        continues.truncate(depth + 1);
+    }
+    guides
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_tree_permutation() {
+        // sorted by name with subdirectories expanded
+        let paths = [
+            "/d/a",
+            "/d/a/x",
+            "/d/b",
+            "/d/a/y",
+            "/d/a/x/1",
+            "/d/c",
+            "/e/orphan",
+        ];
+        let order = tree_permutation(Path::new("/d"), paths.iter().map(Path::new));
+        let ordered: Vec<&str> = order.into_iter().map(|i| paths[i]).collect();
+        assert_eq!(
+            ordered,
+            [
+                "/d/a",
+                "/d/a/x",
+                "/d/a/x/1",
+                "/d/a/y",
+                "/d/b",
+                "/d/c",
+                "/e/orphan"
+            ]
+        );
+    }
+
+    #[test]
+    fn test_tree_guides() {
+        // a
+        // ├ x
+        // │ └ 1
+        // └ y
+        // b
+        let depths = [0, 1, 2, 1, 0];
+        assert_eq!(tree_guides(&depths), ["", "├ ", "│ └ ", "└ ", ""]);
+
+        // a
+        // └ x
+        //   ├ 1
+        //   │ └ p
+        //   └ 2
+        let depths = [0, 1, 2, 3, 2];
+        assert_eq!(tree_guides(&depths), ["", "└ ", "  ├ ", "  │ └ ", "  └ "]);
+    }
+}
@@ -0,0 +1,170 @@
+use std::collections::hash_map::DefaultHasher;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::io;
+use std::os::unix::fs::DirBuilderExt;
+use std::path::{Path, PathBuf};
+use std::sync::OnceLock;
+
+use uuid::Uuid;
+
+use super::{archive_index, extract, ArchiveKind, FileType};
+
+/// Where a path leads: to the local filesystem, or inside an archive browsed as a directory.
+/// Paths inside an archive continue the archive's own path, as in `/tmp/a.zip/dir/file`.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VfsPath<'a> {
+    Local(&'a Path),
+    Archive {
+        /// the archive file
+        archive: &'a Path,
+        kind: ArchiveKind,
+        /// the path inside the archive, empty for its root
+        inner: &'a Path,
+    },
+}
+
+impl<'a> VfsPath<'a> {
+    /// Resolves `path`: it leads inside an archive if it or one of its ancestors is an archive
+    /// file. Only ancestors named like archives are stat'ed.
+    pub fn resolve(path: &'a Path) -> Self {
+        for ancestor in path.ancestors() {
+            if let Some(kind) = ArchiveKind::from_path(ancestor) {
+                if ancestor.is_file() {
+                    let inner = path.strip_prefix(ancestor).unwrap_or(Path::new(""));
+                    return Self::Archive {
+                        archive: ancestor,
+                        kind,
+                        inner,
+                    };
+                }
+            }
+        }
+        Self::Local(path)
+    }
+
+    /// Returns `true` if this leads inside an archive, or to an archive's root.
+    pub fn is_archive(&self) -> bool {
+        matches!(self, Self::Archive { .. })
+    }
+
+    /// Returns the closest directory on the local filesystem: the path itself, or the directory
+    /// containing the archive.
+    pub fn local_dir(&self) -> &'a Path {
+        match *self {
+            Self::Local(path) => path,
+            Self::Archive { archive, .. } => archive.parent().unwrap_or(archive),
+        }
+    }
+
+    /// Returns `true` if this can be listed: a directory, an archive, or a directory inside one.
+    pub fn is_dir(&self) -> bool {
+        match *self {
+            Self::Local(path) => path.is_dir(),
+            Self::Archive { inner, .. } if inner.as_os_str().is_empty() => true,
+            Self::Archive {
+                archive,
+                kind,
+                inner,
+            } => archive_index(archive, kind).is_ok_and(|index| {
+                index
+                    .get(inner)
+                    .is_some_and(|entry| entry.file_type == FileType::Directory)
+            }),
+        }
+    }
+
+    /// Returns `true` if this can't be written to: it's inside an archive.
+    pub fn is_read_only(&self) -> bool {
+        matches!(self, Self::Archive { inner, .. } if !inner.as_os_str().is_empty())
+    }
+
+    /// Returns a path on the local filesystem with the contents of this, for programs to read:
+    /// the path itself, or a copy extracted from the archive it is in. Copies are kept until
+    /// [`clear_local_copies`], and extracted again if the archive changes.
+    pub fn local_path(&self) -> io::Result<PathBuf> {
+        let (archive, kind, inner) = match *self {
+            Self::Local(path) => return Ok(path.to_path_buf()),
+            Self::Archive { archive, .. } if !self.is_read_only() => {
+                return Ok(archive.to_path_buf())
+            }
+            Self::Archive {
+                archive,
+                kind,
+                inner,
+            } => (archive, kind, inner),
+        };
+        let index = archive_index(archive, kind)?;
+        let mut hasher = DefaultHasher::new();
+        archive.hash(&mut hasher);
+        index.modified().hash(&mut hasher);
+        let dir = local_copies_dir()?.join(format!("{:016x}", hasher.finish()));
+        let local = dir.join(inner);
+        if local.symlink_metadata().is_ok() {
+            return Ok(local);
+        }
+
+        // extract under a temporary name first, so that nobody reads a partial copy
+        fs::create_dir_all(&dir)?;
+        let part = dir.join(format!(".{}.part", Uuid::new_v4()));
+        let res = extract(archive, kind, inner, &part, |_| {}).and_then(|_| {
+            if let Some(parent) = local.parent() {
+                fs::create_dir_all(parent)?;
+            }
+            fs::rename(&part, &local)
+        });
+        if let Err(e) = res {
+            let _ = fs::remove_dir_all(&part).or_else(|_| fs::remove_file(&part));
+            // another thread may have extracted it meanwhile
+            if local.symlink_metadata().is_err() {
+                return Err(e);
+            }
+        }
+        Ok(local)
+    }
+}
+
+/// Removes the copies of archive entries made by [`VfsPath::local_path`].
+pub fn clear_local_copies() {
+    if let Some(dir) = LOCAL_COPIES_DIR.get() {
+        let _ = fs::remove_dir_all(dir);
+    }
+}
+
+/// Where copies of archive entries are extracted, see [`local_copies_dir`].
+static LOCAL_COPIES_DIR: OnceLock<PathBuf> = OnceLock::new();
+
+/// Returns the directory copies of archive entries are extracted to, creating it on first use:
+/// a directory with an unpredictable name, only accessible to the user, in
+/// `$XDG_RUNTIME_DIR` or else the temporary directory.
+fn local_copies_dir() -> io::Result<&'static Path> {
+    let dir = LOCAL_COPIES_DIR.get_or_init(|| {
+        let base = std::env::var_os("XDG_RUNTIME_DIR")
+            .map(PathBuf::from)
+            .filter(|dir| dir.is_absolute())
+            .unwrap_or_else(std::env::temp_dir);
+        base.join(format!("joshuto-archives-{}", Uuid::new_v4()))
+    });
+    match fs::DirBuilder::new().mode(0o700).create(dir) {
+        // created on an earlier call: the name can't have been taken by anyone else
+        Err(e) if e.kind() == io::ErrorKind::AlreadyExists => Ok(dir),
+        res => res.map(|_| dir.as_path()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::os::unix::fs::PermissionsExt;
+
+    use super::*;
+
+    #[test]
+    fn test_local_copies_dir_is_private() {
+        let dir = local_copies_dir().unwrap();
+        let mode = fs::metadata(dir).unwrap().permissions().mode();
+        assert_eq!(mode & 0o777, 0o700);
+        // later calls reuse it
+        assert_eq!(local_copies_dir().unwrap(), dir);
+        clear_local_copies();
+    }
+}
@@ -0,0 +1,88 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::tab::TabDisplayOption;
+use crate::types::option::display::DisplayOption;
+
+use super::{JoshutoDirEntry, JoshutoDirList, JoshutoMetadata};
+
+/// Returns the deepest directory containing all of `paths`, all absolute.
+pub fn common_dir(paths: &[PathBuf]) -> PathBuf {
+    let mut dir = match paths.first().and_then(|p| p.parent()) {
+        Some(parent) => parent.to_path_buf(),
+        None => PathBuf::from("/"),
+    };
+    for path in paths.iter().skip(1) {
+        while !path.starts_with(&dir) && dir.pop() {}
+    }
+    dir
+}
+
+/// Builds a virtual listing of `paths`: entries named relative to `base`, a directory containing
+/// all of them, rather than the contents of a directory. Paths that don't exist (anymore) are left
+/// out. Selection and cursor position are carried over from `former`, the listing being
+/// reloaded, if any.
+pub fn virtual_dirlist(
+    base: PathBuf,
+    paths: Vec<PathBuf>,
+    former: Option<&JoshutoDirList>,
+    options: &DisplayOption,
+    tab_options: &TabDisplayOption,
+) -> io::Result<JoshutoDirList> {
+    let mut contents: Vec<JoshutoDirEntry> = paths
+        .iter()
+        .filter_map(|path| JoshutoDirEntry::from_path(path, &base, options, tab_options).ok())
+        .collect();
+
+    let sort_options = tab_options.sort_options_ref();
+    contents.sort_by(|f1, f2| sort_options.compare(f1, f2));
+
+    let mut index = if contents.is_empty() { None } else { Some(0) };
+    if let Some(former) = former {
+        let former_entries_by_path = HashMap::<&Path, &JoshutoDirEntry>::from_iter(
+            former.contents.iter().map(|e| (e.file_path(), e)),
+        );
+        for entry in contents.iter_mut() {
+            if let Some(former_entry) = former_entries_by_path.get(entry.file_path()) {
+                entry.set_permanent_selected(former_entry.is_permanent_selected());
+            }
+        }
+        if let Some(curr) = former.curr_entry_ref() {
+            index = contents
+                .iter()
+                .position(|e| e.file_path() == curr.file_path())
+                .or(index);
+        }
+    }
+
+    let viewport_index = former
+        .map(|former| former.first_index_for_viewport())
+        .unwrap_or_default()
+        .min(contents.len().saturating_sub(1));
+
+    let metadata = JoshutoMetadata::from(&base)?;
+    let mut dirlist = JoshutoDirList::new(base, contents, index, viewport_index, None, metadata);
+    dirlist.virtual_paths = Some(paths);
+    Ok(dirlist)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_common_dir() {
+        let paths = |paths: &[&str]| paths.iter().map(PathBuf::from).collect::<Vec<_>>();
+        assert_eq!(
+            common_dir(&paths(&["/a/b/c.log", "/a/b/d/e.log", "/a/b/f.log"])),
+            Path::new("/a/b")
+        );
+        assert_eq!(
+            common_dir(&paths(&["/a/b/c.log", "/a/bc/d.log"])),
+            Path::new("/a")
+        );
+        assert_eq!(common_dir(&paths(&["/a/b", "/c"])), Path::new("/"));
+        assert_eq!(common_dir(&paths(&["/a/b/c.log"])), Path::new("/a/b"));
+    }
+}
@@ -0,0 +1,5 @@
+//! Git integration: per-repository working tree status, read with the `git` command line tool.
+
+mod status;
+
+pub use status::*;
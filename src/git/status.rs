@@ -0,0 +1,317 @@
+use std::collections::HashMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+/// The git status of an entry, ordered by how prominently it is shown: a directory takes the
+/// highest status of anything inside it.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord)]
+pub enum GitStatus {
+    Ignored,
+    Untracked,
+    Staged,
+    Modified,
+    Conflicted,
+}
+
+impl GitStatus {
+    /// Returns the single-character marker shown in the `git` linemode column.
+    pub fn symbol(&self) -> &'static str {
+        match self {
+            Self::Ignored => "!",
+            Self::Untracked => "?",
+            Self::Staged => "S",
+            Self::Modified => "M",
+            Self::Conflicted => "U",
+        }
+    }
+
+    fn from_xy(xy: &str) -> Option<Self> {
+        let mut chars = xy.chars();
+        let (x, y) = (chars.next()?, chars.next()?);
+        if y != '.' {
+            Some(Self::Modified)
+        } else if x != '.' {
+            Some(Self::Staged)
+        } else {
+            None
+        }
+    }
+}
+
+/// A snapshot of `git status` for one repository.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct GitRepoStatus {
+    pub root: PathBuf,
+    /// The checked out branch, or `None` if `HEAD` is detached.
+    pub branch: Option<String>,
+    /// Commits ahead of and behind the upstream branch, if there is one.
+    pub ahead_behind: Option<(usize, usize)>,
+    /// Status of each changed entry, relative to `root`.
+    entries: HashMap<PathBuf, GitStatus>,
+    /// Aggregated status of each directory containing changed entries, relative to `root`.
+    dirs: HashMap<PathBuf, GitStatus>,
+}
+
+impl GitRepoStatus {
+    /// Runs `git status` for the repository containing `dir`. Returns `None` if `dir` isn't
+    /// inside a git work tree.
+    pub fn read(dir: &Path) -> io::Result<Option<Self>> {
+        let Some(root) = toplevel(dir)? else {
+            return Ok(None);
+        };
+
+        let args = [
+            "status",
+            "--porcelain=v2",
+            "--branch",
+            "--ignored=matching",
+            "-z",
+        ];
+        match git_output(&root, &args)? {
+            Some(output) => Ok(Some(Self::parse(root, &String::from_utf8_lossy(&output)))),
+            None => Ok(None),
+        }
+    }
+
+    /// Parses the output of `git status --porcelain=v2 --branch -z`.
+    pub fn parse(root: PathBuf, output: &str) -> Self {
+        let mut status = Self {
+            root,
+            ..Self::default()
+        };
+
+        let mut records = output.split('\0');
+        while let Some(record) = records.next() {
+            let (kind, rest) = record.split_once(' ').unwrap_or((record, ""));
+            match kind {
+                "#" => status.parse_header(rest),
+                "1" => {
+                    let fields: Vec<&str> = rest.splitn(8, ' ').collect();
+                    if let (Some(xy), Some(path)) = (fields.first(), fields.get(7)) {
+                        status.add(path, GitStatus::from_xy(xy));
+                    }
+                }
+                "2" => {
+                    let fields: Vec<&str> = rest.splitn(9, ' ').collect();
+                    if let (Some(xy), Some(path)) = (fields.first(), fields.get(8)) {
+                        status.add(path, GitStatus::from_xy(xy));
+                    }
+                    // the original path of a rename or copy follows as its own record
+                    records.next();
+                }
+                "u" => {
+                    if let Some(path) = rest.splitn(10, ' ').nth(9) {
+                        status.add(path, Some(GitStatus::Conflicted));
+                    }
+                }
+                "?" => status.add(rest, Some(GitStatus::Untracked)),
+                "!" => status.add(rest, Some(GitStatus::Ignored)),
+                _ => {}
+            }
+        }
+        status
+    }
+
+    fn parse_header(&mut self, header: &str) {
+        let Some((key, value)) = header.split_once(' ') else {
+            return;
+        };
+        match key {
+            "branch.head" if value != "(detached)" => self.branch = Some(value.to_string()),
+            "branch.ab" => {
+                let mut counts = value
+                    .split(' ')
+                    .map(|s| s[1..].parse::<usize>().unwrap_or_default());
+                if let (Some(ahead), Some(behind)) = (counts.next(), counts.next()) {
+                    self.ahead_behind = Some((ahead, behind));
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn add(&mut self, path: &str, status: Option<GitStatus>) {
+        let Some(status) = status else {
+            return;
+        };
+        let path = PathBuf::from(path.trim_end_matches('/'));
+        // ignored files don't make the directories around them interesting
+        if status != GitStatus::Ignored {
+            for dir in path.ancestors().skip(1) {
+                if dir.as_os_str().is_empty() {
+                    break;
+                }
+                let dir_status = self.dirs.entry(dir.to_path_buf()).or_insert(status);
+                *dir_status = (*dir_status).max(status);
+            }
+        }
+        self.entries.insert(path, status);
+    }
+
+    /// Returns the status of `path`, aggregated over its contents if it is a directory. Entries
+    /// inside untracked or ignored directories inherit their status.
+    pub fn status_of(&self, path: &Path) -> Option<GitStatus> {
+        let relative = path.strip_prefix(&self.root).ok()?;
+        if relative.as_os_str().is_empty() {
+            return None;
+        }
+        let own = self.entries.get(relative).copied();
+        let contents = self.dirs.get(relative).copied();
+        if own.is_some() || contents.is_some() {
+            return own.max(contents);
+        }
+        relative
+            .ancestors()
+            .skip(1)
+            .filter_map(|dir| self.entries.get(dir))
+            .find(|status| matches!(status, GitStatus::Untracked | GitStatus::Ignored))
+            .copied()
+    }
+}
+
+/// Returns the top level of the git work tree containing `dir`, or `None` if it isn't in one.
+/// git resolves symlinks, so where `dir` is reached through one, the top level is given as the
+/// ancestor of `dir` it is, for comparing with paths below `dir`.
+pub fn toplevel(dir: &Path) -> io::Result<Option<PathBuf>> {
+    let Some(output) = git_output(dir, &["rev-parse", "--show-toplevel"])? else {
+        return Ok(None);
+    };
+    let root = PathBuf::from(String::from_utf8_lossy(&output).trim_end());
+    Ok(Some(unresolved_ancestor(dir, &root).unwrap_or(root)))
+}
+
+/// Returns the ancestor of `dir` that resolves to `resolved`, where `resolved` is an ancestor
+/// of the canonical `dir`.
+fn unresolved_ancestor(dir: &Path, resolved: &Path) -> Option<PathBuf> {
+    let canonical = dir.canonicalize().ok()?;
+    let depth = canonical.strip_prefix(resolved).ok()?.components().count();
+    let ancestor = dir.ancestors().nth(depth)?;
+    (ancestor.canonicalize().ok()? == resolved).then(|| ancestor.to_path_buf())
+}
+
+/// Runs `git` with `args` in `dir`, returning its stdout, or `None` if it failed (e.g. because
+/// `dir` isn't in a repository).
+fn git_output(dir: &Path, args: &[&str]) -> io::Result<Option<Vec<u8>>> {
+    let output = Command::new("git")
+        .arg("-C")
+        .arg(dir)
+        .args(args)
+        .stdin(Stdio::null())
+        .stderr(Stdio::null())
+        .output()?;
+    Ok(output.status.success().then_some(output.stdout))
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::{Path, PathBuf};
+
+    use super::{GitRepoStatus, GitStatus};
+
+    const OUTPUT: &str = concat!(
+        "# branch.oid 0123456789abcdef0123456789abcdef01234567\0",
+        "# branch.head main\0",
+        "# branch.upstream origin/main\0",
+        "# branch.ab +2 -1\0",
+        "1 .M N... 100644 100644 100644 0123 0123 src/main.rs\0",
+        "1 A. N... 000000 100644 100644 0000 0123 src/new file.rs\0",
+        "2 R. N... 100644 100644 100644 0123 0123 R100 docs/renamed.md\0docs/old.md\0",
+        "u UU N... 100644 100644 100644 100644 0123 0123 0123 conflict.txt\0",
+        "? notes/\0",
+        "! target/\0",
+    );
+
+    fn status() -> GitRepoStatus {
+        GitRepoStatus::parse(PathBuf::from("/repo"), OUTPUT)
+    }
+
+    #[test]
+    fn branch_and_ahead_behind_are_parsed() {
+        let status = status();
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(status.ahead_behind, Some((2, 1)));
+    }
+
+    #[test]
+    fn entries_get_their_status() {
+        let status = status();
+        let of = |p: &str| status.status_of(&Path::new("/repo").join(p));
+        assert_eq!(of("src/main.rs"), Some(GitStatus::Modified));
+        assert_eq!(of("src/new file.rs"), Some(GitStatus::Staged));
+        assert_eq!(of("docs/renamed.md"), Some(GitStatus::Staged));
+        assert_eq!(of("docs/old.md"), None);
+        assert_eq!(of("conflict.txt"), Some(GitStatus::Conflicted));
+        assert_eq!(of("README.md"), None);
+    }
+
+    #[test]
+    fn directories_are_aggregated() {
+        let status = status();
+        let of = |p: &str| status.status_of(&Path::new("/repo").join(p));
+        assert_eq!(of("src"), Some(GitStatus::Modified));
+        assert_eq!(of("docs"), Some(GitStatus::Staged));
+        assert_eq!(of("notes"), Some(GitStatus::Untracked));
+        assert_eq!(of("notes/todo.md"), Some(GitStatus::Untracked));
+        assert_eq!(of("target"), Some(GitStatus::Ignored));
+        assert_eq!(of("target/debug/joshuto"), Some(GitStatus::Ignored));
+        assert_eq!(status.status_of(Path::new("/repo")), None);
+    }
+
+    #[test]
+    fn detached_head_has_no_branch() {
+        let status = GitRepoStatus::parse(PathBuf::from("/repo"), "# branch.head (detached)\0");
+        assert_eq!(status.branch, None);
+        assert_eq!(status.ahead_behind, None);
+    }
+
+    #[test]
+    fn reads_a_local_repository() {
+        let dir = std::env::temp_dir().join(format!("joshuto-git-status-{}", std::process::id()));
+        let _ = std::fs::remove_dir_all(&dir);
+        std::fs::create_dir_all(dir.join("sub")).unwrap();
+        let git = |args: &[&str]| {
+            std::process::Command::new("git")
+                .arg("-C")
+                .arg(&dir)
+                .args(args)
+                .output()
+                .map(|o| o.status.success())
+                .unwrap_or(false)
+        };
+        if !git(&["init", "-q", "-b", "main"]) {
+            // git isn't available
+            let _ = std::fs::remove_dir_all(&dir);
+            return;
+        }
+        std::fs::write(dir.join("staged.txt"), "a").unwrap();
+        std::fs::write(dir.join("sub/untracked.txt"), "b").unwrap();
+        assert!(git(&["add", "staged.txt"]));
+
+        let status = GitRepoStatus::read(&dir.join("sub")).unwrap().unwrap();
+        let root = status.root.clone();
+        assert_eq!(status.branch.as_deref(), Some("main"));
+        assert_eq!(
+            status.status_of(&root.join("staged.txt")),
+            Some(GitStatus::Staged)
+        );
+        assert_eq!(
+            status.status_of(&root.join("sub/untracked.txt")),
+            Some(GitStatus::Untracked)
+        );
+
+        // through a symlink, paths are compared as they are browsed
+        let link = dir.with_extension("link");
+        let _ = std::fs::remove_file(&link);
+        std::os::unix::fs::symlink(&dir, &link).unwrap();
+        let status = GitRepoStatus::read(&link.join("sub")).unwrap().unwrap();
+        assert_eq!(status.root, link);
+        assert_eq!(
+            status.status_of(&link.join("sub/untracked.txt")),
+            Some(GitStatus::Untracked)
+        );
+
+        std::fs::remove_file(&link).unwrap();
+        std::fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,10 +1,14 @@
-use std::collections::HashMap;
+use std::cell::Cell;
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::path::{Path, PathBuf};
 
 use walkdir::WalkDir;
 
-use crate::fs::{DirListDisplayOptions, JoshutoDirEntry, JoshutoDirList, JoshutoMetadata};
+use crate::fs::{
+    archive_index, tree_order, ArchiveKind, DirListDisplayOptions, FileType, IgnoreMatcher,
+    JoshutoDirEntry, JoshutoDirList, JoshutoMetadata, VfsPath,
+};
 use crate::tab::TabDisplayOption;
 use crate::types::option::display::DisplayOption;
 use crate::types::state::UiState;
@@ -51,7 +55,10 @@ pub fn create_dirlist_with_history(
     tab_options: &TabDisplayOption,
 ) -> io::Result<JoshutoDirList> {
     let filter_func = options.filter_func();
-    let mut contents = read_directory(path, filter_func, options, tab_options)?;
+    let (mut contents, hidden_count) = read_directory(path, filter_func, options, tab_options)?;
+    if options.mode.is_tree() {
+        expand_tree(history, path, &mut contents, options, tab_options);
+    }
 
     // re-use directory size information on reload
     for entry in contents.iter_mut() {
@@ -80,6 +87,9 @@ pub fn create_dirlist_with_history(
 
     let sort_options = tab_options.sort_options_ref();
     contents.sort_by(|f1, f2| sort_options.compare(f1, f2));
+    if options.mode.is_tree() {
+        tree_order(path, &mut contents);
+    }
 
     let contents_len = contents.len();
     let index = if contents_len == 0 {
@@ -126,7 +136,7 @@ pub fn create_dirlist_with_history(
     });
 
     let metadata = JoshutoMetadata::from(path)?;
-    let dirlist = JoshutoDirList::new(
+    let mut dirlist = JoshutoDirList::new(
         path.to_path_buf(),
         contents,
         index,
@@ -134,52 +144,222 @@ pub fn create_dirlist_with_history(
         visual_mode_anchor_index,
         metadata,
     );
+    dirlist.hidden_count = hidden_count;
+    if options.mode.is_tree() {
+        dirlist.update_tree_guides();
+    }
 
     Ok(dirlist)
 }
 
+/// Adds the contents of the expanded directories among `contents` to it, recursively, named
+/// relative to `base`. Contents are taken from `history` where it's up to date, and read from
+/// disk otherwise.
+fn expand_tree(
+    history: &JoshutoHistory,
+    base: &Path,
+    contents: &mut Vec<JoshutoDirEntry>,
+    options: &DisplayOption,
+    tab_options: &TabDisplayOption,
+) {
+    let mut listed: HashSet<PathBuf> = contents.iter().map(|e| e.file_path_buf()).collect();
+    let mut i = 0;
+    while i < contents.len() {
+        let entry = &contents[i];
+        i += 1;
+        if !entry.metadata.is_dir() || !tab_options.is_expanded(entry.file_path()) {
+            continue;
+        }
+        let dir = entry.file_path_buf();
+        let children = match history.get(&dir) {
+            Some(list) if list.load_id.is_none() && !list.need_update() => list.contents.clone(),
+            _ => read_directory(&dir, options.filter_func(), options, tab_options)
+                .map(|(contents, _)| contents)
+                .unwrap_or_default(),
+        };
+        for mut child in children {
+            // listings of the expanded directory may include expanded directories of its own
+            if child.file_path().parent() != Some(dir.as_path())
+                || !listed.insert(child.file_path_buf())
+            {
+                continue;
+            }
+            child.rebase(base);
+            contents.push(child);
+        }
+    }
+}
+
+/// Returns the contents of the expanded directories among `dirs`, entries of `base`,
+/// recursively, named relative to `base`, for listings of `base` read without them.
+pub fn read_expanded_dirs(
+    base: &Path,
+    mut dirs: Vec<JoshutoDirEntry>,
+    options: &DisplayOption,
+    tab_options: &TabDisplayOption,
+) -> Vec<JoshutoDirEntry> {
+    let count = dirs.len();
+    expand_tree(
+        &JoshutoHistory::new(),
+        base,
+        &mut dirs,
+        options,
+        tab_options,
+    );
+    dirs.split_off(count)
+}
+
 /// Reads the immediate (or, if flattened, nested) contents of `path` from disk, keeping only
-/// entries that pass `filter_func`.
+/// entries that pass `filter_func`. Also returns how many entries were left out for being
+/// hidden.
 pub fn read_directory<F>(
     path: &Path,
     filter_func: F,
     display_options: &DisplayOption,
     tab_options: &TabDisplayOption,
-) -> io::Result<Vec<JoshutoDirEntry>>
+) -> io::Result<(Vec<JoshutoDirEntry>, usize)>
 where
     F: Fn(&walkdir::DirEntry, &DisplayOption, &DirListDisplayOptions) -> bool,
 {
+    if let VfsPath::Archive {
+        archive,
+        kind,
+        inner,
+    } = VfsPath::resolve(path)
+    {
+        return read_archive_directory(path, archive, kind, inner, display_options, tab_options);
+    }
+
+    let hidden = Cell::new(0);
+    let results: Vec<JoshutoDirEntry> =
+        walk_directory(path, filter_func, display_options, tab_options, &hidden)
+            .filter_map(|e| JoshutoDirEntry::from(&e, path, display_options, tab_options).ok())
+            .collect();
+    Ok((results, hidden.get()))
+}
+
+/// Reads the contents of `path`, the directory `inner` of (or the root of) the `kind` archive at
+/// `archive`, from the archive's index. Entries are shown as [`read_directory`] shows those of
+/// a local directory, with their metadata, but without a MIME type, and hidden ones are counted
+/// the same way.
+fn read_archive_directory(
+    path: &Path,
+    archive: &Path,
+    kind: ArchiveKind,
+    inner: &Path,
+    display_options: &DisplayOption,
+    tab_options: &TabDisplayOption,
+) -> io::Result<(Vec<JoshutoDirEntry>, usize)> {
     let dirlist_opts = tab_options
         .dirlist_options_ref(&path.to_path_buf())
         .map(|v| v.to_owned())
         .unwrap_or_default();
+    let index = archive_index(archive, kind)?;
 
-    let results: Vec<JoshutoDirEntry> = WalkDir::new(path)
+    let is_listed =
+        |p: &Path, is_dir| is_path_listed(path, p, is_dir, display_options, &dirlist_opts, None);
+    // hidden entries count if the walk would have reached them, that is if their directory is
+    // listed
+    let is_hidden = |p: &Path| {
+        !display_options.show_hidden
+            && p.file_name()
+                .and_then(|s| s.to_str())
+                .is_some_and(|name| display_options.is_hidden(name))
+            && p.parent()
+                .is_some_and(|parent| parent == path || is_listed(parent, true))
+    };
+    let mut hidden = 0;
+    let results = index
+        .entries_below(inner, dirlist_opts.depth() as usize)
+        .map(|(entry_path, entry)| (archive.join(entry_path), entry))
+        .filter(|(entry_path, entry)| {
+            if is_hidden(entry_path) {
+                hidden += 1;
+                return false;
+            }
+            is_listed(entry_path, entry.file_type == FileType::Directory)
+        })
+        .map(|(entry_path, entry)| {
+            let metadata = JoshutoMetadata::from_archive_entry(entry);
+            JoshutoDirEntry::from_metadata(entry_path, path, metadata)
+        })
+        .collect();
+    Ok((results, hidden))
+}
+
+/// Returns `true` if the entry at `path`, below `base`, is listed the way [`walk_directory`]
+/// lists it: it and the directories in between pass the hidden-file setting and the listing's
+/// filters, and aren't ignored by `ignore`. Entries are left out along with the directory
+/// they're in.
+pub fn is_path_listed(
+    base: &Path,
+    path: &Path,
+    is_dir: bool,
+    display_options: &DisplayOption,
+    dirlist_opts: &DirListDisplayOptions,
+    ignore: Option<&IgnoreMatcher>,
+) -> bool {
+    path.ancestors()
+        .take_while(|p| *p != base)
+        .enumerate()
+        .all(|(i, p)| {
+            let Some(name) = p.file_name().and_then(|s| s.to_str()) else {
+                return false;
+            };
+            display_options.is_listed(p, name, dirlist_opts)
+                && !ignore.is_some_and(|m| m.is_ignored(p, i > 0 || is_dir))
+        })
+}
+
+/// Lists the immediate (or, if flattened, nested) entries of `path` that pass `filter_func`,
+/// and aren't ignored unless the tab shows ignored entries, without reading their metadata.
+/// Entries left out for being hidden are counted in `hidden` as the walk goes.
+pub fn walk_directory<'a, F>(
+    path: &'a Path,
+    filter_func: F,
+    display_options: &'a DisplayOption,
+    tab_options: &TabDisplayOption,
+    hidden: &'a Cell<usize>,
+) -> impl Iterator<Item = walkdir::DirEntry> + 'a
+where
+    F: Fn(&walkdir::DirEntry, &DisplayOption, &DirListDisplayOptions) -> bool + 'a,
+{
+    let dirlist_opts = tab_options
+        .dirlist_options_ref(&path.to_path_buf())
+        .map(|v| v.to_owned())
+        .unwrap_or_default();
+
+    let ignore = (!tab_options.show_ignored).then(|| IgnoreMatcher::new(path));
+
+    WalkDir::new(path)
         .max_depth(dirlist_opts.depth() as usize + 1)
         .into_iter()
-        .filter_entry(|e| {
+        .filter_entry(move |e| {
             if e.path().to_str().cmp(&path.to_str()).is_ne() {
+                let is_hidden = e
+                    .file_name()
+                    .to_str()
+                    .is_some_and(|name| display_options.is_hidden(name));
+                if is_hidden && !display_options.show_hidden {
+                    hidden.set(hidden.get() + 1);
+                    return false;
+                }
                 filter_func(e, display_options, &dirlist_opts)
+                    && !ignore
+                        .as_ref()
+                        .is_some_and(|m| m.is_ignored(e.path(), e.file_type().is_dir()))
             } else {
                 true
             }
         })
-        .filter(|e| {
-            if let Ok(e) = e.as_ref() {
-                e.path().to_str().cmp(&path.to_str()).is_ne()
-            } else {
-                true
-            }
-        })
-        .filter_map(|res| JoshutoDirEntry::from(&res.ok()?, path, display_options).ok())
-        .collect();
-
-    Ok(results)
+        .filter_map(|res| res.ok())
+        .filter(move |e| e.path().to_str().cmp(&path.to_str()).is_ne())
 }
 
 /// Builds a directory listing for `path` and each of its ancestors up to the filesystem root,
 /// with each listing's cursor set to the child it descended through, reusing `history` where
-/// possible.
+/// possible. Listings inside archives that aren't in `history` are left out, to be read in the
+/// background: reading an archive's index can take long.
 pub fn generate_entries_to_root(
     path: &Path,
     history: &JoshutoHistory,
@@ -200,6 +380,9 @@ pub fn generate_entries_to_root(
                 }
             }
             dirlists.push(new_dirlist);
+        } else if VfsPath::resolve(curr).is_archive() {
+            prev = Some(curr);
+            continue;
         } else {
             let mut new_dirlist = JoshutoDirList::from_path(
                 curr.to_path_buf().clone(),
@@ -6,6 +6,7 @@ mod config;
 mod constants;
 mod error;
 mod fs;
+mod git;
 mod history;
 mod preview;
 mod run;
@@ -29,6 +30,7 @@ use config::icon::AppIcons;
 use config::mimetype::AppProgramRegistry;
 use config::preview::FileEntryPreview;
 use config::theme::AppTheme;
+use fs::Tags;
 use lazy_static::lazy_static;
 
 use traits::config::TomlConfigFile;
@@ -75,6 +77,7 @@ lazy_static! {
     static ref MIMETYPE_T: AppProgramRegistry = AppProgramRegistry::get_config();
     static ref PREVIEW_T: FileEntryPreview = FileEntryPreview::get_config_or_default();
     static ref BOOKMARKS_T: Mutex<Bookmarks> = Mutex::new(Bookmarks::get_config());
+    static ref TAGS_T: Mutex<Tags> = Mutex::new(Tags::load());
     static ref ICONS_T: AppIcons = AppIcons::get_config();
 
     static ref HOME_DIR: Option<PathBuf> = dirs::home_dir();
@@ -110,6 +113,10 @@ pub struct Args {
     #[arg(long = "output-file")]
     output_file: Option<PathBuf>,
 
+    /// Read paths from stdin, one per line, and start in a virtual listing of them.
+    #[arg(long = "stdin")]
+    stdin: bool,
+
     /// Directory to start in, if given.
     #[arg(name = "ARGUMENTS")]
     rest: Vec<PathBuf>,
@@ -159,6 +166,13 @@ fn run_main(args: Args) -> Result<i32, AppError> {
         cwd::set_current_dir(path)?;
     }
 
+    // read before the input listener takes over stdin
+    let stdin_paths = if args.stdin {
+        Some(read_stdin_paths()?)
+    } else {
+        None
+    };
+
     // make sure all configs have been loaded before starting
     let config = AppConfig::get_config();
     let keymap = AppKeyMapping::get_config();
@@ -167,6 +181,7 @@ fn run_main(args: Args) -> Result<i32, AppError> {
     lazy_static::initialize(&MIMETYPE_T);
     lazy_static::initialize(&PREVIEW_T);
     lazy_static::initialize(&BOOKMARKS_T);
+    lazy_static::initialize(&TAGS_T);
     lazy_static::initialize(&ICONS_T);
 
     lazy_static::initialize(&HOME_DIR);
@@ -177,12 +192,26 @@ fn run_main(args: Args) -> Result<i32, AppError> {
     let mut app_state = AppState::new(config, args.clone());
     {
         let mut backend: ui::AppBackend = ui::AppBackend::new(mouse_support)?;
-        run::process_run_loop::run_loop(&mut backend, &mut app_state, keymap)?;
+        run::process_run_loop::run_loop(&mut backend, &mut app_state, keymap, stdin_paths)?;
     }
+    fs::clear_local_copies();
     run_quit(&args, &app_state)?;
     Ok(app_state.quit.exit_code())
 }
 
+/// Reads paths from stdin, one per line, then reconnects stdin to the terminal for input.
+fn read_stdin_paths() -> Result<Vec<PathBuf>, AppError> {
+    let paths = std::io::stdin()
+        .lines()
+        .map_while(Result::ok)
+        .filter(|line| !line.is_empty())
+        .map(PathBuf::from)
+        .collect();
+    let tty = File::open("/dev/tty")?;
+    nix::unistd::dup2_stdin(&tty).map_err(std::io::Error::from)?;
+    Ok(paths)
+}
+
 fn run_quit(args: &Args, app_state: &AppState) -> Result<(), AppError> {
     match &args.output_file {
         Some(output_path) => match app_state.quit {
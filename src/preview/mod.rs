@@ -4,3 +4,4 @@ pub mod preview_default;
 pub mod preview_dir;
 pub mod preview_entry;
 pub mod preview_file;
+pub mod preview_text;
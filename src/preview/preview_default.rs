@@ -1,14 +1,16 @@
 use std::path;
 
-use crate::fs::JoshutoMetadata;
+use crate::fs::{JoshutoMetadata, VfsPath};
 use crate::preview::preview_dir;
 use crate::types::state::AppState;
 use crate::ui::AppBackend;
 
 /// Loads a preview for the entry currently under the cursor (or the current directory, if
-/// empty) in the active tab.
+/// empty) in the active tab. The listings of the current directory and its parent are loaded
+/// too if they're missing, as they are while entering an archive.
 pub fn load_previews(app_state: &mut AppState, backend: &mut AppBackend) {
     let mut load_list = Vec::with_capacity(2);
+    let mut dirs = Vec::with_capacity(2);
 
     let curr_tab = app_state.state.tab_state_ref().curr_tab_ref();
     match curr_tab.curr_list_ref() {
@@ -18,20 +20,23 @@ pub fn load_previews(app_state: &mut AppState, backend: &mut AppBackend) {
                 load_list.push((entry.file_path().to_path_buf(), entry.metadata.clone()));
             }
         }
-        None => {
-            if let Ok(metadata) = JoshutoMetadata::from(curr_tab.get_cwd()) {
-                load_list.push((curr_tab.get_cwd().to_path_buf(), metadata));
-            }
-        }
+        None => dirs.push(curr_tab.get_cwd().to_path_buf()),
+    }
+    if curr_tab.parent_list_ref().is_none() {
+        dirs.extend(curr_tab.get_cwd().parent().map(path::Path::to_path_buf));
     }
 
+    for dir in dirs {
+        load_dir_preview(app_state, dir);
+    }
     for (path, metadata) in load_list {
         load_preview_path(app_state, backend, path, metadata);
     }
 }
 
-/// Loads a preview for `p`: kicks off a background directory read if it's a directory not
-/// already loading or cached, or loads a file preview if it's under the configured size limit.
+/// Loads a preview for `p`: kicks off a background directory read if it's a directory (or an
+/// archive, if configured to be previewed as one) not already loading or cached, or loads a
+/// file preview if it's under the configured size limit.
 pub fn load_preview_path(
     app_state: &mut AppState,
     backend: &mut AppBackend,
@@ -39,24 +44,12 @@ pub fn load_preview_path(
     metadata: JoshutoMetadata,
 ) {
     let preview_options = &app_state.config.preview_options;
-    if metadata.is_dir() {
-        let tab = app_state.state.tab_state_ref().curr_tab_ref();
-        // only load if there doesn't already exist a loading thread and
-        // there isn't an entry in history
-        let need_to_load = tab
-            .history_metadata_ref()
-            .get(p.as_path())
-            .map(|m| m.is_loading())
-            .unwrap_or(true)
-            && tab
-                .history_ref()
-                .get(p.as_path())
-                .map(|e| e.need_update())
-                .unwrap_or(true);
-
-        if need_to_load {
-            preview_dir::Background::load_preview(app_state, p);
-        }
+    let is_archive = || {
+        let vfs_path = VfsPath::resolve(&p);
+        vfs_path.is_archive() && vfs_path.is_dir()
+    };
+    if metadata.is_dir() || (preview_options.preview_archives && is_archive()) {
+        load_dir_preview(app_state, p);
     } else if metadata.len() <= preview_options.max_preview_size {
         app_state
             .state
@@ -64,3 +57,18 @@ pub fn load_preview_path(
             .load_preview(&app_state.config, backend, p);
     }
 }
+
+/// Starts reading the directory `p` in the background, unless it's already loading or cached.
+fn load_dir_preview(app_state: &mut AppState, p: path::PathBuf) {
+    let tab = app_state.state.tab_state_ref().curr_tab_ref();
+    let need_to_load = !tab.history_metadata_ref().contains_key(p.as_path())
+        && tab
+            .history_ref()
+            .get(p.as_path())
+            .map(|e| e.need_update())
+            .unwrap_or(true);
+
+    if need_to_load {
+        preview_dir::Background::load_preview(app_state, p);
+    }
+}
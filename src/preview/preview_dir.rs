@@ -1,30 +1,48 @@
+use std::cell::Cell;
+use std::io;
 use std::path;
 use std::thread;
 
-use crate::fs::JoshutoDirList;
+use uuid::Uuid;
+
+use crate::fs::{load_metadata, JoshutoDirEntry, JoshutoMetadata, VfsPath};
+use crate::history::{read_directory, read_expanded_dirs, walk_directory};
 use crate::types::event::AppEvent;
 use crate::types::state::AppState;
 
+/// Number of entries sent in the first batch of a directory load. Later batches double in size
+/// (up to [`MAX_BATCH_SIZE`] for metadata), so huge directories show up quickly without
+/// flooding the event loop.
+const FIRST_BATCH_SIZE: usize = 256;
+const MAX_BATCH_SIZE: usize = 16384;
+
 /// Status of a directory preview being generated on a background thread.
 #[derive(Debug, Clone)]
 pub enum PreviewDirState {
-    Loading,
+    Loading { id: Uuid, count: usize },
     Error { message: String },
 }
 
-impl PreviewDirState {
-    /// Returns `true` if the preview is still being generated.
-    pub fn is_loading(&self) -> bool {
-        matches!(*self, Self::Loading)
-    }
+/// A piece of a directory listing streamed from a background load.
+#[derive(Debug)]
+pub enum DirLoadUpdate {
+    /// Newly read entries, with only their names and file types filled in.
+    Entries(Vec<JoshutoDirEntry>),
+    /// Metadata for previously sent entries, by file name. `None` if the entry has vanished.
+    Metadata(Vec<(String, Option<JoshutoMetadata>)>),
+    /// All entries and their metadata have been sent, and this many entries were hidden.
+    Done {
+        hidden_count: usize,
+    },
+    Error(io::Error),
 }
 
 /// Namespace for spawning background directory-preview loads.
 pub struct Background {}
 
 impl Background {
-    /// Spawns a background thread that reads `dir_path` and posts an [`AppEvent::PreviewDir`]
-    /// with the result when done.
+    /// Spawns a background thread that reads `dir_path` and streams it to the main loop as
+    /// [`AppEvent::PreviewDir`] updates: entry names first, then their metadata.
     pub fn load_preview(
         app_state: &mut AppState,
         dir_path: path::PathBuf,
@@ -38,6 +56,7 @@ impl Background {
             .option_ref()
             .clone();
         let tab_id = app_state.state.tab_state_ref().curr_tab_id();
+        let load_id = Uuid::new_v4();
 
         // add to loading state
         app_state
@@ -45,17 +64,114 @@ impl Background {
             .tab_state_mut()
             .curr_tab_mut()
             .history_metadata_mut()
-            .insert(dir_path.clone(), PreviewDirState::Loading);
+            .insert(
+                dir_path.clone(),
+                PreviewDirState::Loading {
+                    id: load_id,
+                    count: 0,
+                },
+            );
 
         thread::spawn(move || {
-            let path_clone = dir_path.clone();
-            let dir_res = JoshutoDirList::from_path(dir_path, &options, &tab_options);
-            let res = AppEvent::PreviewDir {
-                id: tab_id,
-                path: path_clone,
-                res: Box::new(dir_res),
+            let send = |update: DirLoadUpdate| {
+                let event = AppEvent::PreviewDir {
+                    id: tab_id,
+                    load_id,
+                    path: dir_path.clone(),
+                    update,
+                };
+                event_tx.send(event).is_ok()
             };
-            let _ = event_tx.send(res);
+
+            if let Err(e) = JoshutoMetadata::from(&dir_path) {
+                send(DirLoadUpdate::Error(e));
+                return;
+            }
+
+            // archives are listed from their index, with all metadata at once
+            if VfsPath::resolve(&dir_path).is_archive() {
+                let filter_func = options.filter_func();
+                match read_directory(&dir_path, filter_func, &options, &tab_options) {
+                    Ok((entries, hidden_count)) => {
+                        if send(DirLoadUpdate::Entries(entries)) {
+                            send(DirLoadUpdate::Done { hidden_count });
+                        }
+                    }
+                    Err(e) => {
+                        send(DirLoadUpdate::Error(e));
+                    }
+                }
+                return;
+            }
+
+            // names first, so the listing can be shown and navigated right away
+            let mut names = Vec::new();
+            let mut batch = Vec::with_capacity(FIRST_BATCH_SIZE);
+            let filter_func = options.filter_func();
+            let hidden = Cell::new(0);
+            for direntry in walk_directory(&dir_path, filter_func, &options, &tab_options, &hidden)
+            {
+                let entry = JoshutoDirEntry::from_unloaded(&direntry, &dir_path);
+                names.push(entry.file_name().to_string());
+                batch.push(entry);
+                let sent = names.len() - batch.len();
+                if batch.len() >= FIRST_BATCH_SIZE.max(sent)
+                    && !send(DirLoadUpdate::Entries(std::mem::take(&mut batch)))
+                {
+                    return;
+                }
+            }
+            if !send(DirLoadUpdate::Entries(batch)) {
+                return;
+            }
+
+            // then fill in the metadata
+            let mut batch_size = FIRST_BATCH_SIZE;
+            let mut names = names.into_iter().peekable();
+            let mut expanded_dirs = Vec::new();
+            while names.peek().is_some() {
+                let batch: Vec<_> = names
+                    .by_ref()
+                    .take(batch_size)
+                    .map(|name| {
+                        let path = dir_path.join(&name);
+                        let metadata = load_metadata(&path, &options, &tab_options).ok();
+                        if let Some(metadata) = metadata.as_ref() {
+                            if metadata.is_dir() && tab_options.is_expanded(&path) {
+                                let entry = JoshutoDirEntry::from_metadata(
+                                    path,
+                                    &dir_path,
+                                    metadata.clone(),
+                                );
+                                expanded_dirs.push(entry);
+                            }
+                        }
+                        (name, metadata)
+                    })
+                    .collect();
+                if !send(DirLoadUpdate::Metadata(batch)) {
+                    return;
+                }
+                batch_size = (batch_size * 2).min(MAX_BATCH_SIZE);
+            }
+
+            // in the tree mode, the contents of expanded directories follow, read whole; their
+            // metadata is sent too for entries kept from an earlier listing
+            if options.mode.is_tree() && !expanded_dirs.is_empty() {
+                let entries = read_expanded_dirs(&dir_path, expanded_dirs, &options, &tab_options);
+                let metadata = entries
+                    .iter()
+                    .map(|e| (e.file_name().to_string(), Some(e.metadata.clone())))
+                    .collect();
+                if !send(DirLoadUpdate::Entries(entries))
+                    || !send(DirLoadUpdate::Metadata(metadata))
+                {
+                    return;
+                }
+            }
+            send(DirLoadUpdate::Done {
+                hidden_count: hidden.get(),
+            });
         })
     }
 }
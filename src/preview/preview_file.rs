@@ -1,5 +1,7 @@
 use std::fmt::Debug;
-use std::{process::Output, time};
+use std::os::unix::process::ExitStatusExt;
+use std::process::{ExitStatus, Output};
+use std::time;
 
 /// Status of a file preview: still being generated, failed, or ready to display.
 #[derive(Clone)]
@@ -28,11 +30,14 @@ pub struct FilePreview {
     pub _modified: time::SystemTime,
 }
 
-impl std::convert::From<Output> for FilePreview {
-    fn from(output: Output) -> Self {
-        let s = String::from_utf8_lossy(&output.stdout).to_string();
+impl FilePreview {
+    /// Builds a successful preview showing `text`, as generated without a preview script.
+    pub fn from_text(text: String) -> Self {
+        Self::new(ExitStatus::from_raw(0), &text)
+    }
+
+    fn new(status: ExitStatus, s: &str) -> Self {
         let s2 = s.replace('\t', "        ");
-        let status = output.status;
         let modified = time::SystemTime::now();
         Self {
             status,
@@ -42,3 +47,10 @@ impl std::convert::From<Output> for FilePreview {
         }
     }
 }
+
+impl std::convert::From<Output> for FilePreview {
+    fn from(output: Output) -> Self {
+        let s = String::from_utf8_lossy(&output.stdout);
+        Self::new(output.status, &s)
+    }
+}
@@ -0,0 +1,207 @@
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+
+use crate::error::AppResult;
+use crate::preview::preview_file::FilePreview;
+
+/// How much of a file is read for its text preview.
+const MAX_TEXT_PREVIEW_SIZE: u64 = 64 * 1024;
+
+/// Generates a preview of the text file at `path` without a preview script, from its beginning,
+/// highlighted by its extension or shebang with the `syntax_theme` when the `syntax_highlight`
+/// feature is enabled. Fails with `InvalidData` for binary files.
+#[cfg_attr(not(feature = "syntax_highlight"), allow(unused_variables))]
+pub fn text_preview(path: &Path, syntax_theme: &str) -> io::Result<FilePreview> {
+    let mut bytes = Vec::new();
+    fs::File::open(path)?
+        .take(MAX_TEXT_PREVIEW_SIZE)
+        .read_to_end(&mut bytes)?;
+    let truncated = bytes.len() as u64 == MAX_TEXT_PREVIEW_SIZE;
+    let text = decode_text(&bytes, truncated)
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidData, "binary file"))?;
+
+    #[cfg(feature = "syntax_highlight")]
+    let text = highlight::highlight(path, &text, syntax_theme);
+    Ok(FilePreview::from_text(text))
+}
+
+/// Checks that text can be highlighted with the theme named `syntax_theme`, if highlighting
+/// is enabled.
+#[cfg_attr(not(feature = "syntax_highlight"), allow(unused_variables))]
+pub fn check_syntax_theme(syntax_theme: &str) -> AppResult<()> {
+    #[cfg(feature = "syntax_highlight")]
+    if !highlight::THEME_SET.themes.contains_key(syntax_theme) {
+        let mut names: Vec<_> = highlight::THEME_SET
+            .themes
+            .keys()
+            .map(String::as_str)
+            .collect();
+        names.sort_unstable();
+        return Err(crate::error::AppError::new(
+            crate::error::AppErrorKind::Config,
+            format!(
+                "unknown syntax_theme '{syntax_theme}', expected one of: {}",
+                names.join(", ")
+            ),
+        ));
+    }
+    Ok(())
+}
+
+/// Decodes the beginning of a text file: UTF-8, or UTF-16 with a byte order mark, falling back
+/// to Latin-1. Control characters other than newlines and tabs are left out, so that they don't
+/// mess up the terminal. Returns `None` for binary content: with NUL bytes or mostly control
+/// characters. A character cut off at the end is ignored if `truncated`.
+fn decode_text(bytes: &[u8], truncated: bool) -> Option<String> {
+    let text = if let Some(rest) = bytes.strip_prefix(b"\xef\xbb\xbf") {
+        String::from_utf8_lossy(rest).into_owned()
+    } else if let Some(rest) = bytes.strip_prefix(b"\xff\xfe") {
+        decode_utf16(rest, u16::from_le_bytes)
+    } else if let Some(rest) = bytes.strip_prefix(b"\xfe\xff") {
+        decode_utf16(rest, u16::from_be_bytes)
+    } else {
+        if bytes.contains(&0) || is_mostly_control(bytes) {
+            return None;
+        }
+        match std::str::from_utf8(bytes) {
+            Ok(s) => s.to_string(),
+            Err(e) if truncated && e.error_len().is_none() => {
+                String::from_utf8_lossy(&bytes[..e.valid_up_to()]).into_owned()
+            }
+            Err(_) => bytes.iter().map(|b| char::from(*b)).collect(),
+        }
+    };
+    Some(
+        text.chars()
+            .filter(|c| !c.is_control() || *c == '\n' || *c == '\t')
+            .collect(),
+    )
+}
+
+fn decode_utf16(bytes: &[u8], from_bytes: fn([u8; 2]) -> u16) -> String {
+    let units: Vec<u16> = bytes
+        .chunks_exact(2)
+        .map(|pair| from_bytes([pair[0], pair[1]]))
+        .collect();
+    String::from_utf16_lossy(&units)
+}
+
+/// Returns `true` if more than a tenth of `bytes` are control characters that don't appear in
+/// text.
+fn is_mostly_control(bytes: &[u8]) -> bool {
+    let control = bytes
+        .iter()
+        .filter(|b| b.is_ascii_control() && !b"\t\n\r\x0c\x1b".contains(b))
+        .count();
+    control * 10 > bytes.len()
+}
+
+#[cfg(feature = "syntax_highlight")]
+mod highlight {
+    use std::path::Path;
+
+    use lazy_static::lazy_static;
+    use syntect::easy::HighlightLines;
+    use syntect::highlighting::ThemeSet;
+    use syntect::parsing::{SyntaxReference, SyntaxSet};
+    use syntect::util::{as_24_bit_terminal_escaped, LinesWithEndings};
+
+    /// Lines longer than this are left plain, along with the rest of the file, as highlighting
+    /// them (as in minified code) is slow.
+    const MAX_HIGHLIGHT_LINE_LENGTH: usize = 4096;
+
+    lazy_static! {
+        static ref SYNTAX_SET: SyntaxSet = SyntaxSet::load_defaults_newlines();
+        pub static ref THEME_SET: ThemeSet = ThemeSet::load_defaults();
+    }
+
+    /// Highlights `text`, the beginning of the file at `path`, with ANSI escape sequences and
+    /// the colors of the theme named `theme`. Returns it as it is if the syntax or the theme
+    /// isn't known.
+    pub fn highlight(path: &Path, text: &str, theme: &str) -> String {
+        let (Some(syntax), Some(theme)) = (find_syntax(path, text), THEME_SET.themes.get(theme))
+        else {
+            return text.to_string();
+        };
+        let mut highlighter = HighlightLines::new(syntax, theme);
+        let mut output = String::with_capacity(text.len() * 2);
+        let mut lines = LinesWithEndings::from(text);
+        for line in lines.by_ref() {
+            if line.len() > MAX_HIGHLIGHT_LINE_LENGTH {
+                output.push_str("\x1b[0m");
+                output.push_str(line);
+                break;
+            }
+            match highlighter.highlight_line(line, &SYNTAX_SET) {
+                Ok(ranges) => output.push_str(&as_24_bit_terminal_escaped(&ranges, false)),
+                Err(_) => return text.to_string(),
+            }
+        }
+        output.push_str("\x1b[0m");
+        output.extend(lines);
+        output
+    }
+
+    /// Finds the syntax of a file by its name (as in `Makefile`), its extension, or its first
+    /// line (a shebang, or a mode line).
+    fn find_syntax(path: &Path, text: &str) -> Option<&'static SyntaxReference> {
+        let by_name = path
+            .file_name()
+            .and_then(|name| name.to_str())
+            .and_then(|name| SYNTAX_SET.find_syntax_by_extension(name));
+        let by_extension = || {
+            path.extension()
+                .and_then(|ext| ext.to_str())
+                .and_then(|ext| SYNTAX_SET.find_syntax_by_extension(ext))
+        };
+        let by_first_line = || {
+            text.lines()
+                .next()
+                .and_then(|line| SYNTAX_SET.find_syntax_by_first_line(line))
+        };
+        by_name
+            .or_else(by_extension)
+            .or_else(by_first_line)
+            .filter(|syntax| syntax.name != "Plain Text")
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_decode_text() {
+        assert_eq!(decode_text(b"a\tb\r\nc", false).as_deref(), Some("a\tb\nc"));
+        assert_eq!(
+            decode_text(b"\xef\xbb\xbfcaf\xc3\xa9", false).as_deref(),
+            Some("café")
+        );
+        assert_eq!(
+            decode_text(b"\xff\xfea\x00b\x00", false).as_deref(),
+            Some("ab")
+        );
+        assert_eq!(decode_text(b"\xfe\xff\x00a", false).as_deref(), Some("a"));
+        // Latin-1
+        assert_eq!(decode_text(b"caf\xe9", false).as_deref(), Some("café"));
+        // a character cut off by the end of the preview
+        assert_eq!(decode_text(b"caf\xc3", true).as_deref(), Some("caf"));
+
+        assert_eq!(decode_text(b"\x7fELF\x02\x01\x01\x00", false), None);
+        assert_eq!(decode_text(b"\x01\x02\x03abcdef", false), None);
+    }
+
+    #[cfg(feature = "syntax_highlight")]
+    #[test]
+    fn test_syntax_themes() {
+        assert!(check_syntax_theme("base16-ocean.dark").is_ok());
+        assert!(check_syntax_theme("InspiredGitHub").is_ok());
+        assert!(check_syntax_theme("base16-ocean").is_err());
+
+        let path = Path::new("main.rs");
+        let dark = highlight::highlight(path, "fn main() {}\n", "base16-ocean.dark");
+        let light = highlight::highlight(path, "fn main() {}\n", "InspiredGitHub");
+        assert_ne!(dark, light);
+    }
+}
@@ -1,7 +1,8 @@
+use std::collections::{HashMap, HashSet};
 use std::io;
 use std::path;
+use std::time;
 
-use notify;
 use ratatui::layout::Rect;
 use ratatui::layout::{Constraint, Direction, Layout};
 use ratatui::termion::event::{Event, Key, MouseButton, MouseEvent};
@@ -11,18 +12,19 @@ use uuid::Uuid;
 use crate::commands::tab_ops;
 use crate::commands::{cursor_move, parent_cursor_move, reload};
 use crate::error::AppResult;
-use crate::fs::JoshutoDirList;
-use crate::preview::preview_dir::PreviewDirState;
+use crate::fs::{JoshutoDirEntry, JoshutoDirList, JoshutoMetadata};
+use crate::preview::preview_dir::{DirLoadUpdate, PreviewDirState};
 use crate::preview::preview_file::PreviewFileState;
 use crate::traits::app_execute::AppExecute;
 use crate::types::command::Command;
-use crate::types::event::AppEvent;
 use crate::types::event::PreviewData;
+use crate::types::event::{AppEvent, FilesystemChanges};
 use crate::types::io::IoTaskProgressMessage;
 use crate::types::io::IoTaskStat;
 use crate::types::keybind::CommandKeybind;
 use crate::types::keybind::KeyMapping;
 use crate::types::keymap::AppKeyMapping;
+use crate::types::option::sort::SortMethod;
 use crate::types::state::AppState;
 use crate::ui;
 use crate::ui::views::TuiCommandMenu;
@@ -77,8 +79,25 @@ pub fn process_noninteractive(event: AppEvent, app_state: &mut AppState) {
         AppEvent::IoTaskStart(stats) => process_io_task_start(app_state, stats),
         AppEvent::IoTaskProgress(res) => process_io_task_progress(app_state, res),
         AppEvent::IoTaskResult(res) => process_finished_io_task(app_state, res),
-        AppEvent::PreviewDir { id, path, res } => process_dir_preview(app_state, id, path, *res),
+        AppEvent::PreviewDir {
+            id,
+            load_id,
+            path,
+            update,
+        } => process_dir_preview(app_state, id, load_id, path, update),
         AppEvent::PreviewFile { path, res } => process_file_preview(app_state, path, res),
+        AppEvent::GitStatus { dir, res } => app_state.state.git_state_mut().update(dir, *res),
+        AppEvent::CustomColumn { name, values } => process_custom_column(app_state, name, values),
+        AppEvent::CumulativeSizes(sizes) => process_cumulative_sizes(app_state, sizes),
+        AppEvent::Mimetypes(results) => process_mimetypes(app_state, results),
+        AppEvent::CumulativeSizeDone(msg) => {
+            app_state
+                .state
+                .sort_cumulative_sizes(&app_state.config.display_options);
+            app_state.state.message_queue_mut().push_info(msg);
+        }
+        AppEvent::FindResults { id, entries } => process_find_results(app_state, id, entries),
+        AppEvent::FindDone { id } => process_find_done(app_state, id),
         AppEvent::Signal(signal::SIGWINCH) => {}
         AppEvent::Filesystem(e) => process_filesystem_event(e, app_state),
         AppEvent::ChildProcessComplete(child_id) => {
@@ -88,7 +107,285 @@ pub fn process_noninteractive(event: AppEvent, app_state: &mut AppState) {
     }
 }
 
-fn process_filesystem_event(_event: notify::Event, app_state: &mut AppState) {
+/// Records cumulative sizes computed in the background and shows them on the entries in every
+/// listing they're in. The listings are sorted once all sizes requested together are known.
+fn process_cumulative_sizes(
+    app_state: &mut AppState,
+    sizes: Vec<(path::PathBuf, time::SystemTime, u64)>,
+) {
+    app_state.state.cumulative_size_state_mut().update(&sizes);
+    let parents: HashSet<path::PathBuf> = sizes
+        .iter()
+        .filter_map(|(path, _, _)| path.parent().map(|p| p.to_path_buf()))
+        .collect();
+    let sizes: HashMap<path::PathBuf, u64> = sizes
+        .into_iter()
+        .map(|(path, _, size)| (path, size))
+        .collect();
+    for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+        for parent in parents.iter() {
+            let Some(list) = tab.history_mut().get_mut(parent) else {
+                continue;
+            };
+            for entry in list.iter_mut() {
+                if let Some(size) = sizes.get(entry.file_path()) {
+                    entry.metadata.update_cumulative_size(*size);
+                }
+            }
+        }
+    }
+}
+
+/// Records custom column values computed in the background and shows them on the entries in
+/// every listing they're in, re-sorting each listing sorted by the column once.
+fn process_custom_column(
+    app_state: &mut AppState,
+    name: String,
+    values: Vec<(path::PathBuf, time::SystemTime, String)>,
+) {
+    app_state
+        .state
+        .custom_column_state_mut()
+        .update(&name, &values);
+    let parents: HashSet<path::PathBuf> = values
+        .iter()
+        .filter_map(|(path, _, _)| path.parent().map(|p| p.to_path_buf()))
+        .collect();
+    let values: HashMap<path::PathBuf, String> = values
+        .into_iter()
+        .map(|(path, _, value)| (path, value))
+        .collect();
+
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    let set_value = |entry: &mut JoshutoDirEntry, value: &String| {
+        entry
+            .metadata
+            .custom_columns
+            .insert(name.clone(), value.clone());
+    };
+    for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+        let sort_options = tab.option_ref().sort_options_ref().clone();
+        // lists are only sorted again if sorted by the column
+        let sorted_by = matches!(
+            sort_options.sort_methods.list.front(),
+            Some(SortMethod::Custom(sort_name)) if *sort_name == name
+        );
+        let update = |list: &mut JoshutoDirList| {
+            if sorted_by {
+                list.update_entries(
+                    &values,
+                    set_value,
+                    &sort_options,
+                    &ui_state,
+                    display_options,
+                );
+            } else {
+                for entry in list.iter_mut() {
+                    if let Some(value) = values.get(entry.file_path()) {
+                        set_value(entry, value);
+                    }
+                }
+            }
+        };
+        for parent in parents.iter() {
+            if let Some(list) = tab.history_mut().get_mut(parent) {
+                update(list);
+            }
+        }
+        if let Some(list) = tab.virtual_list.as_mut() {
+            update(list);
+        }
+    }
+}
+
+/// Records MIME types detected in the background and shows them on the entries in every
+/// listing they're in, re-sorting each listing once.
+fn process_mimetypes(
+    app_state: &mut AppState,
+    results: Vec<(path::PathBuf, time::SystemTime, Option<String>)>,
+) {
+    app_state.state.mimetype_state.update(&results);
+    let parents: HashSet<path::PathBuf> = results
+        .iter()
+        .filter_map(|(path, _, _)| path.parent().map(|p| p.to_path_buf()))
+        .collect();
+    let mimetypes: HashMap<path::PathBuf, Option<String>> = results
+        .into_iter()
+        .map(|(path, _, mimetype)| (path, mimetype))
+        .collect();
+
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    let set_mimetype = |entry: &mut JoshutoDirEntry, mimetype: &Option<String>| {
+        entry.metadata.mimetype = mimetype.clone()
+    };
+    for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+        let sort_options = tab.option_ref().sort_options_ref().clone();
+        for parent in parents.iter() {
+            if let Some(list) = tab.history_mut().get_mut(parent) {
+                list.update_entries(
+                    &mimetypes,
+                    set_mimetype,
+                    &sort_options,
+                    &ui_state,
+                    display_options,
+                );
+            }
+        }
+        if let Some(list) = tab.virtual_list.as_mut() {
+            list.update_entries(
+                &mimetypes,
+                set_mimetype,
+                &sort_options,
+                &ui_state,
+                display_options,
+            );
+        }
+    }
+}
+
+/// Adds a batch of `find` results to the virtual listing they stream into. The search is
+/// cancelled if that listing is gone, e.g. after leaving it, while batches of searches already
+/// cancelled are dropped.
+fn process_find_results(app_state: &mut AppState, id: Uuid, entries: Vec<JoshutoDirEntry>) {
+    if !app_state.state.find_state_mut().is_running(id) {
+        return;
+    }
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    // only the tab the search was started in streams its results
+    let tab = app_state
+        .state
+        .tab_state_mut()
+        .iter_mut()
+        .map(|(_, tab)| tab)
+        .find(|tab| {
+            tab.virtual_list
+                .as_ref()
+                .is_some_and(|l| l.load_id == Some(id))
+        });
+    let Some(tab) = tab else {
+        app_state.state.find_state_mut().cancel();
+        return;
+    };
+    let sort_options = tab.option_ref().sort_options_ref().clone();
+    if let Some(list) = tab.virtual_list.as_mut() {
+        list.virtual_paths
+            .get_or_insert_with(Vec::new)
+            .extend(entries.iter().map(|e| e.file_path_buf()));
+        list.merge_sorted(entries, &sort_options, &ui_state, display_options);
+    }
+}
+
+/// Marks the virtual listing filled by the `find` `id` as complete and reports the number of
+/// results.
+fn process_find_done(app_state: &mut AppState, id: Uuid) {
+    if !app_state.state.find_state_mut().finish(id) {
+        return;
+    }
+    let mut found = None;
+    for (_, tab) in app_state.state.tab_state_mut().iter_mut() {
+        if let Some(list) = tab.virtual_list.as_mut().filter(|l| l.load_id == Some(id)) {
+            list.load_id = None;
+            found = Some(list.len());
+        }
+    }
+    if let Some(found) = found {
+        app_state
+            .state
+            .message_queue_mut()
+            .push_info(format!("find done, {} found", found));
+    }
+}
+
+/// Applies a burst of filesystem changes: entries of the directories shown in the current tab
+/// are inserted, updated or removed in place, while other cached listings are only marked
+/// stale.
+fn process_filesystem_event(changes: FilesystemChanges, app_state: &mut AppState) {
+    let git_state = app_state.state.git_state_mut();
+    for path in changes.entries.iter().chain(changes.rescan.iter()) {
+        git_state.invalidate(path);
+    }
+
+    let mut changes_by_dir: HashMap<path::PathBuf, Vec<path::PathBuf>> = HashMap::new();
+    for path in changes.entries {
+        if let Some(parent) = path.parent() {
+            changes_by_dir
+                .entry(parent.to_path_buf())
+                .or_default()
+                .push(path);
+        }
+    }
+
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    let curr_tab_id = app_state.state.tab_state_ref().curr_tab_id();
+    let displayed: Vec<path::PathBuf> = {
+        let curr_tab = app_state.state.tab_state_ref().curr_tab_ref();
+        [
+            curr_tab.parent_list_ref(),
+            curr_tab.curr_list_ref(),
+            curr_tab.child_list_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|list| list.file_path().to_path_buf())
+        .collect()
+    };
+    // in the tree mode, the current listing shows the contents of its expanded directories
+    let (curr_path, expanded): (Option<path::PathBuf>, HashSet<path::PathBuf>) = {
+        let curr_tab = app_state.state.tab_state_ref().curr_tab_ref();
+        match curr_tab.curr_list_ref() {
+            Some(list) => (
+                Some(list.file_path().to_path_buf()),
+                curr_tab
+                    .option_ref()
+                    .expanded_below(list.file_path())
+                    .map(|dir| dir.to_path_buf())
+                    .collect(),
+            ),
+            None => (None, HashSet::new()),
+        }
+    };
+
+    for (tab_id, tab) in app_state.state.tab_state_mut().iter_mut() {
+        let tab_options = tab.option_ref().clone();
+        let history = tab.history_mut();
+        for dir in changes.rescan.iter() {
+            for dir in [Some(dir.as_path()), dir.parent()].into_iter().flatten() {
+                if let Some(dirlist) = history.get_mut(dir) {
+                    dirlist.depreciate();
+                }
+            }
+        }
+        for (dir, paths) in changes_by_dir.iter() {
+            if *tab_id == curr_tab_id && expanded.contains(dir) {
+                let tree_list = curr_path.as_ref().and_then(|p| history.get_mut(p));
+                if let Some(tree_list) = tree_list.filter(|list| list.load_id.is_none()) {
+                    tree_list.refresh_entries(paths, display_options, &tab_options, &ui_state);
+                }
+            }
+            let Some(dirlist) = history.get_mut(dir) else {
+                continue;
+            };
+            // listings still being loaded pick up changes as they are read
+            if dirlist.load_id.is_some() {
+                continue;
+            }
+            if *tab_id != curr_tab_id || !displayed.contains(dir) {
+                dirlist.depreciate();
+                continue;
+            }
+            dirlist.refresh_entries(paths, display_options, &tab_options, &ui_state);
+            // the directory's own mtime changed too; don't let that trigger a full re-read
+            if let Ok(metadata) = JoshutoMetadata::from(dir) {
+                dirlist.metadata = metadata;
+            }
+        }
+    }
+
+    // re-read whatever had to be rescanned
     let _ = reload::soft_reload_curr_tab(app_state);
 }
 
@@ -155,38 +452,88 @@ pub fn process_finished_io_task(app_state: &mut AppState, res: AppResult) {
         }
     }
     app_state.state.worker_state_mut().progress = None;
+    app_state.state.fs_info_state_mut().invalidate();
     process_new_io_task(app_state);
 }
 
-/// Applies a completed background directory-preview read to the tab that requested it.
+/// Applies a piece of a streamed background directory load to the tab that requested it.
+/// Updates from a load that has since been superseded are dropped.
 pub fn process_dir_preview(
     app_state: &mut AppState,
     id: Uuid,
+    load_id: Uuid,
     path: path::PathBuf,
-    res: io::Result<JoshutoDirList>,
+    update: DirLoadUpdate,
 ) {
-    for (tab_id, tab) in app_state.state.tab_state_mut().iter_mut() {
-        if *tab_id == id {
-            match res {
-                Ok(dirlist) => {
-                    // remove from loading state
-                    tab.history_metadata_mut().remove(dirlist.file_path());
-
-                    let history = tab.history_mut();
-                    let dir_path = dirlist.file_path().to_path_buf();
-                    history.insert(dir_path, dirlist);
+    let ui_state = app_state.state.ui_state_ref().clone();
+    let display_options = &app_state.config.display_options;
+    let Some(tab) = app_state.state.tab_state_mut().tab_mut(&id) else {
+        return;
+    };
+    let count = match tab.history_metadata_ref().get(path.as_path()) {
+        Some(PreviewDirState::Loading { id, count }) if *id == load_id => *count,
+        _ => return,
+    };
+    let sort_options = tab.option_ref().sort_options_ref().clone();
+    let is_loading_list = tab
+        .history_ref()
+        .get(path.as_path())
+        .is_some_and(|list| list.load_id == Some(load_id));
+
+    match update {
+        DirLoadUpdate::Entries(entries) => {
+            if !is_loading_list {
+                // first batch: merge into any existing listing, to keep its cursor and selection
+                let metadata = match JoshutoMetadata::from(&path) {
+                    Ok(metadata) => metadata,
+                    Err(e) => {
+                        let message = e.to_string();
+                        tab.history_metadata_mut()
+                            .insert(path, PreviewDirState::Error { message });
+                        return;
+                    }
+                };
+                match tab.history_mut().get_mut(path.as_path()) {
+                    Some(dirlist) => dirlist.begin_reload(load_id, metadata),
+                    None => {
+                        let mut dirlist =
+                            JoshutoDirList::new(path.clone(), Vec::new(), None, 0, None, metadata);
+                        dirlist.load_id = Some(load_id);
+                        tab.history_mut().insert(path.clone(), dirlist);
+                    }
+                }
+            }
+            let count = count + entries.len();
+            if let Some(dirlist) = tab.history_mut().get_mut(path.as_path()) {
+                dirlist.merge_sorted(entries, &sort_options, &ui_state, display_options);
+            }
+            tab.history_metadata_mut()
+                .insert(path, PreviewDirState::Loading { id: load_id, count });
+        }
+        DirLoadUpdate::Metadata(metadata) => {
+            if let Some(dirlist) = tab.history_mut().get_mut(path.as_path()) {
+                if is_loading_list {
+                    dirlist.update_metadata(metadata, &sort_options, &ui_state, display_options);
                 }
-                Err(e) => {
-                    // set to false so we don't load again
-                    tab.history_metadata_mut().insert(
-                        path,
-                        PreviewDirState::Error {
-                            message: e.to_string(),
-                        },
-                    );
+            }
+        }
+        DirLoadUpdate::Done { hidden_count } => {
+            // remove from loading state
+            tab.history_metadata_mut().remove(path.as_path());
+            if let Some(dirlist) = tab.history_mut().get_mut(path.as_path()) {
+                if is_loading_list {
+                    dirlist.finish_load(hidden_count, &sort_options, &ui_state, display_options);
                 }
             }
-            break;
+        }
+        DirLoadUpdate::Error(e) => {
+            // set to error so we don't load again
+            tab.history_metadata_mut().insert(
+                path,
+                PreviewDirState::Error {
+                    message: e.to_string(),
+                },
+            );
         }
     }
 }
@@ -1,20 +1,26 @@
 use std::fs;
 use std::io;
+#[cfg(unix)]
+use std::os::unix::fs::{MetadataExt, PermissionsExt};
 use std::path;
 use std::process::{Command, Stdio};
 use std::sync::mpsc;
 
+use uuid::Uuid;
+
 #[cfg(unix)]
 use std::os::unix;
 
 use crate::error::AppError;
 use crate::error::AppErrorKind;
 use crate::error::AppResult;
+use crate::fs::{compress, extract, ArchiveKind, ArchiveProgress, VfsPath};
 use crate::types::event::AppEvent;
 use crate::types::io::FileOperationProgress;
 use crate::types::io::IoTaskStat;
 use crate::types::io::{FileOperation, FileOperationOptions, IoTask, IoTaskProgressMessage};
-use crate::utils::fs::query_number_of_items;
+use crate::types::io::{ModeChangeOptions, TimeChangeOptions};
+use crate::utils::fs::{query_archive_items, query_number_of_items, rename_tags};
 use crate::utils::name_resolution::rename_filename_conflict;
 
 /// Runs on the dedicated IO worker thread: processes queued [`IoTask`]s one at a time,
@@ -32,11 +38,16 @@ pub fn process_io_tasks(
 }
 
 /// Runs a single [`IoTask`] to completion, reporting start progress before dispatching to the
-/// operation-specific implementation (cut/copy/delete/symlink).
+/// operation-specific implementation (cut/copy/delete/symlink/set mode/set time/extract/
+/// compress).
 pub fn process_io_task(io_task: &IoTask, event_tx: &mpsc::Sender<AppEvent>) -> AppResult {
-    let (total_files, total_bytes) = query_number_of_items(io_task.paths.as_slice())?;
+    check_writable(io_task)?;
+    let (total_files, total_bytes) = match io_task.operation {
+        FileOperation::Extract => query_number_of_archive_items(io_task.paths.as_slice())?,
+        _ => query_number_of_items(io_task.paths.as_slice())?,
+    };
     let src = io_task.paths[0].parent().unwrap().to_path_buf();
-    let dest = io_task.dest.clone();
+    let dest = dest_dir(io_task).to_path_buf();
 
     let operation_progress = FileOperationProgress {
         kind: io_task.operation,
@@ -56,15 +67,230 @@ pub fn process_io_task(io_task: &IoTask, event_tx: &mpsc::Sender<AppEvent>) -> A
         FileOperation::Copy => paste_copy(io_task, event_tx),
         FileOperation::Delete => delete(io_task, event_tx),
         FileOperation::Symlink => paste_symlink(io_task, event_tx),
+        FileOperation::SetMode => set_mode(io_task, event_tx),
+        FileOperation::SetTime => set_time(io_task, event_tx),
+        FileOperation::Extract => extract_archives(io_task, event_tx),
+        FileOperation::Compress => compress_files(io_task, event_tx),
     };
     res?;
     Ok(())
 }
 
+/// Fails if `task` would write inside an archive: archives are browsed read-only, and their
+/// entries can only be copied out.
+fn check_writable(task: &IoTask) -> io::Result<()> {
+    let writes_dest = matches!(
+        task.operation,
+        FileOperation::Cut
+            | FileOperation::Copy
+            | FileOperation::Symlink
+            | FileOperation::Extract
+            | FileOperation::Compress
+    );
+    let writes_paths = !matches!(
+        task.operation,
+        FileOperation::Copy | FileOperation::Extract | FileOperation::Compress
+    );
+    if (writes_dest && VfsPath::resolve(dest_dir(task)).is_archive())
+        || (writes_paths
+            && task
+                .paths
+                .iter()
+                .any(|p| VfsPath::resolve(p).is_read_only()))
+    {
+        return Err(io::Error::new(
+            io::ErrorKind::ReadOnlyFilesystem,
+            "archives are read-only",
+        ));
+    }
+    Ok(())
+}
+
+/// Returns the directory `task` writes into: its `dest`, or the directory of the archive to
+/// create.
+fn dest_dir(task: &IoTask) -> &path::Path {
+    match task.operation {
+        FileOperation::Compress => task.dest.parent().unwrap_or(&task.dest),
+        _ => &task.dest,
+    }
+}
+
 fn paste_copy(task: &IoTask, tx: &mpsc::Sender<AppEvent>) -> AppResult {
     for path in task.paths.iter() {
-        recursive_copy(tx, path.as_path(), task.dest.as_path(), task.options)?;
+        match VfsPath::resolve(path) {
+            VfsPath::Archive {
+                archive,
+                kind,
+                inner,
+            } if !inner.as_os_str().is_empty() => {
+                copy_from_archive(tx, archive, kind, inner, task.dest.as_path(), task.options)?
+            }
+            _ => recursive_copy(tx, path.as_path(), task.dest.as_path(), task.options)?,
+        }
+    }
+    Ok(())
+}
+
+/// Copies `inner`, an entry of the `kind` archive at `archive`, with everything below it into
+/// `dest`, renaming on conflict unless `options.overwrite`, reporting per-file progress over
+/// `tx`.
+fn copy_from_archive(
+    tx: &mpsc::Sender<AppEvent>,
+    archive: &path::Path,
+    kind: ArchiveKind,
+    inner: &path::Path,
+    dest: &path::Path,
+    options: FileOperationOptions,
+) -> io::Result<()> {
+    let mut dest_buf = dest.to_path_buf();
+    if let Some(s) = inner.file_name() {
+        dest_buf.push(s);
+    }
+    if !options.overwrite {
+        rename_filename_conflict(&mut dest_buf);
+    }
+    extract(archive, kind, inner, &dest_buf, |progress| {
+        send_archive_progress(tx, progress)
+    })
+}
+
+/// Reports the progress of an archive being extracted or written.
+fn send_archive_progress(tx: &mpsc::Sender<AppEvent>, progress: ArchiveProgress) {
+    let event = match progress {
+        ArchiveProgress::Entry(path) => IoTaskProgressMessage::FileStart {
+            file_path: path.to_path_buf(),
+        },
+        ArchiveProgress::Bytes(bytes) => IoTaskProgressMessage::BytesProcessed { bytes },
+        // the entry's bytes were already counted
+        ArchiveProgress::Done => IoTaskProgressMessage::FileComplete { file_size: 0 },
+    };
+    let _ = tx.send(AppEvent::IoTaskProgress(event));
+}
+
+/// Returns the archive file at `path` on the local filesystem, extracting it first if it is
+/// inside another archive, along with its format.
+fn local_archive(path: &path::Path) -> io::Result<(path::PathBuf, ArchiveKind)> {
+    let kind = ArchiveKind::from_path(path).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}: not a supported archive", path.display()),
+        )
+    })?;
+    Ok((VfsPath::resolve(path).local_path()?, kind))
+}
+
+/// Get total bytes and number of files inside a list of archives
+fn query_number_of_archive_items(paths: &[path::PathBuf]) -> io::Result<(usize, u64)> {
+    let mut total_bytes = 0;
+    let mut total_files = 0;
+    for path in paths {
+        let (archive, kind) = local_archive(path)?;
+        let (files, bytes) = query_archive_items(&archive, kind, path::Path::new(""))?;
+        total_files += files;
+        total_bytes += bytes;
+    }
+    Ok((total_files, total_bytes))
+}
+
+fn extract_archives(task: &IoTask, tx: &mpsc::Sender<AppEvent>) -> AppResult {
+    for path in task.paths.iter() {
+        extract_archive(tx, path.as_path(), task.dest.as_path(), task.options)?;
+    }
+    Ok(())
+}
+
+/// Extracts the archive at `path` into `dest`, or into a new directory there named after the
+/// archive if `options.subdir`, reporting per-file progress over `tx`. Entries that would end up
+/// outside of it, through `..` or symlinks, are skipped, and absolute paths are made relative.
+///
+/// The archive is extracted into a temporary directory first, so that conflicts with existing
+/// files can be handled like when pasting: by renaming the extracted files, or overwriting the
+/// existing ones if `options.overwrite`.
+fn extract_archive(
+    tx: &mpsc::Sender<AppEvent>,
+    path: &path::Path,
+    dest: &path::Path,
+    options: FileOperationOptions,
+) -> io::Result<()> {
+    let (archive, kind) = local_archive(path)?;
+    let temp_dir = dest.join(format!(".{}.part", Uuid::new_v4()));
+    fs::create_dir(&temp_dir)?;
+    let res = extract(&archive, kind, path::Path::new(""), &temp_dir, |progress| {
+        send_archive_progress(tx, progress)
+    })
+    .and_then(|_| {
+        if options.subdir {
+            let name = ArchiveKind::file_stem(path).unwrap_or_default();
+            move_extracted(&temp_dir, &dest.join(name), options)
+        } else {
+            for entry in fs::read_dir(&temp_dir)? {
+                let entry = entry?;
+                move_extracted(&entry.path(), &dest.join(entry.file_name()), options)?;
+            }
+            Ok(())
+        }
+    });
+    let _ = fs::remove_dir_all(&temp_dir);
+    res
+}
+
+/// Moves the extracted `src` to `dest`, renaming on conflict unless `options.overwrite`, in
+/// which case existing files are replaced and directories merged. As when pasting, a directory
+/// is never replaced with a file or the other way around: that fails instead.
+fn move_extracted(
+    src: &path::Path,
+    dest: &path::Path,
+    options: FileOperationOptions,
+) -> io::Result<()> {
+    if !options.overwrite {
+        let mut dest_buf = dest.to_path_buf();
+        rename_filename_conflict(&mut dest_buf);
+        return fs::rename(src, dest_buf);
+    }
+
+    let src_is_dir = fs::symlink_metadata(src)?.is_dir();
+    match fs::symlink_metadata(dest) {
+        Ok(metadata) if metadata.is_dir() != src_is_dir => Err(io::Error::new(
+            io::ErrorKind::AlreadyExists,
+            format!(
+                "{}: exists and is {}a directory",
+                dest.display(),
+                if src_is_dir { "not " } else { "" }
+            ),
+        )),
+        Ok(metadata) if metadata.is_dir() => {
+            for entry in fs::read_dir(src)? {
+                let entry = entry?;
+                move_extracted(&entry.path(), &dest.join(entry.file_name()), options)?;
+            }
+            fs::remove_dir(src)
+        }
+        _ => fs::rename(src, dest),
+    }
+}
+
+/// Creates the archive `task.dest` of `task.paths`, renaming it on conflict unless
+/// `options.overwrite`, reporting per-file progress over `tx`. Entries of other archives are
+/// added from copies extracted from them.
+fn compress_files(task: &IoTask, tx: &mpsc::Sender<AppEvent>) -> AppResult {
+    let kind = ArchiveKind::from_path(&task.dest).ok_or_else(|| {
+        io::Error::new(
+            io::ErrorKind::InvalidInput,
+            format!("{}: not a supported archive", task.dest.display()),
+        )
+    })?;
+    let sources = task
+        .paths
+        .iter()
+        .map(|path| VfsPath::resolve(path).local_path())
+        .collect::<io::Result<Vec<_>>>()?;
+    let mut dest_buf = task.dest.clone();
+    if !task.options.overwrite {
+        rename_filename_conflict(&mut dest_buf);
     }
+    compress(&sources, kind, &dest_buf, |progress| {
+        send_archive_progress(tx, progress)
+    })?;
     Ok(())
 }
 
@@ -154,6 +380,88 @@ fn delete(task: &IoTask, tx: &mpsc::Sender<AppEvent>) -> AppResult {
     Ok(())
 }
 
+fn set_mode(task: &IoTask, tx: &mpsc::Sender<AppEvent>) -> AppResult {
+    for path in task.paths.iter() {
+        recursive_set_mode(tx, path.as_path(), task.options.mode_change)?;
+    }
+    Ok(())
+}
+
+/// Applies the file or directory change in `options` to `path`, descending into directories if
+/// `options.recursive`. Symlinks are skipped, as their permissions are not used.
+pub fn recursive_set_mode(
+    tx: &mpsc::Sender<AppEvent>,
+    path: &path::Path,
+    options: ModeChangeOptions,
+) -> io::Result<()> {
+    let event = IoTaskProgressMessage::FileStart {
+        file_path: path.to_path_buf(),
+    };
+    let _ = tx.send(AppEvent::IoTaskProgress(event));
+
+    let metadata = fs::symlink_metadata(path)?;
+    let file_type = metadata.file_type();
+    if !file_type.is_symlink() {
+        if let Some(change) = options.change_for(file_type.is_dir()) {
+            let mode = nix::sys::stat::Mode::from_bits_truncate(metadata.mode() as _);
+            let new_mode = change.apply(mode, file_type.is_dir());
+            if new_mode != mode {
+                fs::set_permissions(path, fs::Permissions::from_mode(new_mode.bits() as _))?;
+            }
+        }
+    }
+
+    if file_type.is_dir() && options.recursive {
+        for entry in fs::read_dir(path)? {
+            let entry_path = entry?.path();
+            recursive_set_mode(tx, entry_path.as_path(), options)?;
+        }
+    }
+    let file_size = if file_type.is_dir() {
+        1
+    } else {
+        metadata.len()
+    };
+    let event = IoTaskProgressMessage::FileComplete { file_size };
+    let _ = tx.send(AppEvent::IoTaskProgress(event));
+    Ok(())
+}
+
+fn set_time(task: &IoTask, tx: &mpsc::Sender<AppEvent>) -> AppResult {
+    for path in task.paths.iter() {
+        recursive_set_time(tx, path.as_path(), task.options.time_change)?;
+    }
+    Ok(())
+}
+
+/// Applies the access/modification time changes in `options` to `path`, descending into
+/// directories if `options.recursive`.
+pub fn recursive_set_time(
+    tx: &mpsc::Sender<AppEvent>,
+    path: &path::Path,
+    options: TimeChangeOptions,
+) -> io::Result<()> {
+    let event = IoTaskProgressMessage::FileStart {
+        file_path: path.to_path_buf(),
+    };
+    let _ = tx.send(AppEvent::IoTaskProgress(event));
+
+    let metadata = fs::symlink_metadata(path)?;
+    if metadata.is_dir() && options.recursive {
+        for entry in fs::read_dir(path)? {
+            let entry_path = entry?.path();
+            recursive_set_time(tx, entry_path.as_path(), options)?;
+        }
+    }
+    // set the directory's own times last, as changing its contents can update them
+    options.apply_to(path)?;
+
+    let file_size = if metadata.is_dir() { 1 } else { metadata.len() };
+    let event = IoTaskProgressMessage::FileComplete { file_size };
+    let _ = tx.send(AppEvent::IoTaskProgress(event));
+    Ok(())
+}
+
 /// Recursively copies `src` into `dest`, renaming on conflict unless `options.overwrite`,
 /// reporting per-file progress over `tx`.
 pub fn recursive_copy(
@@ -258,7 +566,7 @@ pub fn recursive_cut(
                 let _ = tx.send(AppEvent::IoTaskProgress(event));
             } else if file_type.is_symlink() {
                 let link_path = fs::read_link(src)?;
-                std::os::unix::fs::symlink(link_path, dest_buf)?;
+                std::os::unix::fs::symlink(link_path, &dest_buf)?;
                 fs::remove_file(src)?;
 
                 let bytes_processed = metadata.len();
@@ -277,6 +585,7 @@ pub fn recursive_cut(
             }
         }
     }
+    rename_tags(src, &dest_buf);
     Ok(())
 }
 
@@ -357,3 +666,37 @@ where
         "Failed to trash file".to_string(),
     ))
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_move_extracted_keeps_file_types() {
+        let dir = std::env::temp_dir().join(format!("joshuto-extract-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        for sub in ["new/a", "new/b", "old/a", "old/c"] {
+            fs::create_dir_all(dir.join(sub)).unwrap();
+        }
+        fs::write(dir.join("new/a/x"), "new").unwrap();
+        fs::write(dir.join("old/a/x"), "old").unwrap();
+        fs::write(dir.join("old/b"), "file").unwrap();
+        fs::write(dir.join("new/c"), "file").unwrap();
+        let options = FileOperationOptions {
+            overwrite: true,
+            ..Default::default()
+        };
+
+        // files are replaced and directories merged
+        move_extracted(&dir.join("new/a"), &dir.join("old/a"), options).unwrap();
+        assert_eq!(fs::read_to_string(dir.join("old/a/x")).unwrap(), "new");
+
+        // but a file never replaces a directory, nor a directory a file
+        assert!(move_extracted(&dir.join("new/b"), &dir.join("old/b"), options).is_err());
+        assert!(dir.join("old/b").is_file());
+        assert!(move_extracted(&dir.join("new/c"), &dir.join("old/c"), options).is_err());
+        assert!(dir.join("old/c").is_dir());
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}
@@ -1,6 +1,9 @@
+use std::path::PathBuf;
+
 use super::process_event;
 
 use crate::commands::quit::QuitAction;
+use crate::commands::{dir_settings, reload, virtual_list};
 use crate::history::generate_entries_to_root;
 use crate::history::DirectoryHistory;
 use crate::history::JoshutoHistory;
@@ -23,12 +26,14 @@ use uuid::Uuid;
 use ratatui::layout::Rect;
 use ratatui::termion::event::Event;
 
-/// joshuto's main loop: opens the initial tab, then repeatedly renders the UI, waits for the
-/// next event, and dispatches it, until a quit command sets `app_state.quit`.
+/// joshuto's main loop: opens the initial tab (with a virtual listing of `virtual_paths`, if
+/// given), then repeatedly renders the UI, waits for the next event, and dispatches it, until a
+/// quit command sets `app_state.quit`.
 pub fn run_loop(
     backend: &mut ui::AppBackend,
     app_state: &mut AppState,
     keymap_t: AppKeyMapping,
+    virtual_paths: Option<Vec<PathBuf>>,
 ) -> std::io::Result<()> {
     let curr_path = std::env::current_dir()?;
 
@@ -63,12 +68,45 @@ pub fn run_loop(
 
         let tab = JoshutoTab::new(curr_path, new_tab_history, tab_display_options)?;
         app_state.state.tab_state_mut().insert_tab(id, tab, true);
+        if dir_settings::apply(app_state) {
+            reload::soft_reload_curr_tab(app_state)?;
+        }
+
+        if let Some(paths) = virtual_paths {
+            if let Err(e) = virtual_list::open_virtual_list(app_state, paths) {
+                app_state
+                    .state
+                    .message_queue_mut()
+                    .push_error(e.to_string());
+            }
+        }
 
         // trigger a preview of child
         preview_default::load_previews(app_state, backend);
     }
 
     while app_state.quit == QuitAction::DoNot {
+        // refresh git status of the shown directories in the background
+        if app_state.config.display_options.show_git_status {
+            app_state.state.load_git_status();
+        }
+        // show cumulative sizes computed for earlier listings of the current directory
+        app_state
+            .state
+            .load_cumulative_sizes(&app_state.config.display_options);
+        // show the free space of the current filesystem
+        if app_state.config.display_options.show_fs_info {
+            app_state.state.load_fs_info();
+        }
+        // detect the MIME types of entries in the background, if shown or sorted by
+        app_state
+            .state
+            .load_mimetypes(&app_state.config.display_options);
+        // compute custom linemode columns of the visible entries in the background
+        app_state
+            .state
+            .load_custom_columns(&app_state.config.display_options);
+
         // do the ui
         if let Ok(size) = backend.terminal_ref().size() {
             let area = Rect {
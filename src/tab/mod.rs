@@ -29,6 +29,9 @@ pub struct JoshutoTab {
     pub history_metadata: HistoryMetadata,
     pub options: TabDisplayOption,
     pub navigation_history: NavigationHistory,
+    /// A listing of arbitrary paths shown in place of the current directory's, until the tab
+    /// changes directory
+    pub virtual_list: Option<JoshutoDirList>,
 }
 
 impl JoshutoTab {
@@ -46,6 +49,7 @@ impl JoshutoTab {
             history_metadata: HashMap::new(),
             navigation_history,
             options: tab_options,
+            virtual_list: None,
         };
 
         Ok(new_tab)
@@ -66,10 +70,12 @@ impl JoshutoTab {
         self.cwd.as_path()
     }
     /// Changes this tab's current directory, recording the previous one and, if
-    /// `history_update` is set, pushing it onto the back/forward navigation history.
+    /// `history_update` is set, pushing it onto the back/forward navigation history. Closes the
+    /// virtual listing, if any.
     pub fn set_cwd(&mut self, cwd: &path::Path, history_update: bool) {
         self.previous_dir = Some(self.cwd.to_path_buf());
         self.cwd = cwd.to_path_buf();
+        self.virtual_list = None;
 
         if history_update {
             self.navigation_history.push(cwd);
@@ -112,9 +118,17 @@ impl JoshutoTab {
         &mut self.navigation_history
     }
 
-    /// Returns the cached listing for the current directory, if present.
+    /// Returns `true` if a virtual listing is shown in place of the current directory's.
+    pub fn is_virtual(&self) -> bool {
+        self.virtual_list.is_some()
+    }
+
+    /// Returns the virtual listing if shown, else the cached listing for the current directory,
+    /// if present.
     pub fn curr_list_ref(&self) -> Option<&JoshutoDirList> {
-        self.history.get(self.get_cwd())
+        self.virtual_list
+            .as_ref()
+            .or_else(|| self.history.get(self.get_cwd()))
     }
     /// Returns the cached listing for the parent of the current directory, if present.
     pub fn parent_list_ref(&self) -> Option<&JoshutoDirList> {
@@ -129,9 +143,13 @@ impl JoshutoTab {
         self.history.get(path)
     }
 
-    /// Returns a mutable reference to the cached listing for the current directory, if present.
+    /// Returns a mutable reference to the virtual listing if shown, else to the cached listing
+    /// for the current directory, if present.
     pub fn curr_list_mut(&mut self) -> Option<&mut JoshutoDirList> {
-        self.history.get_mut(self.cwd.as_path())
+        match self.virtual_list.as_mut() {
+            Some(list) => Some(list),
+            None => self.history.get_mut(self.cwd.as_path()),
+        }
     }
     /// Returns a mutable reference to the cached listing for the parent directory, if present.
     pub fn parent_list_mut(&mut self) -> Option<&mut JoshutoDirList> {
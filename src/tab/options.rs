@@ -1,15 +1,25 @@
-use std::{collections::HashMap, path::PathBuf};
+use std::{
+    collections::{HashMap, HashSet},
+    path::{Path, PathBuf},
+};
 
 use crate::fs::DirListDisplayOptions;
-use crate::types::option::line_mode::LineMode;
-use crate::types::option::sort::SortOption;
+use crate::types::option::dir_settings::DirSettings;
+use crate::types::option::line_mode::{LineMode, LineModeArgs};
+use crate::types::option::sort::{SortMethod, SortOption};
 
 /// Display options valid per JoshutoTab
-#[derive(Clone, Debug, Default)]
+#[derive(Clone, Debug)]
 pub struct TabDisplayOption {
     pub dirlist_options: HashMap<PathBuf, DirListDisplayOptions>,
     pub sort_options: SortOption,
     pub linemode: LineMode,
+    /// the tab's own settings, saved while in a directory with settings of its own
+    pub dir_settings_base: Option<DirSettings>,
+    /// directories whose contents are shown inline in the `tree` display mode
+    pub expanded_dirs: HashSet<PathBuf>,
+    /// whether entries ignored by `.gitignore` and `.ignore` files are listed
+    pub show_ignored: bool,
 }
 
 impl TabDisplayOption {
@@ -23,6 +33,39 @@ impl TabDisplayOption {
         &mut self.sort_options
     }
 
+    /// Returns `true` if the number of entries of directories is shown or sorted by.
+    pub fn needs_item_count(&self) -> bool {
+        self.linemode.contains(&LineModeArgs::ItemCount)
+            || self.sort_options.sort_methods.is_active(&SortMethod::Count)
+    }
+
+    /// Returns `true` if the MIME types of entries are shown or sorted by.
+    pub fn needs_mimetype(&self) -> bool {
+        self.linemode.contains(&LineModeArgs::MimeType)
+            || self.sort_options.sort_methods.is_active(&SortMethod::Type)
+    }
+
+    /// Returns `true` if the contents of `path` are shown inline in the `tree` display mode.
+    pub fn is_expanded(&self, path: &Path) -> bool {
+        self.expanded_dirs.contains(path)
+    }
+
+    /// Returns the expanded directories whose contents are listed in the tree of `base`: those
+    /// below it whose ancestors up to `base` are all expanded too.
+    pub fn expanded_below<'a>(&'a self, base: &'a Path) -> impl Iterator<Item = &'a Path> + 'a {
+        self.expanded_dirs
+            .iter()
+            .map(PathBuf::as_path)
+            .filter(move |dir| {
+                dir.parent().is_some_and(|parent| parent.starts_with(base))
+                    && dir
+                        .ancestors()
+                        .skip(1)
+                        .take_while(|ancestor| *ancestor != base)
+                        .all(|ancestor| self.is_expanded(ancestor))
+            })
+    }
+
     /// Returns the per-directory display options for `path`, if set.
     pub fn dirlist_options_ref(&self, path: &PathBuf) -> Option<&DirListDisplayOptions> {
         self.dirlist_options.get(path)
@@ -37,3 +80,33 @@ impl TabDisplayOption {
         self.dirlist_options.get_mut(path).unwrap()
     }
 }
+
+impl std::default::Default for TabDisplayOption {
+    fn default() -> Self {
+        Self {
+            dirlist_options: HashMap::new(),
+            sort_options: SortOption::default(),
+            linemode: LineMode::default(),
+            dir_settings_base: None,
+            expanded_dirs: HashSet::new(),
+            show_ignored: true,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_expanded_below() {
+        let mut options = TabDisplayOption::default();
+        for dir in ["/d/a", "/d/a/x", "/d/b/y", "/e/z"] {
+            options.expanded_dirs.insert(PathBuf::from(dir));
+        }
+        let mut expanded: Vec<&Path> = options.expanded_below(Path::new("/d")).collect();
+        expanded.sort();
+        // "/d/b" itself is collapsed, hiding "/d/b/y"
+        assert_eq!(expanded, [Path::new("/d/a"), Path::new("/d/a/x")]);
+    }
+}
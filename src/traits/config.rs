@@ -2,15 +2,16 @@ use serde::de::DeserializeOwned;
 use std::fs;
 use std::path::{Path, PathBuf};
 
-use crate::error::AppResult;
+use crate::error::{AppError, AppResult};
 use crate::types::config_type::ConfigType;
 use crate::CONFIG_HIERARCHY;
 
 /// Implemented by types that are loaded from a TOML config file, with a `Raw` deserialization
 /// form and a default used when no file is found or parsing fails.
 pub trait TomlConfigFile: Sized + Default {
-    /// The `serde`-deserializable form read from disk, convertible into `Self`.
-    type Raw: Into<Self> + DeserializeOwned;
+    /// The `serde`-deserializable form read from disk, convertible into `Self`, or failing
+    /// if its values are inconsistent.
+    type Raw: TryInto<Self, Error: Into<AppError>> + DeserializeOwned;
 
     /// Returns which config file this type is loaded from.
     fn get_type() -> ConfigType;
@@ -39,18 +40,18 @@ pub fn search_config_directories(file_name: &str) -> Option<PathBuf> {
 
 fn parse_file_to_config<T, S>(file_path: &Path) -> AppResult<S>
 where
-    T: DeserializeOwned + Into<S>,
+    T: DeserializeOwned + TryInto<S, Error: Into<AppError>>,
 {
     let file_contents = fs::read_to_string(file_path)?;
     let config = toml::from_str::<T>(&file_contents)?;
-    Ok(config.into())
+    config.try_into().map_err(Into::into)
 }
 
 /// Finds, reads, and parses `file_name` from the config directory hierarchy, returning
 /// `S::default()` (and printing a warning) if it's missing or fails to parse.
 pub fn parse_config_or_default<T, S>(file_name: &str) -> S
 where
-    T: DeserializeOwned + Into<S>,
+    T: DeserializeOwned + TryInto<S, Error: Into<AppError>>,
     S: std::default::Default,
 {
     match search_config_directories(file_name) {
@@ -35,9 +35,17 @@ impl AppCommand for Command {
             Self::CopyDirPath => CMD_COPY_DIRECTORY_PATH,
             Self::SymlinkFiles { .. } => CMD_SYMLINK_FILES,
             Self::PasteFiles { .. } => CMD_PASTE_FILES,
+            Self::Extract { .. } => CMD_EXTRACT,
+            Self::Compress { .. } => CMD_COMPRESS,
 
             Self::DeleteFiles { .. } => CMD_DELETE_FILES,
 
+            Self::GitStage => CMD_GIT_STAGE,
+            Self::GitUnstage => CMD_GIT_UNSTAGE,
+            Self::GitRestore { .. } => CMD_GIT_RESTORE,
+            Self::GitDiff => CMD_GIT_DIFF,
+            Self::CdGitRoot => CMD_CD_GIT_ROOT,
+
             Self::CursorMoveUp { .. } => CMD_CURSOR_MOVE_UP,
             Self::CursorMoveDown { .. } => CMD_CURSOR_MOVE_DOWN,
             Self::CursorMoveHome => CMD_CURSOR_MOVE_HOME,
@@ -55,6 +63,7 @@ impl AppCommand for Command {
             Self::PreviewCursorMoveDown { .. } => CMD_PREVIEW_CURSOR_MOVE_DOWN,
 
             Self::NewDirectory { .. } => CMD_NEW_DIRECTORY,
+            Self::NewFromTemplate { .. } => CMD_NEW_FROM_TEMPLATE,
             Self::OpenFile => CMD_OPEN_FILE,
             Self::OpenFileWith { .. } => CMD_OPEN_FILE_WITH,
 
@@ -77,7 +86,8 @@ impl AppCommand for Command {
             Self::SelectString { .. } => CMD_SELECT_STRING,
 
             Self::SetCaseSensitivity { .. } => CMD_SET_CASE_SENSITIVITY,
-            Self::SetMode => CMD_SET_MODE,
+            Self::SetMode { .. } => CMD_SET_MODE,
+            Self::SetTime { .. } => CMD_SET_TIME,
 
             Self::ShowTasks => CMD_SHOW_TASKS,
 
@@ -86,6 +96,8 @@ impl AppCommand for Command {
 
             Self::Sort { .. } => CMD_SORT,
             Self::SortReverse => CMD_SORT_REVERSE,
+            Self::SortReshuffle => CMD_SORT_RESHUFFLE,
+            Self::SortMenu => CMD_SORT_MENU,
 
             Self::FilterGlob { .. } => CMD_FILTER_GLOB,
             Self::FilterRegex { .. } => CMD_FILTER_REGEX,
@@ -113,6 +125,21 @@ impl AppCommand for Command {
             Self::TabSwitch { .. } => CMD_TAB_SWITCH,
             Self::TabSwitchIndex { .. } => CMD_TAB_SWITCH_INDEX,
             Self::ToggleHiddenFiles => CMD_TOGGLE_HIDDEN,
+            Self::ToggleIgnoredFiles => CMD_TOGGLE_IGNORED,
+            Self::HidePattern { .. } => CMD_HIDE_PATTERN,
+            Self::UnhidePattern { .. } => CMD_UNHIDE_PATTERN,
+            Self::FollowLink => CMD_FOLLOW_LINK,
+            Self::EditLink { .. } => CMD_EDIT_LINK,
+            Self::LinkMakeRelative => CMD_LINK_MAKE_RELATIVE,
+            Self::LinkMakeAbsolute => CMD_LINK_MAKE_ABSOLUTE,
+            Self::FindBrokenLinks => CMD_FIND_BROKEN_LINKS,
+            Self::TagToggle { .. } => CMD_TAG_TOGGLE,
+            Self::SelectTag { .. } => CMD_SELECT_TAG,
+            Self::FilterTag { .. } => CMD_FILTER_TAG,
+            Self::CloseVirtual => CMD_CLOSE_VIRTUAL,
+            Self::Find { .. } => CMD_FIND,
+            Self::FindCancel => CMD_FIND_CANCEL,
+            Self::Grep { .. } => CMD_GREP,
             Self::TouchFile { .. } => CMD_TOUCH_FILE,
 
             Self::SearchFzf => CMD_SEARCH_FZF,
@@ -127,7 +154,12 @@ impl AppCommand for Command {
             Self::BookmarkAdd => CMD_BOOKMARK_ADD,
             Self::BookmarkChangeDirectory => CMD_BOOKMARK_CHANGE_DIRECTORY,
 
-            Self::CalculateCumulativeSize => CMD_CALCULATE_CUMULATIVE_SIZE,
+            Self::CalculateCumulativeSize { .. } => CMD_CALCULATE_CUMULATIVE_SIZE,
+            Self::CancelCumulativeSize => CMD_CANCEL_CUMULATIVE_SIZE,
+            Self::DiskUsage => CMD_DISK_USAGE,
+            Self::TreeExpand => CMD_TREE_EXPAND,
+            Self::TreeCollapse => CMD_TREE_COLLAPSE,
+            Self::TreeToggle => CMD_TREE_TOGGLE,
         }
     }
 }
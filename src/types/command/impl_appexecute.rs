@@ -62,6 +62,8 @@ impl AppExecute for Command {
                 file_ops::create_io_task(app_state, FileOperation::Symlink, options)
             }
             Self::PasteFiles { options } => file_ops::create_io_paste_task(app_state, *options),
+            Self::Extract { subdir, overwrite } => archive::extract(app_state, *subdir, *overwrite),
+            Self::Compress { name, overwrite } => archive::compress(app_state, name, *overwrite),
 
             Self::DeleteFiles {
                 background,
@@ -75,6 +77,12 @@ impl AppExecute for Command {
                 *noconfirm,
             ),
 
+            Self::GitStage => git::git_stage(app_state),
+            Self::GitUnstage => git::git_unstage(app_state),
+            Self::GitRestore { noconfirm } => git::git_restore(app_state, backend, *noconfirm),
+            Self::GitDiff => git::git_diff(app_state),
+            Self::CdGitRoot => git::cd_git_root(app_state),
+
             Self::CursorMoveUp { offset } => cursor_move::up(app_state, *offset),
             Self::CursorMoveDown { offset } => cursor_move::down(app_state, *offset),
             Self::CursorMoveHome => cursor_move::home(app_state),
@@ -101,6 +109,17 @@ impl AppExecute for Command {
             }
 
             Self::NewDirectory { path } => new_directory::new_directory(app_state, path.as_path()),
+            Self::NewFromTemplate {
+                template,
+                file_name,
+                raw,
+            } => new_from_template::new_from_template(
+                app_state,
+                backend,
+                template.as_deref(),
+                file_name.as_deref(),
+                *raw,
+            ),
             Self::OpenFile => open_file::open(app_state, backend),
             Self::OpenFileWith { index: None } => {
                 open_file::open_with_interactive(app_state, backend)
@@ -154,15 +173,23 @@ impl AppExecute for Command {
                 case_sensitivity,
                 set_type,
             } => case_sensitivity::set_case_sensitivity(app_state, *case_sensitivity, *set_type),
-            Self::SetMode => set_mode::set_mode(app_state, backend),
+            Self::SetMode { options } => set_mode::set_mode(app_state, backend, *options),
+            Self::SetTime {
+                source,
+                accessed,
+                modified,
+                recursive,
+            } => set_time::set_time(app_state, source, *accessed, *modified, *recursive),
             Self::ShowTasks => show_tasks::show_tasks(app_state, backend, keymap_t),
             Self::Sort {
                 sort_method,
                 reverse,
-            } => sort::set_sort(app_state, *sort_method, *reverse),
+            } => sort::set_sort(app_state, sort_method.clone(), *reverse),
             Self::SetDisplayMode(mode) => set_display_mode::set_display_mode(app_state, *mode),
-            Self::SetLineMode(mode) => linemode::set_linemode(app_state, *mode),
+            Self::SetLineMode(mode) => linemode::set_linemode(app_state, mode.clone()),
             Self::SortReverse => sort::toggle_reverse(app_state),
+            Self::SortReshuffle => sort::reshuffle(app_state),
+            Self::SortMenu => sort::sort_menu(app_state, backend),
             Self::SignalSuspend => signal::signal_suspend(backend),
             Self::SubProcess { words, mode } => {
                 sub_process::sub_process(app_state, backend, words.as_slice(), mode.clone())
@@ -184,6 +211,27 @@ impl AppExecute for Command {
             }
 
             Self::ToggleHiddenFiles => show_hidden::toggle_hidden(app_state),
+            Self::ToggleIgnoredFiles => show_ignored::toggle_ignored(app_state),
+            Self::HidePattern { pattern } => hidden_patterns::hide_pattern(app_state, pattern),
+            Self::UnhidePattern { pattern } => hidden_patterns::unhide_pattern(app_state, pattern),
+            Self::FollowLink => symlink::follow_link(app_state),
+            Self::EditLink { target } => {
+                symlink::edit_link(app_state, backend, keymap_t, target.as_deref())
+            }
+            Self::LinkMakeRelative => symlink::link_convert(app_state, true),
+            Self::LinkMakeAbsolute => symlink::link_convert(app_state, false),
+            Self::FindBrokenLinks => symlink::find_broken_links(app_state),
+            Self::TagToggle { tag } => tags::tag_toggle(app_state, *tag),
+            Self::SelectTag { tag, options } => tags::select_tag(app_state, *tag, options),
+            Self::FilterTag { tag } => tags::filter_tag(app_state, *tag),
+            Self::CloseVirtual => virtual_list::close_virtual_list(app_state),
+            Self::Find {
+                pattern,
+                pattern_type,
+                filters,
+            } => find::find(app_state, pattern, *pattern_type, *filters),
+            Self::FindCancel => find::find_cancel(app_state),
+            Self::Grep { pattern } => grep::grep(app_state, backend, keymap_t, pattern),
 
             Self::TabSwitch { offset } => {
                 tab_ops::tab_switch(app_state, *offset).map_err(|e| e.into())
@@ -204,7 +252,15 @@ impl AppExecute for Command {
                 bookmark::change_directory_bookmark(app_state, backend)
             }
 
-            Self::CalculateCumulativeSize => cumulative_size::calculate_cumulative_size(app_state),
+            Self::CalculateCumulativeSize { all, force } => {
+                cumulative_size::calculate_cumulative_size(app_state, *all, *force)
+            }
+            Self::CancelCumulativeSize => cumulative_size::cancel_cumulative_size(app_state),
+            Self::DiskUsage => disk_usage::disk_usage(app_state, backend, keymap_t),
+
+            Self::TreeExpand => tree::tree_expand(app_state),
+            Self::TreeCollapse => tree::tree_collapse(app_state),
+            Self::TreeToggle => tree::tree_toggle(app_state),
 
             Self::CustomSearch(words) => {
                 custom_search::custom_search(app_state, backend, words.as_slice(), false)
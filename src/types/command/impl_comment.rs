@@ -56,8 +56,17 @@ impl CommandComment for Command {
                 (false, true) => "Paste, skip existing files",
                 _ => "Paste",
             },
+            Self::Extract { subdir: true, .. } => "Extract selected archives into new directories",
+            Self::Extract { .. } => "Extract selected archives",
+            Self::Compress { .. } => "Compress selected files into an archive",
             Self::DeleteFiles { .. } => "Delete selected files",
 
+            Self::GitStage => "Stage selected files",
+            Self::GitUnstage => "Unstage selected files",
+            Self::GitRestore { .. } => "Discard changes to selected files",
+            Self::GitDiff => "Show changes of selected files in preview",
+            Self::CdGitRoot => "Change to top level of git repository",
+
             Self::CursorMoveUp { .. } => "Move cursor up",
             Self::CursorMoveDown { .. } => "Move cursor down",
             Self::CursorMoveHome => "Move cursor to the very top",
@@ -76,6 +85,7 @@ impl CommandComment for Command {
             Self::PreviewCursorMoveDown { .. } => "Cursor down in file preview",
 
             Self::NewDirectory { .. } => "Make a new directory",
+            Self::NewFromTemplate { .. } => "Create a new file from a template",
             Self::OpenFile => "Open a file",
             Self::OpenFileWith { .. } => "Open using selected program",
 
@@ -100,7 +110,8 @@ impl CommandComment for Command {
             Self::SelectString { .. } => "Select files",
 
             Self::SetCaseSensitivity { .. } => "Set case sensitivity",
-            Self::SetMode => "Set file permissions",
+            Self::SetMode { .. } => "Set file permissions",
+            Self::SetTime { .. } => "Set file access/modification times",
             Self::SubProcess { mode: SubprocessCallMode::Interactive, .. } => "Run a shell command (blocking) and hand over shell temporarily",
             Self::SubProcess { mode: SubprocessCallMode::Spawn, .. } => "Spawn a shell command",
             Self::SubProcess { mode: SubprocessCallMode::Capture, .. } => "Run a shell command (blocking), do not hand over shall but capture stdout for post-processing",
@@ -110,6 +121,21 @@ impl CommandComment for Command {
             Self::SignalSuspend => "Suspend the current session",
 
             Self::ToggleHiddenFiles => "Toggle hidden files displaying",
+            Self::ToggleIgnoredFiles => "Toggle git-ignored files displaying",
+            Self::HidePattern { .. } => "Hide files matching a pattern",
+            Self::UnhidePattern { .. } => "Stop hiding files matching a pattern",
+            Self::FollowLink => "Jump to the target of a symlink",
+            Self::EditLink { .. } => "Change the target of a symlink",
+            Self::LinkMakeRelative => "Make the targets of symlinks relative",
+            Self::LinkMakeAbsolute => "Make the targets of symlinks absolute",
+            Self::FindBrokenLinks => "Find broken symlinks below the current directory",
+            Self::TagToggle { .. } => "Toggle a tag on files",
+            Self::SelectTag { .. } => "Select files by tag",
+            Self::FilterTag { .. } => "Filter directory list by tag",
+            Self::CloseVirtual => "Leave the virtual listing",
+            Self::Find { .. } => "Find files below the current directory",
+            Self::FindCancel => "Cancel the running find",
+            Self::Grep { .. } => "Search the contents of files below the current directory",
 
             Self::SwitchLineNums(_) => "Switch line numbering",
 
@@ -122,8 +148,21 @@ impl CommandComment for Command {
                 SortMethod::Natural => "Sort naturally",
                 SortMethod::Size => "Sort by size",
                 SortMethod::Ext => "Sort by extension",
+                SortMethod::Ctime => "Sort by status change time",
+                SortMethod::Atime => "Sort by access time",
+                SortMethod::Btime => "Sort by creation time",
+                SortMethod::Type => "Sort by file type",
+                SortMethod::Count => "Sort by number of directory entries",
+                SortMethod::CumulativeSize => "Sort by cumulative size",
+                SortMethod::Length => "Sort by name length",
+                SortMethod::Random => "Sort randomly",
+                SortMethod::Version => "Sort by version numbers",
+                SortMethod::Tag => "Sort by tag",
+                SortMethod::Custom(_) => "Sort by custom column",
             },
             Self::SortReverse => "Reverse sort order",
+            Self::SortReshuffle => "Reshuffle random sort order",
+            Self::SortMenu => "Choose sort method from a menu",
 
             Self::FilterGlob { .. } => "Filter directory list with globbing",
             Self::FilterRegex { .. } => "Filter directory list with regex",
@@ -141,7 +180,17 @@ impl CommandComment for Command {
 
             Self::BookmarkAdd => "Add a bookmark",
             Self::BookmarkChangeDirectory => "Navigate to a bookmark",
-            Self::CalculateCumulativeSize => "Calculate cumulative size of selected files",
+            Self::CalculateCumulativeSize { all: false, .. } => {
+                "Calculate cumulative size of selected files"
+            }
+            Self::CalculateCumulativeSize { all: true, .. } => {
+                "Calculate cumulative size of all directories"
+            }
+            Self::CancelCumulativeSize => "Cancel calculating cumulative sizes",
+            Self::DiskUsage => "Analyze disk usage of the current directory",
+            Self::TreeExpand => "Expand directory in tree mode",
+            Self::TreeCollapse => "Collapse directory in tree mode",
+            Self::TreeToggle => "Expand or collapse directory in tree mode",
             Self::CustomSearch(_) => "Find file based on the custom command",
             Self::CustomSearchInteractive(_) => {
                 "Interactively find file based on the custom command"
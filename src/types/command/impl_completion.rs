@@ -8,12 +8,20 @@ impl CommandCompletion for Command {
     fn completion_kind<'a>(cmd: &'a str) -> Option<CompletionKind<'a>> {
         Some(match cmd {
             CMD_CHANGE_DIRECTORY => CompletionKind::Dir(None),
+            CMD_EDIT_LINK => CompletionKind::File,
             CMD_DELETE_FILES => CompletionKind::Custom(vec![
                 "--background=false",
                 "--background=true",
                 "--noconfirm",
                 "--permanently",
             ]),
+            CMD_EXTRACT => CompletionKind::Custom(vec![
+                "--overwrite=false",
+                "--overwrite=true",
+                "--subdir=false",
+                "--subdir=true",
+            ]),
+            CMD_GIT_RESTORE => CompletionKind::Custom(vec!["--noconfirm"]),
             CMD_NEW_TAB => CompletionKind::Dir(Some(vec!["--current", "--cursor", "--last"])),
             CMD_OPEN_FILE_WITH
             | CMD_SUBPROCESS_CAPTURE
@@ -26,16 +34,19 @@ impl CommandCompletion for Command {
                 "--output-selected-files",
             ]),
             CMD_SEARCH_INCREMENTAL | CMD_SEARCH_STRING => CompletionKind::File,
-            CMD_SELECT_FZF | CMD_SELECT_GLOB | CMD_SELECT_REGEX | CMD_SELECT_STRING => {
-                CompletionKind::Custom(vec![
-                    "--all=false",
-                    "--all=true",
-                    "--deselect=false",
-                    "--deselect=true",
-                    "--toggle=false",
-                    "--toggle=true",
-                ])
-            }
+            CMD_FILTER_TAG => CompletionKind::Custom(vec!["--clear"]),
+            CMD_FIND => CompletionKind::Custom(vec![
+                "--glob", "--mtime=", "--regex", "--size=", "--type=d", "--type=f", "--type=l",
+            ]),
+            CMD_SELECT_FZF | CMD_SELECT_GLOB | CMD_SELECT_REGEX | CMD_SELECT_STRING
+            | CMD_SELECT_TAG => CompletionKind::Custom(vec![
+                "--all=false",
+                "--all=true",
+                "--deselect=false",
+                "--deselect=true",
+                "--toggle=false",
+                "--toggle=true",
+            ]),
             CMD_SET_CASE_SENSITIVITY => CompletionKind::Custom(vec![
                 "--type=fzf",
                 "--type=glob",
@@ -45,19 +56,47 @@ impl CommandCompletion for Command {
                 "insensitive",
                 "sensitive",
             ]),
-            CMD_SET_DISPLAY_MODE => CompletionKind::Custom(vec!["default", "minimal", "hsplit"]),
+            CMD_SET_MODE => CompletionKind::Custom(vec![
+                "--dirs=",
+                "--files=",
+                "--recursive=false",
+                "--recursive=true",
+            ]),
+            CMD_SET_TIME => CompletionKind::Custom(vec![
+                "--atime",
+                "--mtime",
+                "--recursive=false",
+                "--recursive=true",
+                "--reference=",
+                "now",
+            ]),
+            CMD_CALCULATE_CUMULATIVE_SIZE => CompletionKind::Custom(vec!["--all", "--force"]),
+            CMD_SET_DISPLAY_MODE => {
+                CompletionKind::Custom(vec!["default", "minimal", "hsplit", "tree"])
+            }
             CMD_SET_LINEMODE => CompletionKind::Custom(vec![
                 "all", "group", "mtime", "none", "perm", "size", "user",
             ]),
             CMD_SORT => CompletionKind::Custom(vec![
                 "--reverse=false",
                 "--reverse=true",
+                "atime",
+                "btime",
+                "count",
+                "ctime",
+                "cumsize",
                 "ext",
+                "length",
                 "lexical",
                 "mtime",
                 "natural",
+                "random",
+                "reshuffle",
                 "reverse",
                 "size",
+                "tag",
+                "type",
+                "version",
             ]),
             CMD_SWITCH_LINE_NUMBERS => CompletionKind::Custom(vec!["absolute", "none", "relative"]),
             CMD_SYMLINK_FILES => {
@@ -1,3 +1,5 @@
+use crate::commands::case_sensitivity::SetType;
+use crate::fs::TagMatch;
 use crate::traits::app_execute::AppCommand;
 
 use super::Command;
@@ -26,6 +28,16 @@ impl std::fmt::Display for Command {
                 write!(f, "{} --relative={}", self.command(), relative)
             }
             Self::PasteFiles { options } => write!(f, "{}  {}", self.command(), options),
+            Self::Extract { subdir, overwrite } => write!(
+                f,
+                "{} --subdir={} --overwrite={}",
+                self.command(),
+                subdir,
+                overwrite
+            ),
+            Self::Compress { name, overwrite } => {
+                write!(f, "{} --overwrite={} {:?}", self.command(), overwrite, name)
+            }
             Self::DeleteFiles {
                 background,
                 permanently,
@@ -45,8 +57,31 @@ impl std::fmt::Display for Command {
                 )
             }
 
+            Self::GitRestore { noconfirm } => write!(
+                f,
+                "{}{}",
+                self.command(),
+                if *noconfirm { " --noconfirm" } else { "" },
+            ),
+
+            Self::EditLink {
+                target: Some(target),
+            } => write!(f, "{} {:?}", self.command(), target),
             Self::RenameFile { new_name } => write!(f, "{} {:?}", self.command(), new_name),
 
+            Self::TagToggle { tag: Some(tag) } => write!(f, "{} {}", self.command(), tag),
+            Self::SelectTag {
+                tag: TagMatch::Tag(tag),
+                ..
+            } => write!(f, "{} {}", self.command(), tag),
+            Self::FilterTag { tag: None } => write!(f, "{} --clear", self.command()),
+            Self::FilterTag {
+                tag: Some(TagMatch::Tag(tag)),
+            } => write!(f, "{} {}", self.command(), tag),
+
+            Self::HidePattern { pattern } => write!(f, "{} {}", self.command(), pattern),
+            Self::UnhidePattern { pattern } => write!(f, "{} {}", self.command(), pattern),
+            Self::Grep { pattern } => write!(f, "{} {}", self.command(), pattern),
             Self::SearchGlob { pattern } => write!(f, "{} {}", self.command(), pattern),
             Self::SearchRegex { pattern } => write!(f, "{} {}", self.command(), pattern),
             Self::SearchString { pattern } => write!(f, "{} {}", self.command(), pattern),
@@ -67,6 +102,38 @@ impl std::fmt::Display for Command {
             ),
             Self::TabSwitch { offset } => write!(f, "{} {}", self.command(), offset),
             Self::TabSwitchIndex { index } => write!(f, "{} {}", self.command(), index),
+            Self::CalculateCumulativeSize { all, force } => write!(
+                f,
+                "{}{}{}",
+                self.command(),
+                if *all { " --all" } else { "" },
+                if *force { " --force" } else { "" },
+            ),
+            Self::Find {
+                pattern,
+                pattern_type,
+                filters,
+            } => {
+                write!(f, "{}", self.command())?;
+                match pattern_type {
+                    SetType::Glob => write!(f, " --glob")?,
+                    SetType::Regex => write!(f, " --regex")?,
+                    SetType::String | SetType::Fzf => {}
+                }
+                if let Some(file_type) = filters.file_type {
+                    write!(f, " --type={}", file_type.as_str())?;
+                }
+                if let Some(size) = filters.size {
+                    write!(f, " --size={}", size)?;
+                }
+                if let Some(mtime) = filters.mtime {
+                    write!(f, " --mtime={}s", mtime)?;
+                }
+                if !pattern.is_empty() {
+                    write!(f, " {:?}", pattern)?;
+                }
+                Ok(())
+            }
             _ => write!(f, "{}", self.command()),
         }
     }
@@ -3,11 +3,13 @@ use std::path;
 use crate::commands::case_sensitivity::SetType;
 use crate::commands::quit::QuitAction;
 use crate::commands::select::SelectOption;
+use crate::commands::set_time::TimeSource;
 use crate::commands::stdout::PostProcessor;
 use crate::commands::sub_process::SubprocessCallMode;
 use crate::error::{AppError, AppErrorKind};
+use crate::fs::{ArchiveKind, Bound, FindFilters, FindType, TagMatch};
 use crate::tab::NewTabMode;
-use crate::types::io::FileOperationOptions;
+use crate::types::io::{FileOperationOptions, ModeChangeOptions, TimeChange};
 use crate::types::option::display::DisplayMode;
 use crate::types::option::line_mode::{LineMode, LineNumberStyle};
 use crate::types::option::search::CaseSensitivity;
@@ -90,6 +92,11 @@ impl std::str::FromStr for Command {
         // simple_command_conversion_case!(command, CMD_COPY_FILEPATH, Self::CopyFilePath);
         simple_command_conversion_case!(command, CMD_COPY_DIRECTORY_PATH, Self::CopyDirPath);
 
+        simple_command_conversion_case!(command, CMD_GIT_STAGE, Self::GitStage);
+        simple_command_conversion_case!(command, CMD_GIT_UNSTAGE, Self::GitUnstage);
+        simple_command_conversion_case!(command, CMD_GIT_DIFF, Self::GitDiff);
+        simple_command_conversion_case!(command, CMD_CD_GIT_ROOT, Self::CdGitRoot);
+
         simple_command_conversion_case!(command, CMD_OPEN_FILE, Self::OpenFile);
 
         simple_command_conversion_case!(command, CMD_RELOAD_DIRECTORY_LIST, Self::ReloadDirList);
@@ -104,8 +111,15 @@ impl std::str::FromStr for Command {
         simple_command_conversion_case!(command, CMD_SEARCH_NEXT, Self::SearchNext);
         simple_command_conversion_case!(command, CMD_SEARCH_PREV, Self::SearchPrev);
         simple_command_conversion_case!(command, CMD_SHOW_TASKS, Self::ShowTasks);
-        simple_command_conversion_case!(command, CMD_SET_MODE, Self::SetMode);
+        simple_command_conversion_case!(command, CMD_SORT_MENU, Self::SortMenu);
         simple_command_conversion_case!(command, CMD_TOGGLE_HIDDEN, Self::ToggleHiddenFiles);
+        simple_command_conversion_case!(command, CMD_TOGGLE_IGNORED, Self::ToggleIgnoredFiles);
+        simple_command_conversion_case!(command, CMD_FOLLOW_LINK, Self::FollowLink);
+        simple_command_conversion_case!(command, CMD_LINK_MAKE_RELATIVE, Self::LinkMakeRelative);
+        simple_command_conversion_case!(command, CMD_LINK_MAKE_ABSOLUTE, Self::LinkMakeAbsolute);
+        simple_command_conversion_case!(command, CMD_FIND_BROKEN_LINKS, Self::FindBrokenLinks);
+        simple_command_conversion_case!(command, CMD_CLOSE_VIRTUAL, Self::CloseVirtual);
+        simple_command_conversion_case!(command, CMD_FIND_CANCEL, Self::FindCancel);
         simple_command_conversion_case!(command, CMD_BULK_RENAME, Self::BulkRename);
 
         simple_command_conversion_case!(command, CMD_SEARCH_FZF, Self::SearchFzf);
@@ -130,9 +144,13 @@ impl std::str::FromStr for Command {
 
         simple_command_conversion_case!(
             command,
-            CMD_CALCULATE_CUMULATIVE_SIZE,
-            Self::CalculateCumulativeSize
+            CMD_CANCEL_CUMULATIVE_SIZE,
+            Self::CancelCumulativeSize
         );
+        simple_command_conversion_case!(command, CMD_DISK_USAGE, Self::DiskUsage);
+        simple_command_conversion_case!(command, CMD_TREE_EXPAND, Self::TreeExpand);
+        simple_command_conversion_case!(command, CMD_TREE_COLLAPSE, Self::TreeCollapse);
+        simple_command_conversion_case!(command, CMD_TREE_TOGGLE, Self::TreeToggle);
 
         if command == CMD_QUIT {
             match arg {
@@ -237,6 +255,29 @@ impl std::str::FromStr for Command {
                 let path = path::PathBuf::from(arg);
                 Ok(Self::NewDirectory { path })
             }
+        } else if command == CMD_NEW_FROM_TEMPLATE {
+            match shell_words::split(arg) {
+                Ok(args) => {
+                    let mut raw = false;
+                    let mut words = Vec::new();
+                    for arg in args {
+                        match arg.as_str() {
+                            "--raw" => raw = true,
+                            _ => words.push(arg),
+                        }
+                    }
+                    let mut words = words.into_iter();
+                    Ok(Self::NewFromTemplate {
+                        template: words.next(),
+                        file_name: words.next().map(path::PathBuf::from),
+                        raw,
+                    })
+                }
+                Err(e) => Err(AppError::new(
+                    AppErrorKind::InvalidParameters,
+                    format!("{}: {}", arg, e),
+                )),
+            }
         } else if command == CMD_OPEN_FILE_WITH {
             match arg {
                 "" => Ok(Self::OpenFileWith { index: None }),
@@ -292,6 +333,58 @@ impl std::str::FromStr for Command {
                 }
             }
             Ok(Self::PasteFiles { options })
+        } else if command == CMD_EXTRACT {
+            let [mut subdir, mut overwrite] = [false; 2];
+            for arg in arg.split_whitespace() {
+                match arg {
+                    "--subdir=true" => subdir = true,
+                    "--subdir=false" => subdir = false,
+                    "--overwrite=true" => overwrite = true,
+                    "--overwrite=false" => overwrite = false,
+                    _ => {
+                        return Err(AppError::new(
+                            AppErrorKind::UnrecognizedArgument,
+                            format!("{}: unknown option '{}'", command, arg),
+                        ));
+                    }
+                }
+            }
+            Ok(Self::Extract { subdir, overwrite })
+        } else if command == CMD_COMPRESS {
+            match shell_words::split(arg) {
+                Ok(args) => {
+                    let mut overwrite = false;
+                    let mut name = None;
+                    for arg in args {
+                        match arg.as_str() {
+                            "--overwrite=true" => overwrite = true,
+                            "--overwrite=false" => overwrite = false,
+                            _ => name = Some(path::PathBuf::from(arg)),
+                        }
+                    }
+                    match name {
+                        Some(name) if ArchiveKind::from_path(&name).is_some() => {
+                            Ok(Self::Compress { name, overwrite })
+                        }
+                        Some(name) => Err(AppError::new(
+                            AppErrorKind::InvalidParameters,
+                            format!(
+                                "{}: {}: expected a .zip, .tar, .tar.gz, .tar.xz or .tar.zst file name",
+                                command,
+                                name.display()
+                            ),
+                        )),
+                        None => Err(AppError::new(
+                            AppErrorKind::InvalidParameters,
+                            format!("{}: no archive name given", command),
+                        )),
+                    }
+                }
+                Err(e) => Err(AppError::new(
+                    AppErrorKind::InvalidParameters,
+                    format!("{}: {}", arg, e),
+                )),
+            }
         } else if command == CMD_DELETE_FILES {
             let [mut permanently, mut background, mut noconfirm] = [false; 3];
             for arg in arg.split_whitespace() {
@@ -313,6 +406,15 @@ impl std::str::FromStr for Command {
                 permanently,
                 noconfirm,
             })
+        } else if command == CMD_GIT_RESTORE {
+            match arg {
+                "" => Ok(Self::GitRestore { noconfirm: false }),
+                "--noconfirm" => Ok(Self::GitRestore { noconfirm: true }),
+                arg => Err(AppError::new(
+                    AppErrorKind::UnrecognizedArgument,
+                    format!("{}: unknown option '{}'", command, arg),
+                )),
+            }
         } else if command == CMD_RENAME_FILE {
             match arg {
                 "" => Err(AppError::new(
@@ -470,6 +572,53 @@ impl std::str::FromStr for Command {
                     format!("{}: {}", arg, e),
                 )),
             }
+        } else if command == CMD_SET_MODE {
+            let options = ModeChangeOptions::default().parse_args(arg)?;
+            Ok(Self::SetMode { options })
+        } else if command == CMD_SET_TIME {
+            match shell_words::split(arg) {
+                Ok(args) => {
+                    let [mut accessed, mut modified, mut recursive] = [false; 3];
+                    let mut sources = Vec::new();
+                    for arg in args.iter() {
+                        match arg.as_str() {
+                            "--atime" => accessed = true,
+                            "--mtime" => modified = true,
+                            "--recursive" | "--recursive=true" => recursive = true,
+                            "--recursive=false" => recursive = false,
+                            s => {
+                                if let Some(path) = s.strip_prefix("--reference=") {
+                                    sources.push(TimeSource::Reference(unix::expand_shell_string(
+                                        path,
+                                    )));
+                                } else {
+                                    sources.push(TimeSource::Change(TimeChange::from_str(s)?));
+                                }
+                            }
+                        }
+                    }
+                    if !accessed && !modified {
+                        accessed = true;
+                        modified = true;
+                    }
+                    if sources.len() != 1 {
+                        return Err(AppError::new(
+                            AppErrorKind::InvalidParameters,
+                            format!("{}: Expected 1, got {}", command, sources.len()),
+                        ));
+                    }
+                    Ok(Self::SetTime {
+                        source: sources.remove(0),
+                        accessed,
+                        modified,
+                        recursive,
+                    })
+                }
+                Err(e) => Err(AppError::new(
+                    AppErrorKind::InvalidParameters,
+                    format!("{}: {}", arg, e),
+                )),
+            }
         } else if command == CMD_SET_CASE_SENSITIVITY {
             match shell_words::split(arg) {
                 Ok(args) => {
@@ -537,9 +686,26 @@ impl std::str::FromStr for Command {
                     format!("{} is not a valid argument for stdout post-processing", arg),
                 ))
             }
+        } else if command == CMD_CALCULATE_CUMULATIVE_SIZE {
+            let mut all = false;
+            let mut force = false;
+            for arg in arg.split_whitespace() {
+                match arg {
+                    "--all" => all = true,
+                    "--force" => force = true,
+                    _ => {
+                        return Err(AppError::new(
+                            AppErrorKind::InvalidParameters,
+                            format!("{}: Unknown option '{}'", command, arg),
+                        ))
+                    }
+                }
+            }
+            Ok(Self::CalculateCumulativeSize { all, force })
         } else if command == CMD_SORT {
             match arg {
                 "reverse" => Ok(Self::SortReverse),
+                "reshuffle" => Ok(Self::SortReshuffle),
                 arg => {
                     let (sort, reverse) = match arg.split_once(' ') {
                         Some((s, "--reverse=true")) => (s, Some(true)),
@@ -570,6 +736,7 @@ impl std::str::FromStr for Command {
                 "default" => Ok(Self::SetDisplayMode(DisplayMode::Default)),
                 "minimal" => Ok(Self::SetDisplayMode(DisplayMode::Minimal)),
                 "hsplit" => Ok(Self::SetDisplayMode(DisplayMode::HSplit)),
+                "tree" => Ok(Self::SetDisplayMode(DisplayMode::Tree)),
                 _ => Err(AppError::new(
                     AppErrorKind::InvalidParameters,
                     format!("{}: Unknown option '{}'", command, arg),
@@ -621,6 +788,108 @@ impl std::str::FromStr for Command {
                     format!("{}: no starting character given", command),
                 )),
             }
+        } else if command == CMD_EDIT_LINK {
+            let target = match arg {
+                "" => None,
+                arg => Some(path::PathBuf::from(arg)),
+            };
+            Ok(Self::EditLink { target })
+        } else if command == CMD_TAG_TOGGLE {
+            Ok(Self::TagToggle {
+                tag: parse_tag(command, arg)?,
+            })
+        } else if command == CMD_SELECT_TAG {
+            let mut options = SelectOption::default();
+            let mut tag = "";
+            for arg in arg.split_whitespace() {
+                match arg {
+                    "--toggle=true" => options.toggle = true,
+                    "--all=true" => options.all = true,
+                    "--toggle=false" => options.toggle = false,
+                    "--all=false" => options.all = false,
+                    "--deselect=true" => options.reverse = true,
+                    "--deselect=false" => options.reverse = false,
+                    s => tag = s,
+                }
+            }
+            let tag = parse_tag(command, tag)?.map_or(TagMatch::Any, TagMatch::Tag);
+            Ok(Self::SelectTag { tag, options })
+        } else if command == CMD_FILTER_TAG {
+            let tag = match arg {
+                "--clear" => None,
+                arg => Some(parse_tag(command, arg)?.map_or(TagMatch::Any, TagMatch::Tag)),
+            };
+            Ok(Self::FilterTag { tag })
+        } else if command == CMD_FIND {
+            let args = shell_words::split(arg).map_err(|e| {
+                AppError::new(AppErrorKind::InvalidParameters, format!("{}: {}", arg, e))
+            })?;
+            let invalid = |arg: &str| {
+                AppError::new(
+                    AppErrorKind::InvalidParameters,
+                    format!("{}: Invalid option '{}'", command, arg),
+                )
+            };
+            let mut pattern = None;
+            let mut pattern_type = SetType::String;
+            let mut filters = FindFilters::default();
+            for arg in args.iter() {
+                match arg.as_str() {
+                    "--glob" => pattern_type = SetType::Glob,
+                    "--regex" => pattern_type = SetType::Regex,
+                    s if s.starts_with("--type=") => {
+                        filters.file_type = Some(
+                            FindType::from_str(&s["--type=".len()..]).ok_or_else(|| invalid(s))?,
+                        );
+                    }
+                    s if s.starts_with("--size=") => {
+                        filters.size = Some(
+                            Bound::parse_size(&s["--size=".len()..]).ok_or_else(|| invalid(s))?,
+                        );
+                    }
+                    s if s.starts_with("--mtime=") => {
+                        filters.mtime = Some(
+                            Bound::parse_age(&s["--mtime=".len()..]).ok_or_else(|| invalid(s))?,
+                        );
+                    }
+                    s if s.starts_with("--") => return Err(invalid(s)),
+                    s if pattern.is_none() => pattern = Some(s.to_string()),
+                    _ => {
+                        return Err(AppError::new(
+                            AppErrorKind::InvalidParameters,
+                            format!("{}: Expected at most 1 pattern", command),
+                        ))
+                    }
+                }
+            }
+            Ok(Self::Find {
+                pattern: pattern.unwrap_or_default(),
+                pattern_type,
+                filters,
+            })
+        } else if command == CMD_GREP {
+            if arg.is_empty() {
+                return Err(AppError::new(
+                    AppErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                ));
+            }
+            Ok(Self::Grep {
+                pattern: arg.to_string(),
+            })
+        } else if command == CMD_HIDE_PATTERN || command == CMD_UNHIDE_PATTERN {
+            if arg.is_empty() {
+                return Err(AppError::new(
+                    AppErrorKind::InvalidParameters,
+                    format!("{}: Expected 1, got 0", command),
+                ));
+            }
+            let pattern = arg.to_string();
+            if command == CMD_HIDE_PATTERN {
+                Ok(Self::HidePattern { pattern })
+            } else {
+                Ok(Self::UnhidePattern { pattern })
+            }
         } else if command == CMD_FILTER_GLOB {
             Ok(Self::FilterGlob {
                 pattern: arg.to_string(),
@@ -663,3 +932,41 @@ impl std::str::FromStr for Command {
         }
     }
 }
+
+/// Parses the optional tag character argument of the tag commands.
+fn parse_tag(command: &str, arg: &str) -> Result<Option<char>, AppError> {
+    let mut chars = arg.chars();
+    match (chars.next(), chars.next()) {
+        (None, _) => Ok(None),
+        (Some(c), None) => Ok(Some(c)),
+        _ => Err(AppError::new(
+            AppErrorKind::InvalidParameters,
+            format!("{}: Expected a single character, got {}", command, arg),
+        )),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use super::*;
+
+    #[test]
+    fn test_set_time_takes_one_time() {
+        assert!(Command::from_str("set_time 2024-01-31 --mtime").is_ok());
+        let error = |s| Command::from_str(s).err().map(|e| e.to_string());
+        assert_eq!(
+            error("set_time --atime").as_deref(),
+            Some("set_time: Expected 1, got 0")
+        );
+        assert_eq!(
+            error("set_time 2024-01-31 -2h").as_deref(),
+            Some("set_time: Expected 1, got 2")
+        );
+        assert_eq!(
+            error("set_time --reference=a.txt 2024-01-31").as_deref(),
+            Some("set_time: Expected 1, got 2")
+        );
+    }
+}
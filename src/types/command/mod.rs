@@ -16,10 +16,12 @@ use std::path;
 use crate::commands::case_sensitivity::SetType;
 use crate::commands::quit::QuitAction;
 use crate::commands::select::SelectOption;
+use crate::commands::set_time::TimeSource;
 use crate::commands::stdout::PostProcessor;
 use crate::commands::sub_process::SubprocessCallMode;
+use crate::fs::{FindFilters, TagMatch};
 use crate::tab::NewTabMode;
-use crate::types::io::FileOperationOptions;
+use crate::types::io::{FileOperationOptions, ModeChangeOptions};
 use crate::types::option::display::DisplayMode;
 use crate::types::option::line_mode::{LineMode, LineNumberStyle};
 use crate::types::option::search::CaseSensitivity;
@@ -64,6 +66,14 @@ pub enum Command {
     PasteFiles {
         options: FileOperationOptions,
     },
+    Extract {
+        subdir: bool,
+        overwrite: bool,
+    },
+    Compress {
+        name: path::PathBuf,
+        overwrite: bool,
+    },
 
     DeleteFiles {
         background: bool,
@@ -71,6 +81,14 @@ pub enum Command {
         noconfirm: bool,
     },
 
+    GitStage,
+    GitUnstage,
+    GitRestore {
+        noconfirm: bool,
+    },
+    GitDiff,
+    CdGitRoot,
+
     CursorMoveUp {
         offset: usize,
     },
@@ -107,6 +125,11 @@ pub enum Command {
     NewDirectory {
         path: path::PathBuf,
     },
+    NewFromTemplate {
+        template: Option<String>,
+        file_name: Option<path::PathBuf>,
+        raw: bool,
+    },
     OpenFile,
     OpenFileWith {
         index: Option<usize>,
@@ -157,7 +180,15 @@ pub enum Command {
         case_sensitivity: CaseSensitivity,
         set_type: SetType,
     },
-    SetMode,
+    SetMode {
+        options: ModeChangeOptions,
+    },
+    SetTime {
+        source: TimeSource,
+        accessed: bool,
+        modified: bool,
+        recursive: bool,
+    },
     SubProcess {
         words: Vec<String>,
         mode: SubprocessCallMode,
@@ -181,6 +212,8 @@ pub enum Command {
         reverse: Option<bool>,
     },
     SortReverse,
+    SortReshuffle,
+    SortMenu,
 
     FilterGlob {
         pattern: String,
@@ -222,5 +255,54 @@ pub enum Command {
     BookmarkAdd,
     BookmarkChangeDirectory,
 
-    CalculateCumulativeSize,
+    CalculateCumulativeSize {
+        all: bool,
+        force: bool,
+    },
+    CancelCumulativeSize,
+    DiskUsage,
+
+    TreeExpand,
+    TreeCollapse,
+    TreeToggle,
+
+    ToggleIgnoredFiles,
+    HidePattern {
+        pattern: String,
+    },
+    UnhidePattern {
+        pattern: String,
+    },
+
+    FollowLink,
+    EditLink {
+        target: Option<path::PathBuf>,
+    },
+    LinkMakeRelative,
+    LinkMakeAbsolute,
+    FindBrokenLinks,
+
+    TagToggle {
+        tag: Option<char>,
+    },
+    SelectTag {
+        tag: TagMatch,
+        options: SelectOption,
+    },
+    FilterTag {
+        tag: Option<TagMatch>,
+    },
+
+    CloseVirtual,
+
+    Find {
+        pattern: String,
+        pattern_type: SetType,
+        filters: FindFilters,
+    },
+    FindCancel,
+
+    Grep {
+        pattern: String,
+    },
 }
@@ -3,6 +3,7 @@ use std::io;
 use std::path;
 use std::sync::mpsc;
 use std::thread;
+use std::time;
 
 use ratatui::termion::event::Event;
 use ratatui_image::protocol::Protocol;
@@ -10,8 +11,11 @@ use ratatui_image::protocol::Protocol;
 use uuid::Uuid;
 
 use crate::error::AppResult;
-use crate::fs::JoshutoDirList;
+use crate::fs::{DiskUsageNode, GrepMatch, JoshutoDirEntry};
+use crate::git::GitRepoStatus;
+use crate::preview::preview_dir::DirLoadUpdate;
 use crate::preview::preview_file::FilePreview;
+use crate::types::event::fs_event_listener::FilesystemChanges;
 use crate::types::event::input_listener::TerminalInputListener;
 use crate::types::event::signal_listener::SignalListener;
 use crate::types::io::IoTaskProgressMessage;
@@ -57,17 +61,61 @@ pub enum AppEvent {
     // preview thread events
     PreviewDir {
         id: Uuid,
+        load_id: Uuid,
         path: path::PathBuf,
-        res: Box<io::Result<JoshutoDirList>>,
+        update: DirLoadUpdate,
     },
     PreviewFile {
         path: path::PathBuf,
         res: io::Result<PreviewData>,
     },
+    // git status thread events
+    GitStatus {
+        dir: path::PathBuf,
+        res: Box<Option<GitRepoStatus>>,
+    },
+    // cumulative size thread events
+    CumulativeSizes(Vec<(path::PathBuf, time::SystemTime, u64)>),
+    CumulativeSizeDone(String),
+    // find thread events
+    FindResults {
+        id: Uuid,
+        entries: Vec<JoshutoDirEntry>,
+    },
+    FindDone {
+        id: Uuid,
+    },
+    // custom linemode column thread events
+    CustomColumn {
+        name: String,
+        values: Vec<(path::PathBuf, time::SystemTime, String)>,
+    },
+    // MIME type detection thread events
+    Mimetypes(Vec<(path::PathBuf, time::SystemTime, Option<String>)>),
+    // disk usage scan thread events
+    DiskUsageProgress {
+        id: Uuid,
+        files: u64,
+    },
+    DiskUsageScanned {
+        id: Uuid,
+        tree: Box<DiskUsageNode>,
+    },
+    // grep thread events
+    GrepResults {
+        id: Uuid,
+        matches: Vec<GrepMatch>,
+        /// number of files searched so far
+        files: u64,
+    },
+    GrepDone {
+        id: Uuid,
+        files: u64,
+    },
     // terminal size change events
     Signal(i32),
     // filesystem change events
-    Filesystem(notify::Event),
+    Filesystem(FilesystemChanges),
 }
 
 /// A small event handler that wrap termion input and tick events. Each event
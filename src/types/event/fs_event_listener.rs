@@ -0,0 +1,139 @@
+use std::collections::HashSet;
+use std::path;
+use std::sync::mpsc;
+use std::time::{Duration, Instant};
+
+use notify::EventKind;
+
+use crate::types::event::{AppEvent, AppEventSender};
+
+/// How long the watcher has to be quiet before a burst of changes is delivered.
+const QUIET_PERIOD: Duration = Duration::from_millis(50);
+/// Upper bound on how long changes are held back while the watcher keeps firing.
+const MAX_DELAY: Duration = Duration::from_millis(500);
+
+/// Filesystem changes reported by the watcher during one debounce window, coalesced by path.
+#[derive(Clone, Debug, Default)]
+pub struct FilesystemChanges {
+    /// Entries that were created, modified, renamed or removed. Whether each still exists is
+    /// checked when the changes are applied, so the order of events within a burst doesn't
+    /// matter.
+    pub entries: HashSet<path::PathBuf>,
+    /// Directories whose contents can't be derived from the events and must be re-read.
+    pub rescan: HashSet<path::PathBuf>,
+}
+
+impl FilesystemChanges {
+    /// Folds a watcher event into this set of changes.
+    pub fn add(&mut self, event: notify::Event) {
+        if event.need_rescan() {
+            self.rescan.extend(event.paths);
+            return;
+        }
+        match event.kind {
+            EventKind::Access(_) => {}
+            EventKind::Create(_) | EventKind::Modify(_) | EventKind::Remove(_) => {
+                self.entries.extend(event.paths)
+            }
+            EventKind::Any | EventKind::Other => self.rescan.extend(event.paths),
+        }
+    }
+
+    /// Returns `true` if there's nothing to apply.
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty() && self.rescan.is_empty()
+    }
+}
+
+/// Debounces raw watcher events and forwards them as coalesced [`AppEvent::Filesystem`]s.
+#[derive(Debug)]
+pub struct FilesystemListener {
+    pub event_tx: AppEventSender,
+    watcher_rx: mpsc::Receiver<notify::Event>,
+}
+
+impl FilesystemListener {
+    /// Builds a listener that reads watcher events from `watcher_rx` and sends the coalesced
+    /// changes to `event_tx`.
+    pub fn new(event_tx: AppEventSender, watcher_rx: mpsc::Receiver<notify::Event>) -> Self {
+        Self {
+            event_tx,
+            watcher_rx,
+        }
+    }
+
+    /// Runs the listener loop until either channel closes. Intended to be run on its own
+    /// thread.
+    pub fn run(self) {
+        while let Ok(event) = self.watcher_rx.recv() {
+            let mut changes = FilesystemChanges::default();
+            changes.add(event);
+
+            let start = Instant::now();
+            let mut disconnected = false;
+            loop {
+                let remaining = MAX_DELAY.saturating_sub(start.elapsed());
+                if remaining.is_zero() {
+                    break;
+                }
+                match self.watcher_rx.recv_timeout(QUIET_PERIOD.min(remaining)) {
+                    Ok(event) => changes.add(event),
+                    Err(mpsc::RecvTimeoutError::Timeout) => break,
+                    Err(mpsc::RecvTimeoutError::Disconnected) => {
+                        disconnected = true;
+                        break;
+                    }
+                }
+            }
+
+            if !changes.is_empty() && self.event_tx.send(AppEvent::Filesystem(changes)).is_err() {
+                return;
+            }
+            if disconnected {
+                return;
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::PathBuf;
+
+    use notify::event::{AccessKind, CreateKind, Flag, ModifyKind, RemoveKind, RenameMode};
+    use notify::{Event, EventKind};
+
+    use super::FilesystemChanges;
+
+    #[test]
+    fn events_are_coalesced_by_path() {
+        let mut changes = FilesystemChanges::default();
+        changes.add(Event::new(EventKind::Create(CreateKind::File)).add_path("/d/a".into()));
+        changes.add(Event::new(EventKind::Remove(RemoveKind::File)).add_path("/d/a".into()));
+        changes.add(
+            Event::new(EventKind::Modify(ModifyKind::Name(RenameMode::Both)))
+                .add_path("/d/b".into())
+                .add_path("/d/c".into()),
+        );
+        changes.add(Event::new(EventKind::Access(AccessKind::Any)).add_path("/d/e".into()));
+
+        let mut entries: Vec<_> = changes.entries.into_iter().collect();
+        entries.sort();
+        let expected: Vec<PathBuf> = vec!["/d/a".into(), "/d/b".into(), "/d/c".into()];
+        assert_eq!(entries, expected);
+        assert!(changes.rescan.is_empty());
+    }
+
+    #[test]
+    fn rescans_are_kept_apart() {
+        let mut changes = FilesystemChanges::default();
+        changes.add(
+            Event::new(EventKind::Any)
+                .add_path("/d".into())
+                .set_flag(Flag::Rescan),
+        );
+        changes.add(Event::new(EventKind::Other).add_path("/e".into()));
+        assert!(changes.entries.is_empty());
+        assert_eq!(changes.rescan.len(), 2);
+    }
+}
@@ -2,7 +2,9 @@
 //! all funneled into a single [`AppEvent`] channel.
 
 mod app_event_listener;
+mod fs_event_listener;
 mod input_listener;
 mod signal_listener;
 
 pub use self::app_event_listener::*;
+pub use self::fs_event_listener::*;
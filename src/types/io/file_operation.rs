@@ -1,5 +1,7 @@
 use std::path;
 
+use super::{ModeChangeOptions, TimeChangeOptions};
+
 /// The kind of background file operation being performed.
 #[derive(Clone, Copy, Debug)]
 pub enum FileOperation {
@@ -7,6 +9,10 @@ pub enum FileOperation {
     Copy,
     Delete,
     Symlink,
+    SetMode,
+    SetTime,
+    Extract,
+    Compress,
 }
 
 impl FileOperation {
@@ -17,6 +23,10 @@ impl FileOperation {
             Self::Copy => "Copy",
             Self::Delete => "Delete",
             Self::Symlink => "Symlink",
+            Self::SetMode => "Set mode",
+            Self::SetTime => "Set time",
+            Self::Extract => "Extract",
+            Self::Compress => "Compress",
         }
     }
 
@@ -28,6 +38,10 @@ impl FileOperation {
             Self::Copy => "Copying",
             Self::Delete => "Deleting",
             Self::Symlink => "Symlinking",
+            Self::SetMode => "Setting mode of",
+            Self::SetTime => "Setting time of",
+            Self::Extract => "Extracting",
+            Self::Compress => "Compressing",
         }
     }
     /// Returns the operation's name in past-tense form (e.g. `"moved"`), for completion messages.
@@ -37,6 +51,10 @@ impl FileOperation {
             Self::Copy => "copied",
             Self::Delete => "deleted",
             Self::Symlink => "symlinked",
+            Self::SetMode => "changed mode of",
+            Self::SetTime => "changed time of",
+            Self::Extract => "extracted",
+            Self::Compress => "compressed",
         }
     }
 }
@@ -54,12 +72,21 @@ pub struct FileOperationOptions {
     pub _symlink: bool,
     pub symlink_relative: bool,
 
-    // cut, copy
+    // cut, copy, extract, compress
     pub overwrite: bool,
     pub skip_exist: bool,
 
     // delete
     pub permanently: bool,
+
+    // set mode
+    pub mode_change: ModeChangeOptions,
+
+    // set time
+    pub time_change: TimeChangeOptions,
+
+    // extract
+    pub subdir: bool,
 }
 
 impl std::fmt::Display for FileOperationOptions {
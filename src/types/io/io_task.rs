@@ -62,6 +62,9 @@ impl IoTaskStat {
             IoTaskProgressMessage::FileStart { file_path } => {
                 self.progress.current_file = file_path;
             }
+            IoTaskProgressMessage::BytesProcessed { bytes } => {
+                self.progress.bytes_processed += bytes;
+            }
             IoTaskProgressMessage::FileComplete { file_size } => {
                 self.progress.bytes_processed += file_size;
                 self.progress.files_processed += 1;
@@ -90,8 +93,16 @@ impl IoTaskStat {
 /// A progress update sent from a background file-operation worker thread.
 #[derive(Clone, Debug)]
 pub enum IoTaskProgressMessage {
-    FileStart { file_path: path::PathBuf },
-    FileComplete { file_size: u64 },
+    FileStart {
+        file_path: path::PathBuf,
+    },
+    /// part of the current file was processed
+    BytesProcessed {
+        bytes: u64,
+    },
+    FileComplete {
+        file_size: u64,
+    },
 }
 
 /// Formats a human-readable progress message like `"Copying (3/10) (1.2MB/5MB) completed"`.
@@ -1,7 +1,12 @@
-//! Background file-operation (cut/copy/delete/symlink) task definitions and progress tracking.
+//! Background file-operation (cut/copy/delete/symlink/set mode/set time/extract/compress) task
+//! definitions and progress tracking.
 
 mod file_operation;
 mod io_task;
+mod mode_change;
+mod time_change;
 
 pub use file_operation::*;
 pub use io_task::*;
+pub use mode_change::*;
+pub use time_change::*;
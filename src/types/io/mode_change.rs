@@ -0,0 +1,293 @@
+use std::str::FromStr;
+
+use nix::sys::stat::{mode_t, Mode};
+
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::utils::unix::UNIX_PERMISSION_VALS;
+
+const ALL_BITS: mode_t = 0o7777;
+const EXECUTE_BITS: mode_t = 0o111;
+
+const WHO_USER: mode_t = 0o4700;
+const WHO_GROUP: mode_t = 0o2070;
+const WHO_OTHER: mode_t = 0o1007;
+
+/// A permission change parsed from `chmod`-style syntax: octal (`755`), symbolic
+/// (`u+x,go-w`, `a+X`, `u+s`, `+t`) or a 9-character `rwxr-xr-x` string.
+///
+/// Symbolic clauses are folded left to right into a set of bits to clear, bits to set, and
+/// `X` bits that are only set on directories or entries that end up executable by someone.
+/// Unlike `chmod(1)`, an omitted `who` means `a` and the umask is not consulted.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct ModeChange {
+    clear: mode_t,
+    set: mode_t,
+    conditional_exec: mode_t,
+}
+
+impl ModeChange {
+    /// Builds a change that replaces all permission bits (including setuid, setgid and sticky)
+    /// with `mode`.
+    pub fn absolute(mode: mode_t) -> Self {
+        Self {
+            clear: ALL_BITS,
+            set: mode & ALL_BITS,
+            conditional_exec: 0,
+        }
+    }
+
+    /// Returns the mode an entry with permissions `mode` ends up with after this change.
+    pub fn apply(&self, mode: Mode, is_dir: bool) -> Mode {
+        let mut bits = (mode.bits() & !self.clear) | self.set;
+        if is_dir || bits & EXECUTE_BITS != 0 {
+            bits |= self.conditional_exec;
+        }
+        Mode::from_bits_truncate(bits)
+    }
+
+    fn add(&mut self, bits: mode_t) {
+        self.set |= bits;
+        self.clear &= !bits;
+    }
+
+    fn remove(&mut self, bits: mode_t) {
+        self.clear |= bits;
+        self.set &= !bits;
+        self.conditional_exec &= !bits;
+    }
+
+    fn assign(&mut self, who: mode_t, bits: mode_t) {
+        self.remove(who);
+        self.add(bits);
+    }
+
+    fn parse_octal(s: &str) -> Option<Self> {
+        if s.is_empty() || s.len() > 4 || !s.chars().all(|c| c.is_digit(8)) {
+            return None;
+        }
+        mode_t::from_str_radix(s, 8).ok().map(Self::absolute)
+    }
+
+    fn parse_rwx(s: &str) -> Option<Self> {
+        if s.chars().count() != UNIX_PERMISSION_VALS.len()
+            || !s.chars().all(|c| matches!(c, 'r' | 'w' | 'x' | '-'))
+        {
+            return None;
+        }
+        let mut set = 0;
+        for (ch, (val, expected)) in s.chars().zip(UNIX_PERMISSION_VALS.iter()) {
+            if ch == *expected {
+                set |= val.bits();
+            }
+        }
+        // special bits aren't shown in the rwx string, so leave them untouched
+        Some(Self {
+            clear: 0o777,
+            set,
+            conditional_exec: 0,
+        })
+    }
+
+    fn parse_symbolic(s: &str) -> AppResult<Self> {
+        let mut change = Self::default();
+        for clause in s.split(',') {
+            let mut chars = clause.chars().peekable();
+
+            let mut who = 0;
+            while let Some(c) = chars.peek() {
+                who |= match c {
+                    'u' => WHO_USER,
+                    'g' => WHO_GROUP,
+                    'o' => WHO_OTHER,
+                    'a' => ALL_BITS,
+                    _ => break,
+                };
+                chars.next();
+            }
+            if who == 0 {
+                who = ALL_BITS;
+            }
+
+            let mut op = match chars.next() {
+                Some(c @ ('+' | '-' | '=')) => c,
+                _ => return Err(invalid_mode(s)),
+            };
+            loop {
+                let mut bits = 0;
+                let mut conditional_exec = 0;
+                let mut next_op = None;
+                for c in chars.by_ref() {
+                    match c {
+                        'r' => bits |= 0o444,
+                        'w' => bits |= 0o222,
+                        'x' => bits |= EXECUTE_BITS,
+                        'X' => conditional_exec |= EXECUTE_BITS,
+                        's' => bits |= 0o6000,
+                        't' => bits |= 0o1000,
+                        '+' | '-' | '=' => {
+                            next_op = Some(c);
+                            break;
+                        }
+                        _ => return Err(invalid_mode(s)),
+                    }
+                }
+                let bits = bits & who;
+                let conditional_exec = conditional_exec & who;
+                match op {
+                    '+' => {
+                        change.add(bits);
+                        change.conditional_exec |= conditional_exec;
+                    }
+                    '-' => change.remove(bits | conditional_exec),
+                    _ => {
+                        change.assign(who, bits);
+                        change.conditional_exec |= conditional_exec;
+                    }
+                }
+                match next_op {
+                    Some(c) => op = c,
+                    None => break,
+                }
+            }
+        }
+        Ok(change)
+    }
+}
+
+impl FromStr for ModeChange {
+    type Err = AppError;
+
+    fn from_str(s: &str) -> AppResult<Self> {
+        if let Some(change) = Self::parse_octal(s) {
+            return Ok(change);
+        }
+        if let Some(change) = Self::parse_rwx(s) {
+            return Ok(change);
+        }
+        Self::parse_symbolic(s)
+    }
+}
+
+fn invalid_mode(s: &str) -> AppError {
+    AppError::new(
+        AppErrorKind::InvalidParameters,
+        format!("Invalid mode '{s}'"),
+    )
+}
+
+/// Arguments of a `set_mode` command: the change to apply to files and to directories, and
+/// whether to descend into selected directories.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct ModeChangeOptions {
+    pub file: Option<ModeChange>,
+    pub dir: Option<ModeChange>,
+    pub recursive: bool,
+}
+
+impl ModeChangeOptions {
+    /// Returns `true` if no mode was given, meaning the user should be prompted for one.
+    pub fn is_empty(&self) -> bool {
+        self.file.is_none() && self.dir.is_none()
+    }
+
+    /// Returns the change to apply to an entry, depending on whether it is a directory.
+    pub fn change_for(&self, is_dir: bool) -> Option<ModeChange> {
+        if is_dir {
+            self.dir
+        } else {
+            self.file
+        }
+    }
+
+    /// Parses `set_mode` arguments on top of `self`: a mode applying to both files and
+    /// directories, `--files=MODE`, `--dirs=MODE` and `--recursive[=true|false]`.
+    pub fn parse_args(mut self, args: &str) -> AppResult<Self> {
+        let mut mode = None;
+        for arg in args.split_whitespace() {
+            match arg {
+                "--recursive" | "--recursive=true" => self.recursive = true,
+                "--recursive=false" => self.recursive = false,
+                arg => {
+                    if let Some(s) = arg.strip_prefix("--files=") {
+                        self.file = Some(ModeChange::from_str(s)?);
+                    } else if let Some(s) = arg.strip_prefix("--dirs=") {
+                        self.dir = Some(ModeChange::from_str(s)?);
+                    } else if arg.starts_with("--") {
+                        return Err(AppError::new(
+                            AppErrorKind::UnrecognizedArgument,
+                            format!("set_mode: unknown option '{arg}'"),
+                        ));
+                    } else {
+                        mode = Some(ModeChange::from_str(arg)?);
+                    }
+                }
+            }
+        }
+        if mode.is_some() {
+            self.file = self.file.or(mode);
+            self.dir = self.dir.or(mode);
+        }
+        Ok(self)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+
+    use nix::sys::stat::Mode;
+
+    use super::ModeChange;
+
+    fn apply(change: &str, mode: u32, is_dir: bool) -> u32 {
+        ModeChange::from_str(change)
+            .unwrap()
+            .apply(Mode::from_bits_truncate(mode), is_dir)
+            .bits()
+    }
+
+    #[test]
+    fn octal_replaces_all_bits() {
+        assert_eq!(apply("755", 0o4644, false), 0o755);
+        assert_eq!(apply("1777", 0o755, true), 0o1777);
+    }
+
+    #[test]
+    fn rwx_string_keeps_special_bits() {
+        assert_eq!(apply("rwxr-x---", 0o2644, true), 0o2750);
+    }
+
+    #[test]
+    fn symbolic_clauses_apply_in_order() {
+        assert_eq!(apply("u+x,go-w", 0o666, false), 0o744);
+        assert_eq!(apply("a=r,u+w", 0o777, false), 0o644);
+        assert_eq!(apply("u=rwx,g=rx,o=", 0o000, false), 0o750);
+        assert_eq!(apply("+x-w", 0o666, false), 0o555);
+    }
+
+    #[test]
+    fn conditional_execute_only_for_dirs_or_executables() {
+        assert_eq!(apply("a+X", 0o644, false), 0o644);
+        assert_eq!(apply("a+X", 0o744, false), 0o755);
+        assert_eq!(apply("a+X", 0o700, true), 0o711);
+        assert_eq!(apply("a+X,a-x", 0o700, true), 0o600);
+    }
+
+    #[test]
+    fn special_bits_follow_who() {
+        assert_eq!(apply("u+s", 0o755, false), 0o4755);
+        assert_eq!(apply("g+s", 0o755, true), 0o2755);
+        assert_eq!(apply("o+s", 0o755, false), 0o755);
+        assert_eq!(apply("+t", 0o777, true), 0o1777);
+        assert_eq!(apply("a-st", 0o7777, true), 0o777);
+    }
+
+    #[test]
+    fn invalid_modes_are_rejected() {
+        assert!(ModeChange::from_str("").is_err());
+        assert!(ModeChange::from_str("u").is_err());
+        assert!(ModeChange::from_str("u+q").is_err());
+        assert!(ModeChange::from_str("89").is_err());
+        assert!(ModeChange::from_str("u+x,").is_err());
+    }
+}
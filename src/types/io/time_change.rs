@@ -0,0 +1,223 @@
+use std::str::FromStr;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use std::{fs, io, path};
+
+use chrono::{DateTime, Local, NaiveDate, NaiveDateTime, TimeZone};
+use nix::fcntl::AT_FDCWD;
+use nix::sys::stat::{utimensat, UtimensatFlags};
+use nix::sys::time::TimeSpec;
+
+use crate::error::{AppError, AppErrorKind, AppResult};
+
+const DATETIME_FORMATS: [&str; 4] = [
+    "%Y-%m-%d %H:%M:%S",
+    "%Y-%m-%d %H:%M",
+    "%Y-%m-%dT%H:%M:%S",
+    "%Y-%m-%dT%H:%M",
+];
+
+/// How `set_time` computes an entry's new access or modification time.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum TimeChange {
+    /// Set the time to this point in time.
+    Absolute(SystemTime),
+    /// Shift the entry's current time by this many seconds.
+    Shift(i64),
+}
+
+impl TimeChange {
+    /// Returns the time an entry whose current time is `time` ends up with. Fails with
+    /// `InvalidInput` if a shift takes it out of the range of `SystemTime`.
+    pub fn apply(&self, time: SystemTime) -> io::Result<SystemTime> {
+        let shifted = match *self {
+            Self::Absolute(t) => return Ok(t),
+            Self::Shift(secs) if secs < 0 => {
+                time.checked_sub(Duration::from_secs(secs.unsigned_abs()))
+            }
+            Self::Shift(secs) => time.checked_add(Duration::from_secs(secs as u64)),
+        };
+        shifted.ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "time out of range"))
+    }
+
+    fn parse_shift(s: &str) -> Option<i64> {
+        let (sign, mut rest) = match s.as_bytes().first()? {
+            b'+' => (1, &s[1..]),
+            b'-' => (-1, &s[1..]),
+            _ => return None,
+        };
+        if rest.is_empty() {
+            return None;
+        }
+
+        let mut total: i64 = 0;
+        while !rest.is_empty() {
+            let digits = rest.find(|c: char| !c.is_ascii_digit())?;
+            if digits == 0 {
+                return None;
+            }
+            let value: i64 = rest[..digits].parse().ok()?;
+            let unit = match rest[digits..].chars().next()? {
+                's' => 1,
+                'm' => 60,
+                'h' => 60 * 60,
+                'd' => 24 * 60 * 60,
+                'w' => 7 * 24 * 60 * 60,
+                _ => return None,
+            };
+            total = total.checked_add(value.checked_mul(unit)?)?;
+            rest = &rest[digits + 1..];
+        }
+        Some(sign * total)
+    }
+
+    fn parse_datetime(s: &str) -> Option<DateTime<Local>> {
+        let naive = DATETIME_FORMATS
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+            .or_else(|| {
+                NaiveDate::parse_from_str(s, "%Y-%m-%d")
+                    .ok()
+                    .and_then(|date| date.and_hms_opt(0, 0, 0))
+            })?;
+        Local.from_local_datetime(&naive).earliest()
+    }
+}
+
+impl FromStr for TimeChange {
+    type Err = AppError;
+
+    /// Parses `now`, a local datetime (`2024-01-31 12:00[:00]`, `2024-01-31T12:00`,
+    /// `2024-01-31`), a unix timestamp (`@1706702400`) or a relative offset (`-2h`, `+1d12h`).
+    fn from_str(s: &str) -> AppResult<Self> {
+        if s == "now" {
+            return Ok(Self::Absolute(SystemTime::now()));
+        }
+        if let Some(secs) = Self::parse_shift(s) {
+            return Ok(Self::Shift(secs));
+        }
+        if let Some(timestamp) = s.strip_prefix('@').and_then(|t| t.parse::<i64>().ok()) {
+            if let Some(datetime) = DateTime::from_timestamp(timestamp, 0) {
+                return Ok(Self::Absolute(datetime.into()));
+            }
+        }
+        match Self::parse_datetime(s) {
+            Some(datetime) => Ok(Self::Absolute(datetime.into())),
+            None => Err(AppError::new(
+                AppErrorKind::Parse,
+                format!("Invalid time '{s}'"),
+            )),
+        }
+    }
+}
+
+/// The access and modification time changes of a `set_time` command, and whether to descend
+/// into selected directories.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct TimeChangeOptions {
+    pub accessed: Option<TimeChange>,
+    pub modified: Option<TimeChange>,
+    pub recursive: bool,
+}
+
+impl TimeChangeOptions {
+    /// Applies the access and modification time changes to `path` itself, without following
+    /// symlinks.
+    pub fn apply_to(&self, path: &path::Path) -> io::Result<()> {
+        let metadata = fs::symlink_metadata(path)?;
+        let accessed = match self.accessed {
+            Some(change) => to_timespec(change.apply(metadata.accessed()?)?),
+            None => TimeSpec::UTIME_OMIT,
+        };
+        let modified = match self.modified {
+            Some(change) => to_timespec(change.apply(metadata.modified()?)?),
+            None => TimeSpec::UTIME_OMIT,
+        };
+        utimensat(
+            AT_FDCWD,
+            path,
+            &accessed,
+            &modified,
+            UtimensatFlags::NoFollowSymlink,
+        )?;
+        Ok(())
+    }
+}
+
+fn to_timespec(time: SystemTime) -> TimeSpec {
+    // times before the epoch are clamped to it
+    TimeSpec::from_duration(time.duration_since(UNIX_EPOCH).unwrap_or_default())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::str::FromStr;
+    use std::time::{Duration, SystemTime};
+
+    use super::TimeChange;
+
+    #[test]
+    fn relative_offsets_are_parsed() {
+        assert_eq!(
+            TimeChange::from_str("-2h").unwrap(),
+            TimeChange::Shift(-7200)
+        );
+        assert_eq!(
+            TimeChange::from_str("+1d12h").unwrap(),
+            TimeChange::Shift(129600)
+        );
+        assert_eq!(TimeChange::from_str("+90s").unwrap(), TimeChange::Shift(90));
+        assert_eq!(
+            TimeChange::from_str("-1w").unwrap(),
+            TimeChange::Shift(-604800)
+        );
+    }
+
+    #[test]
+    fn malformed_offsets_are_rejected() {
+        assert!(TimeChange::from_str("-").is_err());
+        assert!(TimeChange::from_str("-h").is_err());
+        assert!(TimeChange::from_str("-2").is_err());
+        assert!(TimeChange::from_str("-2y").is_err());
+        assert!(TimeChange::from_str("yesterday").is_err());
+    }
+
+    #[test]
+    fn absolute_times_are_parsed() {
+        assert_eq!(
+            TimeChange::from_str("@86400").unwrap(),
+            TimeChange::Absolute(SystemTime::UNIX_EPOCH + Duration::from_secs(86400))
+        );
+        for s in [
+            "2024-01-31 12:00:00",
+            "2024-01-31 12:00",
+            "2024-01-31T12:00",
+            "2024-01-31",
+        ] {
+            assert!(matches!(
+                TimeChange::from_str(s),
+                Ok(TimeChange::Absolute(_))
+            ));
+        }
+    }
+
+    #[test]
+    fn shift_moves_time_both_ways() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000);
+        assert_eq!(
+            TimeChange::Shift(-7200).apply(time).unwrap(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(2_800)
+        );
+        assert_eq!(
+            TimeChange::Shift(60).apply(time).unwrap(),
+            SystemTime::UNIX_EPOCH + Duration::from_secs(10_060)
+        );
+    }
+
+    #[test]
+    fn shift_out_of_range_is_an_error() {
+        let time = SystemTime::UNIX_EPOCH + Duration::from_secs(10_000);
+        assert!(TimeChange::Shift(i64::MAX).apply(time).is_err());
+        let before_epoch = SystemTime::UNIX_EPOCH - Duration::from_secs(1);
+        assert!(TimeChange::Shift(i64::MIN).apply(before_epoch).is_err());
+    }
+}
@@ -0,0 +1,148 @@
+use std::path::Path;
+
+use globset::{GlobBuilder, GlobMatcher};
+use serde::{Deserialize, Serialize};
+
+use crate::config::dir_settings_raw::DirSettingsOptionRaw;
+use crate::utils::unix;
+
+/// View settings of a single directory. Unset fields leave the tab's settings unchanged.
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+pub struct DirSettings {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sort_method: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reverse: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub linemode: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub show_hidden: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub depth: Option<u8>,
+}
+
+impl DirSettings {
+    /// Returns `true` if no setting is set.
+    pub fn is_empty(&self) -> bool {
+        *self == Self::default()
+    }
+
+    /// Overrides the settings with those set in `other`.
+    pub fn merge(&mut self, other: &DirSettings) {
+        if other.sort_method.is_some() {
+            self.sort_method.clone_from(&other.sort_method);
+        }
+        if other.reverse.is_some() {
+            self.reverse = other.reverse;
+        }
+        if other.linemode.is_some() {
+            self.linemode.clone_from(&other.linemode);
+        }
+        if other.show_hidden.is_some() {
+            self.show_hidden = other.show_hidden;
+        }
+        if other.depth.is_some() {
+            self.depth = other.depth;
+        }
+    }
+}
+
+/// Settings applied to the directories matching a glob pattern.
+#[derive(Clone, Debug)]
+pub struct DirSettingsRule {
+    pub matcher: GlobMatcher,
+    pub settings: DirSettings,
+}
+
+/// Per-directory view settings: whether changes are remembered across sessions, and the
+/// configured rules.
+#[derive(Clone, Debug, Default)]
+pub struct DirSettingsOption {
+    pub remember: bool,
+    pub rules: Vec<DirSettingsRule>,
+}
+
+impl DirSettingsOption {
+    /// Returns the settings of all rules matching `path`, later rules taking precedence.
+    pub fn settings_for(&self, path: &Path) -> DirSettings {
+        let mut settings = DirSettings::default();
+        for rule in self.rules.iter().filter(|r| r.matcher.is_match(path)) {
+            settings.merge(&rule.settings);
+        }
+        settings
+    }
+}
+
+impl From<DirSettingsOptionRaw> for DirSettingsOption {
+    fn from(raw: DirSettingsOptionRaw) -> Self {
+        // rules with invalid patterns are skipped
+        let rules = raw
+            .rules
+            .into_iter()
+            .filter_map(|rule| {
+                let pattern = unix::expand_shell_string_cow(&rule.glob);
+                let pattern = pattern.trim_end_matches('/');
+                let glob = GlobBuilder::new(pattern)
+                    .literal_separator(true)
+                    .build()
+                    .ok()?;
+                Some(DirSettingsRule {
+                    matcher: glob.compile_matcher(),
+                    settings: rule.settings,
+                })
+            })
+            .collect();
+        Self {
+            remember: raw.remember,
+            rules,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::dir_settings_raw::DirSettingsRuleRaw;
+
+    #[test]
+    fn test_settings_for() {
+        let rule = |glob: &str, settings: DirSettings| DirSettingsRuleRaw {
+            glob: glob.to_string(),
+            settings,
+        };
+        let option = DirSettingsOption::from(DirSettingsOptionRaw {
+            remember: false,
+            rules: vec![
+                rule(
+                    "/home/user/Downloads/",
+                    DirSettings {
+                        sort_method: Some("mtime".to_string()),
+                        reverse: Some(true),
+                        ..Default::default()
+                    },
+                ),
+                rule(
+                    "/home/user/*",
+                    DirSettings {
+                        reverse: Some(false),
+                        show_hidden: Some(true),
+                        ..Default::default()
+                    },
+                ),
+            ],
+        });
+
+        assert_eq!(
+            option.settings_for(Path::new("/home/user/Downloads")),
+            DirSettings {
+                sort_method: Some("mtime".to_string()),
+                reverse: Some(false),
+                show_hidden: Some(true),
+                ..Default::default()
+            }
+        );
+        assert!(option
+            .settings_for(Path::new("/home/user/Downloads/music"))
+            .is_empty());
+    }
+}
@@ -1,4 +1,5 @@
-use std::convert::From;
+use std::convert::TryFrom;
+use std::path::Path;
 
 use ratatui::layout::Constraint;
 
@@ -6,15 +7,29 @@ use crate::{
     config::display_raw::DisplayOptionRaw, fs::DirListDisplayOptions, tab::TabDisplayOption,
 };
 
-use super::line_mode::LineNumberStyle;
+use crate::error::{AppError, AppErrorKind, AppResult};
+use crate::utils::serde::default_fs_usage_warning;
+use crate::TAGS_T;
 
-/// The overall UI layout: the standard multi-pane view, a minimal single-pane view, or a
-/// horizontally-split view.
+use super::hidden::HiddenPattern;
+use super::line_mode::{CustomColumn, LineNumberStyle};
+use super::sort::SortMethod;
+
+/// The overall UI layout: the standard multi-pane view, a minimal single-pane view, a
+/// horizontally-split view, or the standard view with directories expandable in place.
 #[derive(Clone, Copy, Debug)]
 pub enum DisplayMode {
     Default,
     Minimal,
     HSplit,
+    Tree,
+}
+
+impl DisplayMode {
+    /// Returns `true` for the `tree` mode, where listings include expanded subdirectories.
+    pub fn is_tree(&self) -> bool {
+        matches!(self, DisplayMode::Tree)
+    }
 }
 
 /// Returns the default parent/current/preview column width ratio.
@@ -31,22 +46,46 @@ pub struct DisplayOption {
     pub scroll_offset: usize,
     pub show_borders: bool,
     pub show_hostname: bool,
+    pub show_fs_info: bool,
+    /// whether to run `git status` for the directories shown
+    pub show_git_status: bool,
+    /// usage percentage of the current filesystem from which its free space is highlighted
+    pub fs_usage_warning: u8,
     pub show_hidden: bool,
+    /// names hidden along with dotfiles
+    pub hidden_patterns: Vec<HiddenPattern>,
     pub show_icons: bool,
     pub line_number_style: LineNumberStyle,
     pub default_layout: [Constraint; 3],
     pub no_preview_layout: [Constraint; 3],
     pub default_tab_display_option: TabDisplayOption,
+    pub custom_columns: Vec<CustomColumn>,
 }
 
-impl From<DisplayOptionRaw> for DisplayOption {
-    fn from(raw: DisplayOptionRaw) -> Self {
+impl TryFrom<DisplayOptionRaw> for DisplayOption {
+    type Error = AppError;
+
+    fn try_from(raw: DisplayOptionRaw) -> AppResult<Self> {
         let mode = match raw.mode.as_str() {
             "hsplit" => DisplayMode::HSplit,
             "minimal" => DisplayMode::Minimal,
+            "tree" => DisplayMode::Tree,
             _ => DisplayMode::Default,
         };
 
+        let hidden_patterns = raw
+            .hidden_patterns
+            .iter()
+            .map(|pattern| {
+                HiddenPattern::new(pattern).map_err(|e| {
+                    AppError::new(
+                        AppErrorKind::Config,
+                        format!("invalid hidden pattern '{pattern}': {e}"),
+                    )
+                })
+            })
+            .collect::<AppResult<_>>()?;
+
         let (left, mid, right) = match raw.column_ratio {
             Some(s) if s.len() == 3 => (s[0], s[1], s[2]),
             Some(s) if s.len() == 2 => (0, s[0], s[1]),
@@ -66,14 +105,18 @@ impl From<DisplayOptionRaw> for DisplayOption {
             Constraint::Ratio(0, total),
         ];
 
-        Self {
+        let options = Self {
             mode,
             automatically_count_files: raw.automatically_count_files,
             collapse_preview: raw.collapse_preview,
             scroll_offset: raw.scroll_offset,
             show_borders: raw.show_borders,
             show_hostname: raw.show_hostname,
+            show_fs_info: raw.show_fs_info,
+            show_git_status: raw.show_git_status,
+            fs_usage_warning: raw.fs_usage_warning,
             show_hidden: raw.show_hidden,
+            hidden_patterns,
             show_icons: raw.show_icons,
             line_number_style: raw.line_number_style,
 
@@ -83,15 +126,75 @@ impl From<DisplayOptionRaw> for DisplayOption {
                 sort_options: raw.sort_options.into(),
                 // todo: make default line mode configurable
                 linemode: raw.linemode,
+                show_ignored: raw.show_ignored,
                 ..Default::default()
             },
+            custom_columns: raw.custom_columns,
+        };
+        let tab_options = &options.default_tab_display_option;
+        if let Some(SortMethod::Custom(name)) =
+            tab_options.sort_options_ref().sort_methods.list.front()
+        {
+            options.check_custom_column(name)?;
+        }
+        for name in tab_options.linemode.custom_columns() {
+            options.check_custom_column(name)?;
         }
+        Ok(options)
     }
 }
 
 impl DisplayOption {
+    /// Returns an error if `name`, taken for a custom column because it isn't a built-in sort
+    /// method or linemode, isn't configured as one.
+    pub fn check_custom_column(&self, name: &str) -> AppResult {
+        match self.custom_column(name) {
+            Some(_) => Ok(()),
+            None => Err(AppError::new(
+                AppErrorKind::Config,
+                format!("'{name}' is neither a built-in option nor a custom column"),
+            )),
+        }
+    }
+
+    /// Returns the custom linemode column called `name`, if configured.
+    pub fn custom_column(&self, name: &str) -> Option<&CustomColumn> {
+        self.custom_columns
+            .iter()
+            .find(|column| column.name == name)
+    }
+
+    /// Returns `true` if an entry named `file_name` is a dotfile or matches a hidden pattern.
+    pub fn is_hidden(&self, file_name: &str) -> bool {
+        file_name.starts_with('.') || self.hidden_patterns.iter().any(|p| p.is_match(file_name))
+    }
+
+    /// Returns `true` if an entry named `file_name` passes the hidden-file setting and the
+    /// listing's filter.
+    pub fn is_shown(&self, file_name: &str, dirlist_opts: &DirListDisplayOptions) -> bool {
+        if !self.show_hidden && self.is_hidden(file_name) {
+            return false;
+        }
+        dirlist_opts.filter_state_ref().is_match(file_name)
+    }
+
+    /// Returns `true` if the entry at `path`, named `file_name`, passes the hidden-file setting,
+    /// the listing's filter and its tag filter.
+    pub fn is_listed(
+        &self,
+        path: &Path,
+        file_name: &str,
+        dirlist_opts: &DirListDisplayOptions,
+    ) -> bool {
+        self.is_shown(file_name, dirlist_opts)
+            && dirlist_opts.tag_filter().is_none_or(|tag_filter| {
+                let tags = TAGS_T.lock();
+                tag_filter.is_match(tags.ok().and_then(|tags| tags.get(path)))
+            })
+    }
+
     /// Returns the function used to decide whether a directory entry should be shown, based on
-    /// hidden-file and filter/search settings.
+    /// hidden-file, filter/search and tag filter settings.
     pub fn filter_func(
         &self,
     ) -> fn(&walkdir::DirEntry, &DisplayOption, &DirListDisplayOptions) -> bool {
@@ -122,41 +225,58 @@ impl std::default::Default for DisplayOption {
             scroll_offset: 4,
             show_borders: true,
             show_hostname: true,
+            show_fs_info: true,
+            show_git_status: true,
+            fs_usage_warning: default_fs_usage_warning(),
             show_hidden: false,
+            hidden_patterns: Vec::new(),
             show_icons: false,
             line_number_style: LineNumberStyle::None,
             default_layout,
             no_preview_layout,
             default_tab_display_option: TabDisplayOption::default(),
+            custom_columns: Vec::new(),
         }
     }
 }
 
-fn is_hidden(entry: &walkdir::DirEntry) -> bool {
-    entry
-        .file_name()
-        .to_str()
-        .map(|s| s.starts_with('.'))
-        .unwrap_or(false)
-}
-
 fn filter(
     entry: &walkdir::DirEntry,
     opt: &DisplayOption,
     dirlist_opts: &DirListDisplayOptions,
 ) -> bool {
-    if !opt.show_hidden && is_hidden(entry) {
-        return false;
+    match entry.file_name().to_str() {
+        Some(file_name) => opt.is_listed(entry.path(), file_name, dirlist_opts),
+        None => false,
     }
+}
 
-    let file_name = match entry.file_name().to_str() {
-        Some(s) => s,
-        None => return false,
-    };
+#[cfg(test)]
+mod tests {
+    use super::*;
 
-    if !dirlist_opts.filter_state_ref().is_match(file_name) {
-        return false;
+    fn parse(toml: &str) -> AppResult<DisplayOption> {
+        DisplayOption::try_from(toml::from_str::<DisplayOptionRaw>(toml)?)
     }
 
-    true
+    #[test]
+    fn test_unknown_custom_columns_are_errors() {
+        let columns = r#"
+            [[custom_columns]]
+            name = "lines"
+            command = "wc -l < %p"
+        "#;
+        assert!(parse(&format!("linemode = \"size | lines\"\n{columns}")).is_ok());
+        assert!(parse(&format!("[sort]\nsort_method = \"lines\"\n{columns}")).is_ok());
+        assert!(parse(&format!("linemode = \"size | lnies\"\n{columns}")).is_err());
+        assert!(parse("[sort]\nsort_method = \"lines\"").is_err());
+        assert!(parse("[sort]\nsort_method = \"mtime\"").is_ok());
+    }
+
+    #[test]
+    fn test_invalid_hidden_patterns_are_errors() {
+        assert!(parse(r#"hidden_patterns = ["*.pyc", "regex:.*~"]"#).is_ok());
+        assert!(parse(r#"hidden_patterns = ["regex:(unclosed"]"#).is_err());
+        assert!(parse(r#"hidden_patterns = ["[unclosed"]"#).is_err());
+    }
 }
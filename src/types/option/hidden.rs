@@ -0,0 +1,52 @@
+use crate::error::AppResult;
+use crate::types::option::search::CaseSensitivity;
+use crate::types::state::MatchState;
+
+const REGEX_PREFIX: &str = "regex:";
+
+/// A pattern of file names hidden like dotfiles: a glob, or a regex when prefixed with `regex:`.
+/// Either must match the whole name.
+#[derive(Clone, Debug)]
+pub struct HiddenPattern {
+    /// the pattern as written, prefix included
+    pub pattern: String,
+    matcher: MatchState,
+}
+
+impl HiddenPattern {
+    /// Compiles `pattern`, case-sensitively.
+    pub fn new(pattern: &str) -> AppResult<Self> {
+        let matcher = match pattern.strip_prefix(REGEX_PREFIX) {
+            Some(regex) => MatchState::new_regex(regex, CaseSensitivity::Sensitive)?,
+            None => MatchState::new_glob(pattern, CaseSensitivity::Sensitive)?,
+        };
+        Ok(Self {
+            pattern: pattern.to_string(),
+            matcher,
+        })
+    }
+
+    /// Returns `true` if the file named `file_name` is hidden by this pattern.
+    pub fn is_match(&self, file_name: &str) -> bool {
+        self.matcher.is_match(file_name)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_hidden_pattern() {
+        let glob = HiddenPattern::new("*.pyc").unwrap();
+        assert!(glob.is_match("main.pyc"));
+        assert!(!glob.is_match("main.PYC"));
+        assert!(!glob.is_match("main.py"));
+
+        let regex = HiddenPattern::new("regex:.*~").unwrap();
+        assert!(regex.is_match("notes.txt~"));
+        assert!(!regex.is_match("~notes.txt"));
+
+        assert!(HiddenPattern::new("regex:(").is_err());
+    }
+}
@@ -1,14 +1,62 @@
-/// A single metadata column that can be shown per file entry in a [`LineMode`](super::LineMode).
-#[derive(Default, Debug, PartialEq, Eq, Clone, Copy)]
+/// A single metadata (or git status) column that can be shown per file entry in a [`LineMode`](super::LineMode).
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub enum LineModeArgs {
     Size,
     ModifyTime,
     AccessTime,
+    ChangeTime,
+    BirthTime,
     User,
     Group,
     Permission,
-    #[default]
-    Null,
+    OctalPermission,
+    Inode,
+    Links,
+    ItemCount,
+    LinkTarget,
+    MimeType,
+    GitStatus,
+    /// A [`CustomColumn`](super::CustomColumn) from the config, by name.
+    Custom(String),
+}
+
+impl LineModeArgs {
+    /// Every built-in column, in the order `linemode all` shows them.
+    pub const ALL: [LineModeArgs; 15] = [
+        LineModeArgs::Size,
+        LineModeArgs::ModifyTime,
+        LineModeArgs::AccessTime,
+        LineModeArgs::ChangeTime,
+        LineModeArgs::BirthTime,
+        LineModeArgs::User,
+        LineModeArgs::Group,
+        LineModeArgs::Permission,
+        LineModeArgs::OctalPermission,
+        LineModeArgs::Inode,
+        LineModeArgs::Links,
+        LineModeArgs::ItemCount,
+        LineModeArgs::LinkTarget,
+        LineModeArgs::MimeType,
+        LineModeArgs::GitStatus,
+    ];
+
+    /// Parses a column from its name in a linemode spec. Names that aren't built-in refer to
+    /// custom columns.
+    pub fn from_name(name: &str) -> Option<Self> {
+        if name.is_empty() {
+            return None;
+        }
+        let builtin = Self::ALL.into_iter().find(|arg| arg.as_ref() == name);
+        Some(builtin.unwrap_or_else(|| Self::Custom(name.to_string())))
+    }
+
+    /// Returns `true` if the column holds numbers, which are right-aligned.
+    pub fn is_numeric(&self) -> bool {
+        matches!(
+            self,
+            Self::Size | Self::Inode | Self::Links | Self::ItemCount
+        )
+    }
 }
 
 impl AsRef<str> for LineModeArgs {
@@ -17,10 +65,19 @@ impl AsRef<str> for LineModeArgs {
             LineModeArgs::Size => "size",
             LineModeArgs::ModifyTime => "mtime",
             LineModeArgs::AccessTime => "atime",
+            LineModeArgs::ChangeTime => "ctime",
+            LineModeArgs::BirthTime => "btime",
             LineModeArgs::User => "user",
             LineModeArgs::Group => "group",
             LineModeArgs::Permission => "perm",
-            LineModeArgs::Null => unreachable!(),
+            LineModeArgs::OctalPermission => "octal",
+            LineModeArgs::Inode => "inode",
+            LineModeArgs::Links => "links",
+            LineModeArgs::ItemCount => "count",
+            LineModeArgs::LinkTarget => "target",
+            LineModeArgs::MimeType => "mime",
+            LineModeArgs::GitStatus => "git",
+            LineModeArgs::Custom(name) => name,
         }
     }
 }
@@ -0,0 +1,57 @@
+use std::path::Path;
+
+use serde::Deserialize;
+
+use crate::commands::sub_process::shell_quote;
+
+/// Which side of its column a value is aligned to.
+#[derive(Clone, Copy, Debug, Default, Deserialize, PartialEq, Eq)]
+pub enum ColumnAlignment {
+    #[default]
+    #[serde(rename = "left")]
+    Left,
+    #[serde(rename = "right")]
+    Right,
+}
+
+/// A user-defined linemode column, showing the first line printed by `command` for each entry.
+#[derive(Clone, Debug, Deserialize, PartialEq, Eq)]
+pub struct CustomColumn {
+    pub name: String,
+    /// Shell command run for each entry, with `%p` replaced by the entry's path.
+    pub command: String,
+    /// Fixed width of the column; sized to its content if not set.
+    #[serde(default)]
+    pub width: Option<usize>,
+    #[serde(default)]
+    pub align: ColumnAlignment,
+}
+
+impl CustomColumn {
+    /// Returns the shell command line that computes the column's value for `path`.
+    pub fn command_for(&self, path: &Path) -> String {
+        self.command
+            .replace("%p", &shell_quote(&path.to_string_lossy()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::{ColumnAlignment, CustomColumn};
+
+    #[test]
+    fn path_placeholder_is_shell_quoted() {
+        let column = CustomColumn {
+            name: "dims".to_string(),
+            command: "identify -format '%wx%h' %p".to_string(),
+            width: None,
+            align: ColumnAlignment::Right,
+        };
+        assert_eq!(
+            column.command_for(Path::new("/tmp/it's.png")),
+            "identify -format '%wx%h' '/tmp/it'\\''s.png'"
+        );
+    }
+}
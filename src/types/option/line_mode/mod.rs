@@ -1,62 +1,64 @@
-//! Which columns of metadata (size, times, owner, permissions) are shown per file entry.
+//! Which columns of metadata (size, times, owner, permissions, mimetype, git status, ...) are
+//! shown per file entry.
 
 mod args;
+mod custom_column;
 mod line_number;
 
 pub use args::*;
+pub use custom_column::*;
 pub use line_number::*;
 
 use crate::error::{AppError, AppErrorKind, AppResult};
 
-/// An ordered, deduplicated set of up to 7 [`LineModeArgs`] columns to display per entry.
-#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+/// An ordered, deduplicated list of [`LineModeArgs`] columns to display per entry.
+#[derive(Debug, PartialEq, Eq, Clone)]
 pub struct LineMode {
-    pub mode: [LineModeArgs; 7],
-    pub size: usize,
+    pub mode: Vec<LineModeArgs>,
 }
 
 impl LineMode {
-    /// Returns a `LineMode` showing every available column.
-    pub const fn all() -> Self {
-        Self {
-            mode: [
-                LineModeArgs::Size,
-                LineModeArgs::ModifyTime,
-                LineModeArgs::AccessTime,
-                LineModeArgs::User,
-                LineModeArgs::Group,
-                LineModeArgs::Permission,
-                LineModeArgs::Null,
-            ],
-            size: 7,
-        }
+    /// Returns a `LineMode` showing every available column except `mime`, which has to run
+    /// `file` on every entry.
+    pub fn all() -> Self {
+        let mode = LineModeArgs::ALL
+            .into_iter()
+            .filter(|arg| *arg != LineModeArgs::MimeType)
+            .collect();
+        Self { mode }
     }
 
     /// Returns a `LineMode` showing no columns.
     pub const fn empty() -> Self {
-        Self {
-            mode: [LineModeArgs::Null; 7],
-            size: 0,
-        }
+        Self { mode: Vec::new() }
     }
 
     /// Appends `mode` if it isn't already present.
     pub fn add_mode(&mut self, mode: LineModeArgs) {
-        if self.mode.contains(&mode) {
-            return;
+        if !self.contains(&mode) {
+            self.mode.push(mode);
         }
+    }
 
-        self.mode[self.size] = mode;
-        self.size += 1;
+    /// Returns `true` if the `mode` column is shown.
+    pub fn contains(&self, mode: &LineModeArgs) -> bool {
+        self.mode.contains(mode)
+    }
+
+    /// Returns the names of the custom columns shown.
+    pub fn custom_columns(&self) -> impl Iterator<Item = &str> {
+        self.mode.iter().filter_map(|arg| match arg {
+            LineModeArgs::Custom(name) => Some(name.as_str()),
+            _ => None,
+        })
     }
 }
 
 impl Default for LineMode {
     fn default() -> Self {
-        let mut mode = [Default::default(); 7];
-        mode[0] = LineModeArgs::Size;
-
-        Self { size: 1, mode }
+        Self {
+            mode: vec![LineModeArgs::Size],
+        }
     }
 }
 
@@ -71,17 +73,12 @@ impl LineMode {
                 let mut line_mode = LineMode::empty();
 
                 for mode in name.split('|').map(|mode| mode.trim()) {
-                    match mode {
-                        "size" => line_mode.add_mode(LineModeArgs::Size),
-                        "mtime" => line_mode.add_mode(LineModeArgs::ModifyTime),
-                        "atime" => line_mode.add_mode(LineModeArgs::AccessTime),
-                        "user" => line_mode.add_mode(LineModeArgs::User),
-                        "group" => line_mode.add_mode(LineModeArgs::Group),
-                        "perm" => line_mode.add_mode(LineModeArgs::Permission),
-                        e => {
+                    match LineModeArgs::from_name(mode) {
+                        Some(mode) => line_mode.add_mode(mode),
+                        None => {
                             return Err(AppError::new(
                                 AppErrorKind::InvalidParameters,
-                                format!("Linemode '{}' unknown.", e),
+                                format!("Linemode '{}' unknown.", mode),
                             ))
                         }
                     }
@@ -92,14 +89,12 @@ impl LineMode {
         }
     }
 
-    /// Formats the active columns as a `" | "`-joined string.
+    /// Formats the active columns as a `" | "`-joined string, or `"none"` if there are none.
     pub fn as_string(&self) -> String {
-        let modes: Vec<&str> = self
-            .mode
-            .iter()
-            .take(self.size)
-            .map(AsRef::as_ref)
-            .collect();
+        if self.mode.is_empty() {
+            return "none".to_string();
+        }
+        let modes: Vec<&str> = self.mode.iter().map(AsRef::as_ref).collect();
 
         modes.join(" | ")
     }
@@ -1,6 +1,8 @@
-//! Resolved (non-raw) display, preview, search, sort, and line-mode option types.
+//! Resolved (non-raw) display, preview, search, sort, line-mode, and per-directory option types.
 
+pub mod dir_settings;
 pub mod display;
+pub mod hidden;
 pub mod line_mode;
 pub mod preview;
 pub mod search;
@@ -1,11 +1,17 @@
+use std::convert::TryFrom;
 use std::path;
 
 use allmytoes::ThumbSize;
 
 use crate::{
     config::preview::preview_option_raw::PreviewOptionRaw,
+    error::{AppError, AppResult},
+    preview::preview_text::check_syntax_theme,
     traits::config::search_directories,
-    utils::{serde::default_max_preview_size, unix},
+    utils::{
+        serde::{default_max_preview_size, default_syntax_theme},
+        unix,
+    },
     CONFIG_HIERARCHY,
 };
 
@@ -17,6 +23,10 @@ pub struct PreviewOption {
     pub max_preview_size: u64,
     pub preview_protocol: PreviewProtocol,
     pub preview_script: Option<path::PathBuf>,
+    /// preview archives as the directories they're browsed as, rather than with the script
+    pub preview_archives: bool,
+    /// the theme text previewed without a script is highlighted with
+    pub syntax_theme: String,
     pub use_xdg_thumbs: bool,
     pub xdg_thumb_size: ThumbSize,
     pub preview_shown_hook_script: Option<path::PathBuf>,
@@ -31,19 +41,36 @@ impl std::default::Default for PreviewOption {
             use_xdg_thumbs: true,
             xdg_thumb_size: ThumbSize::XLarge,
             preview_script: None,
+            preview_archives: false,
+            syntax_theme: default_syntax_theme(),
             preview_shown_hook_script: None,
             preview_removed_hook_script: None,
         }
     }
 }
 
-impl From<PreviewOptionRaw> for PreviewOption {
-    fn from(raw: PreviewOptionRaw) -> Self {
+impl TryFrom<PreviewOptionRaw> for PreviewOption {
+    type Error = AppError;
+
+    fn try_from(raw: PreviewOptionRaw) -> AppResult<Self> {
+        // a missing script (as the default one, until set up) falls back to built-in previews
         let preview_script = raw
             .preview_script
             .map(|s| unix::expand_shell_string(&s))
+            .filter(|path| {
+                let exists = path.exists();
+                if !exists {
+                    eprintln!(
+                        "Warning: preview_script {} doesn't exist, previewing text files without it",
+                        path.display()
+                    );
+                }
+                exists
+            })
             .or_else(|| search_directories("preview.sh", &CONFIG_HIERARCHY));
 
+        check_syntax_theme(&raw.syntax_theme)?;
+
         let preview_shown_hook_script = raw
             .preview_shown_hook_script
             .map(|s| unix::expand_shell_string(&s));
@@ -52,14 +79,16 @@ impl From<PreviewOptionRaw> for PreviewOption {
             .preview_removed_hook_script
             .map(|s| unix::expand_shell_string(&s));
 
-        Self {
+        Ok(Self {
             max_preview_size: raw.max_preview_size,
             preview_protocol: raw.preview_protocol,
             preview_script,
+            preview_archives: raw.preview_archives,
+            syntax_theme: raw.syntax_theme,
             use_xdg_thumbs: raw.use_xdg_thumbs,
             xdg_thumb_size: raw.xdg_thumb_size.to_amt_size(),
             preview_shown_hook_script,
             preview_removed_hook_script,
-        }
+        })
     }
 }
@@ -3,7 +3,7 @@ use std::collections::VecDeque;
 use serde::{Deserialize, Serialize};
 
 /// A single criterion to sort directory entries by.
-#[derive(Clone, Copy, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
+#[derive(Clone, Debug, Default, Deserialize, Serialize, PartialEq, Eq)]
 #[serde(untagged)]
 pub enum SortMethod {
     #[default]
@@ -17,10 +17,59 @@ pub enum SortMethod {
     Size,
     #[serde(rename = "exit")]
     Ext,
+    #[serde(rename = "ctime")]
+    Ctime,
+    #[serde(rename = "atime")]
+    Atime,
+    #[serde(rename = "btime")]
+    Btime,
+    /// By MIME type where known, by file type otherwise.
+    #[serde(rename = "type")]
+    Type,
+    /// By the number of entries in a directory.
+    #[serde(rename = "count")]
+    Count,
+    /// By the cumulative size of a directory, once computed.
+    #[serde(rename = "cumsize")]
+    CumulativeSize,
+    /// By the length of the file name.
+    #[serde(rename = "length")]
+    Length,
+    /// In a random order, reshuffled on request.
+    #[serde(rename = "random")]
+    Random,
+    /// By version numbers contained in the file name, e.g. `1.9` before `1.10`.
+    #[serde(rename = "version")]
+    Version,
+    /// By tag character, untagged entries last.
+    #[serde(rename = "tag")]
+    Tag,
+    /// By the value of the user-defined linemode column with this name.
+    Custom(String),
 }
 
 impl SortMethod {
+    /// All built-in sort methods, in the order they're offered by `sort_menu`.
+    pub const BUILTIN: [SortMethod; 15] = [
+        SortMethod::Natural,
+        SortMethod::Lexical,
+        SortMethod::Version,
+        SortMethod::Length,
+        SortMethod::Ext,
+        SortMethod::Type,
+        SortMethod::Size,
+        SortMethod::CumulativeSize,
+        SortMethod::Count,
+        SortMethod::Mtime,
+        SortMethod::Ctime,
+        SortMethod::Atime,
+        SortMethod::Btime,
+        SortMethod::Tag,
+        SortMethod::Random,
+    ];
+
     /// Parses a sort method name (e.g. `"lexical"`, `"mtime"`) from a command/config string.
+    /// Any other non-empty name refers to a custom linemode column.
     pub fn from_str(s: &str) -> Option<Self> {
         match s {
             "lexical" => Some(SortMethod::Lexical),
@@ -28,19 +77,64 @@ impl SortMethod {
             "natural" => Some(SortMethod::Natural),
             "size" => Some(SortMethod::Size),
             "ext" => Some(SortMethod::Ext),
-            _ => None,
+            "ctime" => Some(SortMethod::Ctime),
+            "atime" => Some(SortMethod::Atime),
+            "btime" => Some(SortMethod::Btime),
+            "type" => Some(SortMethod::Type),
+            "count" => Some(SortMethod::Count),
+            "cumsize" => Some(SortMethod::CumulativeSize),
+            "length" => Some(SortMethod::Length),
+            "random" => Some(SortMethod::Random),
+            "version" => Some(SortMethod::Version),
+            "tag" => Some(SortMethod::Tag),
+            "" => None,
+            s => Some(SortMethod::Custom(s.to_string())),
         }
     }
     /// Returns the sort method's config/display name.
-    pub const fn as_str(&self) -> &str {
-        match *self {
+    pub fn as_str(&self) -> &str {
+        match self {
             SortMethod::Lexical => "lexical",
             SortMethod::Mtime => "mtime",
             SortMethod::Natural => "natural",
             SortMethod::Size => "size",
             SortMethod::Ext => "ext",
+            SortMethod::Ctime => "ctime",
+            SortMethod::Atime => "atime",
+            SortMethod::Btime => "btime",
+            SortMethod::Type => "type",
+            SortMethod::Count => "count",
+            SortMethod::CumulativeSize => "cumsize",
+            SortMethod::Length => "length",
+            SortMethod::Random => "random",
+            SortMethod::Version => "version",
+            SortMethod::Tag => "tag",
+            SortMethod::Custom(name) => name,
         }
     }
+
+    /// Returns `true` if this method compares metadata that isn't known until an entry is
+    /// stat'ed, see [`JoshutoMetadata::is_loaded`](crate::fs::JoshutoMetadata::is_loaded).
+    pub fn needs_metadata(&self) -> bool {
+        matches!(
+            self,
+            SortMethod::Mtime
+                | SortMethod::Size
+                | SortMethod::Ctime
+                | SortMethod::Atime
+                | SortMethod::Btime
+                | SortMethod::Count
+        )
+    }
+
+    /// Returns `true` if no two entries of a directory compare equal by this method, so that
+    /// methods after it in a [`SortMethodList`] never come into play.
+    pub fn is_total(&self) -> bool {
+        matches!(
+            self,
+            SortMethod::Lexical | SortMethod::Natural | SortMethod::Version | SortMethod::Random
+        )
+    }
 }
 
 impl std::fmt::Display for SortMethod {
@@ -57,10 +151,31 @@ pub struct SortMethodList {
 }
 
 impl SortMethodList {
-    /// Moves `st` to the front of the priority list, dropping the lowest-priority method.
+    /// Moves `st` to the front of the priority list, dropping the lowest-priority method if `st`
+    /// wasn't in the list yet.
     pub fn reorganize(&mut self, st: SortMethod) {
+        let len = self.list.len();
+        self.list.retain(|m| *m != st);
         self.list.push_front(st);
-        self.list.pop_back();
+        if self.list.len() > len {
+            self.list.pop_back();
+        }
+    }
+
+    /// Returns the methods that actually decide the order: the list up to and including the
+    /// first one that orders all entries on its own.
+    pub fn active(&self) -> impl Iterator<Item = &SortMethod> {
+        let end = self
+            .list
+            .iter()
+            .position(|m| m.is_total())
+            .map_or(self.list.len(), |i| i + 1);
+        self.list.iter().take(end)
+    }
+
+    /// Returns `true` if `method` is one of the [`active`](Self::active) methods.
+    pub fn is_active(&self, method: &SortMethod) -> bool {
+        self.active().any(|m| m == method)
     }
 }
 
@@ -79,3 +194,41 @@ impl std::default::Default for SortMethodList {
         Self { list }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_reorganize() {
+        let mut methods = SortMethodList::default();
+        methods.reorganize(SortMethod::Mtime);
+        assert_eq!(
+            methods.list,
+            [
+                SortMethod::Mtime,
+                SortMethod::Natural,
+                SortMethod::Lexical,
+                SortMethod::Size,
+                SortMethod::Ext,
+            ]
+        );
+        methods.reorganize(SortMethod::Ctime);
+        assert_eq!(methods.list.len(), 5);
+        assert_eq!(methods.list.front(), Some(&SortMethod::Ctime));
+        assert_eq!(methods.list.back(), Some(&SortMethod::Size));
+    }
+
+    #[test]
+    fn test_active() {
+        let mut methods = SortMethodList::default();
+        assert_eq!(methods.active().collect::<Vec<_>>(), [&SortMethod::Natural]);
+        methods.reorganize(SortMethod::Size);
+        methods.reorganize(SortMethod::Ext);
+        assert_eq!(
+            methods.active().collect::<Vec<_>>(),
+            [&SortMethod::Ext, &SortMethod::Size, &SortMethod::Natural]
+        );
+        assert!(!methods.is_active(&SortMethod::Lexical));
+    }
+}
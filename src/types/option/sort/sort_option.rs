@@ -1,9 +1,10 @@
 use std::cmp;
+use std::hash::{DefaultHasher, Hash, Hasher};
 
 use serde::{Deserialize, Serialize};
 
 use crate::config::sort_option_raw::SortOptionRaw;
-use crate::fs::JoshutoDirEntry;
+use crate::fs::{FileType, JoshutoDirEntry};
 
 use crate::types::option::sort::{SortMethod, SortMethodList};
 
@@ -15,6 +16,9 @@ pub struct SortOption {
     pub case_sensitive: bool,
     pub reverse: bool,
     pub sort_methods: SortMethodList,
+    /// seed of the `random` sort order
+    #[serde(skip)]
+    pub random_seed: u64,
 }
 
 impl SortOption {
@@ -23,12 +27,17 @@ impl SortOption {
         self.sort_methods.reorganize(method);
     }
 
+    /// Picks a new `random` sort order.
+    pub fn reshuffle(&mut self) {
+        self.random_seed = rand::random();
+    }
+
     /// Compares two entries according to `directories_first` and the configured sort-method
     /// priority order, applying `reverse` to each comparison.
     pub fn compare(&self, f1: &JoshutoDirEntry, f2: &JoshutoDirEntry) -> cmp::Ordering {
         if self.directories_first {
-            let f1_isdir = f1.file_path().is_dir();
-            let f2_isdir = f2.file_path().is_dir();
+            let f1_isdir = f1.metadata.is_dir();
+            let f2_isdir = f2.metadata.is_dir();
 
             if f1_isdir && !f2_isdir {
                 return cmp::Ordering::Less;
@@ -38,6 +47,16 @@ impl SortOption {
         }
 
         for sort_method in self.sort_methods.list.iter() {
+            // entries still waiting for their metadata go last, whatever the order
+            if sort_method.needs_metadata() {
+                match (f1.metadata.is_loaded(), f2.metadata.is_loaded()) {
+                    (true, false) => return cmp::Ordering::Less,
+                    (false, true) => return cmp::Ordering::Greater,
+                    (false, false) => continue,
+                    (true, true) => {}
+                }
+            }
+
             let mut cmp_res = match sort_method {
                 SortMethod::Ext => ext_sort(f1, f2),
                 SortMethod::Lexical => {
@@ -64,6 +83,27 @@ impl SortOption {
                     }
                 }
                 SortMethod::Size => size_sort(f1, f2),
+                SortMethod::Ctime => f1.metadata.changed().cmp(&f2.metadata.changed()),
+                SortMethod::Atime => f1.metadata.accessed().cmp(&f2.metadata.accessed()),
+                SortMethod::Btime => unknown_last(f1.metadata.created(), f2.metadata.created()),
+                SortMethod::Type => type_sort(f1, f2),
+                SortMethod::Count => {
+                    unknown_last(f1.metadata.directory_size(), f2.metadata.directory_size())
+                }
+                SortMethod::CumulativeSize => {
+                    unknown_last(f1.metadata.cumulative_size(), f2.metadata.cumulative_size())
+                }
+                SortMethod::Length => f1
+                    .file_name()
+                    .chars()
+                    .count()
+                    .cmp(&f2.file_name().chars().count()),
+                SortMethod::Random => {
+                    random_key(self.random_seed, f1).cmp(&random_key(self.random_seed, f2))
+                }
+                SortMethod::Version => version_cmp(f1.file_name(), f2.file_name()),
+                SortMethod::Tag => unknown_last(f1.tag(), f2.tag()),
+                SortMethod::Custom(name) => custom_sort(name, f1, f2),
             };
 
             if self.reverse {
@@ -85,6 +125,7 @@ impl std::default::Default for SortOption {
             case_sensitive: false,
             reverse: false,
             sort_methods: SortMethodList::default(),
+            random_seed: rand::random(),
         }
     }
 }
@@ -103,6 +144,7 @@ impl From<SortOptionRaw> for SortOption {
             case_sensitive: raw.case_sensitive,
             reverse: raw.reverse,
             sort_methods,
+            random_seed: rand::random(),
         }
     }
 }
@@ -121,3 +163,163 @@ fn ext_sort(file1: &JoshutoDirEntry, file2: &JoshutoDirEntry) -> cmp::Ordering {
     let f2_ext = file2.ext().unwrap_or_default();
     alphanumeric_sort::compare_str(f1_ext, f2_ext)
 }
+
+/// Orders known values before unknown ones.
+fn unknown_last<T: Ord>(v1: Option<T>, v2: Option<T>) -> cmp::Ordering {
+    match (v1, v2) {
+        (Some(v1), Some(v2)) => v1.cmp(&v2),
+        (Some(_), None) => cmp::Ordering::Less,
+        (None, Some(_)) => cmp::Ordering::Greater,
+        (None, None) => cmp::Ordering::Equal,
+    }
+}
+
+fn type_sort(file1: &JoshutoDirEntry, file2: &JoshutoDirEntry) -> cmp::Ordering {
+    let rank = |e: &JoshutoDirEntry| match e.metadata.file_type() {
+        FileType::Directory => 0,
+        FileType::File => 1,
+        FileType::Link => 2,
+        FileType::Socket => 3,
+        FileType::Block => 4,
+        FileType::Character => 5,
+        FileType::Pipe => 6,
+    };
+    // entries whose MIME type isn't known yet go last among those of their file type
+    rank(file1).cmp(&rank(file2)).then_with(|| {
+        unknown_last(
+            file1.metadata.mimetype.as_ref(),
+            file2.metadata.mimetype.as_ref(),
+        )
+    })
+}
+
+fn random_key(seed: u64, file: &JoshutoDirEntry) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    seed.hash(&mut hasher);
+    file.file_name().hash(&mut hasher);
+    hasher.finish()
+}
+
+/// Compares file names the way `ls -v` does: runs of digits are compared by their numeric
+/// value, everything else character by character, with `~` sorting before anything (even the
+/// end of the name) and letters before other characters. Names that compare equal this way
+/// are ordered by their bytes.
+fn version_cmp(name1: &str, name2: &str) -> cmp::Ordering {
+    fn order(c: Option<char>) -> i32 {
+        match c {
+            None => 0,
+            Some('~') => -1,
+            Some(c) if c.is_ascii_digit() => 0,
+            Some(c) if c.is_alphabetic() => c as i32,
+            Some(c) => c as i32 + 0x110000,
+        }
+    }
+
+    let mut s1 = name1.chars().peekable();
+    let mut s2 = name2.chars().peekable();
+    loop {
+        // compare the non-digit prefixes
+        loop {
+            let c1 = s1.peek().copied().filter(|c| !c.is_ascii_digit());
+            let c2 = s2.peek().copied().filter(|c| !c.is_ascii_digit());
+            if c1.is_none() && c2.is_none() {
+                break;
+            }
+            let res = order(c1).cmp(&order(c2));
+            if res != cmp::Ordering::Equal {
+                return res;
+            }
+            s1.next();
+            s2.next();
+        }
+        if s1.peek().is_none() && s2.peek().is_none() {
+            // names differing only in leading zeros are still ordered, e.g. "1" before "01"
+            return name1.cmp(name2);
+        }
+
+        // then the numbers following them
+        let mut n1: String = std::iter::from_fn(|| s1.next_if(|c| c.is_ascii_digit())).collect();
+        let mut n2: String = std::iter::from_fn(|| s2.next_if(|c| c.is_ascii_digit())).collect();
+        n1 = n1.trim_start_matches('0').to_string();
+        n2 = n2.trim_start_matches('0').to_string();
+        let res = n1.len().cmp(&n2.len()).then_with(|| n1.cmp(&n2));
+        if res != cmp::Ordering::Equal {
+            return res;
+        }
+    }
+}
+
+fn custom_sort(name: &str, file1: &JoshutoDirEntry, file2: &JoshutoDirEntry) -> cmp::Ordering {
+    // entries whose value isn't computed yet go last
+    let f1_value = file1.metadata.custom_columns.get(name);
+    let f2_value = file2.metadata.custom_columns.get(name);
+    match (f1_value, f2_value) {
+        (Some(v1), Some(v2)) => alphanumeric_sort::compare_str(v1, v2),
+        (Some(_), None) => cmp::Ordering::Less,
+        (None, Some(_)) => cmp::Ordering::Greater,
+        (None, None) => cmp::Ordering::Equal,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::path::Path;
+
+    use super::*;
+    use crate::fs::{JoshutoMetadata, LinkType};
+
+    #[test]
+    fn test_version_cmp() {
+        let mut names = vec![
+            "foo-1.10.tar",
+            "foo-1.9.tar",
+            "foo-1.9~rc1.tar",
+            "foo-01.2.tar",
+            "foo.tar",
+            "bar",
+        ];
+        names.sort_by(|n1, n2| version_cmp(n1, n2));
+        assert_eq!(
+            names,
+            [
+                "bar",
+                "foo-01.2.tar",
+                "foo-1.9~rc1.tar",
+                "foo-1.9.tar",
+                "foo-1.10.tar",
+                "foo.tar",
+            ]
+        );
+        assert_eq!(version_cmp("a01", "a1"), cmp::Ordering::Less);
+        assert_eq!(version_cmp("a1", "a01"), cmp::Ordering::Greater);
+        assert_eq!(version_cmp("a1", "a1"), cmp::Ordering::Equal);
+    }
+
+    #[test]
+    fn test_type_sort() {
+        let entry = |name: &str, file_type: FileType, mimetype: Option<&str>| {
+            let mut metadata = JoshutoMetadata::unloaded(file_type, LinkType::Normal);
+            metadata.mimetype = mimetype.map(str::to_string);
+            let base = Path::new("/d");
+            JoshutoDirEntry::from_metadata(base.join(name), base, metadata)
+        };
+        let mut entries = [
+            entry("unknown", FileType::File, None),
+            entry("text", FileType::File, Some("text/plain")),
+            entry("dir", FileType::Directory, Some("inode/directory")),
+            entry("image", FileType::File, Some("image/png")),
+            entry("pipe", FileType::Pipe, None),
+            entry("dir2", FileType::Directory, None),
+        ];
+        entries.sort_by(type_sort);
+        let names: Vec<_> = entries.iter().map(|e| e.file_name()).collect();
+        assert_eq!(names, ["dir", "dir2", "image", "text", "unknown", "pipe"]);
+
+        // the order is consistent whichever way entries are compared
+        for e1 in entries.iter() {
+            for e2 in entries.iter() {
+                assert_eq!(type_sort(e1, e2), type_sort(e2, e1).reverse());
+            }
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use std::collections::HashSet;
 use std::sync::mpsc;
+use std::thread;
 
 use allmytoes::{AMTConfiguration, AMT};
 use ratatui::style::Color;
@@ -7,10 +8,12 @@ use ratatui_image::picker::Picker;
 
 use crate::commands::quit::QuitAction;
 use crate::config::app::AppConfig;
-use crate::types::event::{AppEvent, AppEventListener};
+use crate::types::event::{AppEvent, AppEventListener, FilesystemListener};
 use crate::types::option::preview::PreviewProtocol;
 use crate::types::state::{
-    CommandLineState, MessageQueue, PreviewState, TabState, UiState, WorkerState,
+    CommandLineState, CumulativeSizeState, CustomColumnState, DirSettingsState, FindState,
+    FsInfoState, GitState, MessageQueue, MimetypeState, PreviewState, TabState, UiState,
+    WorkerState,
 };
 
 use crate::{Args, THEME_T};
@@ -57,10 +60,14 @@ impl AppState {
 
         let commandline_state = CommandLineState::new();
 
-        let event_tx_for_fs_notification = event_tx.clone();
+        let (watcher_tx, watcher_rx) = mpsc::channel();
+        let fs_listener = FilesystemListener::new(event_tx.clone(), watcher_rx);
+        let _ = thread::spawn(move || {
+            fs_listener.run();
+        });
         let watcher = notify::recommended_watcher(move |res| {
             if let Ok(event) = res {
-                let _ = event_tx_for_fs_notification.send(AppEvent::Filesystem(event));
+                let _ = watcher_tx.send(event);
             }
         })
         .unwrap();
@@ -73,6 +80,8 @@ impl AppState {
             None
         };
         let xdg_thumb_size = config.preview_options.xdg_thumb_size;
+        let syntax_theme = config.preview_options.syntax_theme.clone();
+        let dir_settings_state = DirSettingsState::new(config.dir_settings_options.remember);
 
         Self {
             config,
@@ -85,10 +94,18 @@ impl AppState {
                 search_state: None,
                 message_queue: MessageQueue::new(),
                 worker_state: WorkerState::new(event_tx.clone()),
+                git_state: GitState::new(event_tx.clone()),
+                cumulative_size_state: CumulativeSizeState::new(event_tx.clone()),
+                find_state: FindState::new(event_tx.clone()),
+                custom_column_state: CustomColumnState::new(event_tx.clone()),
+                mimetype_state: MimetypeState::new(event_tx.clone()),
+                dir_settings_state,
+                fs_info_state: FsInfoState::new(),
                 thread_pool: ThreadPool::new(),
                 preview_state: PreviewState::new(
                     picker,
                     preview_script,
+                    syntax_theme,
                     allmytoes,
                     xdg_thumb_size,
                     event_tx,
@@ -0,0 +1,140 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread;
+use std::time;
+
+use crate::fs::{compute_size, JoshutoDirList};
+use crate::types::event::AppEvent;
+
+/// How often sizes computed in the background are sent, at most.
+const BATCH_INTERVAL: time::Duration = time::Duration::from_millis(200);
+
+/// Cumulative sizes of directories, computed on background threads and cached by path and
+/// modification time. Note that a directory's mtime only changes with its direct entries, so
+/// changes deeper down aren't noticed until the size is recomputed with `--force`.
+pub struct CumulativeSizeState {
+    /// computed sizes by path, with the mtime of the directory they were computed for
+    cache: HashMap<PathBuf, (time::SystemTime, u64)>,
+    /// entries whose size is currently being computed
+    pending: HashSet<PathBuf>,
+    /// the version of each listing, by path, whose entries were all filled in
+    scanned: HashMap<PathBuf, u64>,
+    /// listings, by path, with sizes filled in since they were last sorted
+    unsorted: HashSet<PathBuf>,
+    /// set to cancel the running computations
+    cancel: Arc<AtomicBool>,
+    // for telling main thread when a size is ready
+    event_tx: Sender<AppEvent>,
+}
+
+impl CumulativeSizeState {
+    /// Creates an empty state that sends computed sizes to `event_tx`.
+    pub fn new(event_tx: Sender<AppEvent>) -> Self {
+        Self {
+            cache: HashMap::new(),
+            pending: HashSet::new(),
+            scanned: HashMap::new(),
+            unsorted: HashSet::new(),
+            cancel: Arc::new(AtomicBool::new(false)),
+            event_tx,
+        }
+    }
+
+    /// Returns the cached size of `path`, if it was computed for the entry as last modified at
+    /// `modified`.
+    pub fn get(&self, path: &Path, modified: time::SystemTime) -> Option<u64> {
+        match self.cache.get(path) {
+            Some((cached_modified, size)) if *cached_modified == modified => Some(*size),
+            _ => None,
+        }
+    }
+
+    /// Fills in the sizes of the entries of `list` computed earlier for their current
+    /// modification time, unless this was already done for the list as it is. Returns `true` if
+    /// an entry was filled in.
+    pub fn load(&mut self, list: &mut JoshutoDirList) -> bool {
+        if self.cache.is_empty() || self.scanned.get(list.file_path()) == Some(&list.version()) {
+            return false;
+        }
+        self.scanned
+            .insert(list.file_path().to_path_buf(), list.version());
+        let mut filled = false;
+        for entry in list.iter_mut() {
+            if entry.metadata.cumulative_size().is_some() || !entry.metadata.is_loaded() {
+                continue;
+            }
+            if let Some(size) = self.get(entry.file_path(), entry.metadata.modified()) {
+                entry.metadata.update_cumulative_size(size);
+                filled = true;
+            }
+        }
+        filled
+    }
+
+    /// Starts computing the sizes of `entries` one after another on a background thread,
+    /// skipping those already being computed. The sizes are sent in batches as
+    /// [`AppEvent::CumulativeSizes`] while they're computed. Once done, `report` is called with
+    /// the computed sizes and its message sent as an [`AppEvent::CumulativeSizeDone`].
+    pub fn request<F>(&mut self, entries: Vec<(PathBuf, time::SystemTime)>, report: F)
+    where
+        F: FnOnce(&[u64]) -> String + Send + 'static,
+    {
+        let entries: Vec<_> = entries
+            .into_iter()
+            .filter(|(path, _)| self.pending.insert(path.clone()))
+            .collect();
+        let cancel = self.cancel.clone();
+        let event_tx = self.event_tx.clone();
+        thread::spawn(move || {
+            let mut sizes = Vec::with_capacity(entries.len());
+            let mut batch = Vec::new();
+            let mut last_sent = time::Instant::now();
+            for (path, modified) in entries {
+                let Some(size) = compute_size(&path, &cancel) else {
+                    return;
+                };
+                sizes.push(size);
+                batch.push((path, modified, size));
+                if last_sent.elapsed() >= BATCH_INTERVAL {
+                    let event = AppEvent::CumulativeSizes(std::mem::take(&mut batch));
+                    if event_tx.send(event).is_err() {
+                        return;
+                    }
+                    last_sent = time::Instant::now();
+                }
+            }
+            if !batch.is_empty() && event_tx.send(AppEvent::CumulativeSizes(batch)).is_err() {
+                return;
+            }
+            let _ = event_tx.send(AppEvent::CumulativeSizeDone(report(&sizes)));
+        });
+    }
+
+    /// Cancels all running computations, returning how many sizes were still pending.
+    pub fn cancel(&mut self) -> usize {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.cancel = Arc::new(AtomicBool::new(false));
+        let count = self.pending.len();
+        self.pending.clear();
+        count
+    }
+
+    /// Records sizes computed in the background, whose listings are then to be sorted.
+    pub fn update(&mut self, sizes: &[(PathBuf, time::SystemTime, u64)]) {
+        for (path, modified, size) in sizes {
+            self.pending.remove(path);
+            self.cache.insert(path.clone(), (*modified, *size));
+            if let Some(parent) = path.parent() {
+                self.unsorted.insert(parent.to_path_buf());
+            }
+        }
+    }
+
+    /// Returns the paths of the listings with sizes filled in since they were last sorted.
+    pub fn take_unsorted(&mut self) -> HashSet<PathBuf> {
+        std::mem::take(&mut self.unsorted)
+    }
+}
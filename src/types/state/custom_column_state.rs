@@ -0,0 +1,168 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::process::{Command, Stdio};
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time;
+
+use crate::fs::{JoshutoDirEntry, JoshutoDirList};
+use crate::types::event::AppEvent;
+use crate::types::option::line_mode::CustomColumn;
+
+/// Number of threads running custom column commands concurrently.
+const WORKER_COUNT: usize = 4;
+
+/// Number of entries a worker computes a column for before sending their values.
+const JOB_SIZE: usize = 16;
+
+/// Custom column values to compute: the column, and the entries with their mtime.
+struct CustomColumnJob {
+    column: CustomColumn,
+    entries: Vec<(PathBuf, time::SystemTime)>,
+}
+
+/// Values of the user-defined linemode columns, computed by running their commands on a few
+/// background threads and cached by path and modification time.
+pub struct CustomColumnState {
+    /// computed values by column name and path, with the mtime of the file they were computed for
+    cache: HashMap<(String, PathBuf), (time::SystemTime, String)>,
+    /// values currently being computed
+    pending: HashSet<(String, PathBuf)>,
+    /// the version of each listing, by column name and path, whose entries were all filled in or
+    /// requested
+    scanned: HashMap<(String, PathBuf), u64>,
+    job_tx: Sender<CustomColumnJob>,
+}
+
+impl CustomColumnState {
+    /// Spawns the worker threads, which send computed values to `event_tx`.
+    pub fn new(event_tx: Sender<AppEvent>) -> Self {
+        let (job_tx, job_rx) = mpsc::channel();
+        let job_rx = Arc::new(Mutex::new(job_rx));
+        for _ in 0..WORKER_COUNT {
+            let job_rx = job_rx.clone();
+            let event_tx = event_tx.clone();
+            thread::spawn(move || Self::run_worker(job_rx, event_tx));
+        }
+        Self {
+            cache: HashMap::new(),
+            pending: HashSet::new(),
+            scanned: HashMap::new(),
+            job_tx,
+        }
+    }
+
+    fn run_worker(job_rx: Arc<Mutex<Receiver<CustomColumnJob>>>, event_tx: Sender<AppEvent>) {
+        loop {
+            let job = match job_rx.lock() {
+                Ok(job_rx) => job_rx.recv(),
+                Err(_) => return,
+            };
+            let Ok(job) = job else {
+                return;
+            };
+            let values = job
+                .entries
+                .into_iter()
+                .map(|(path, modified)| {
+                    let value = Self::run_command(&job.column.command_for(&path));
+                    (path, modified, value)
+                })
+                .collect();
+            let event = AppEvent::CustomColumn {
+                name: job.column.name,
+                values,
+            };
+            if event_tx.send(event).is_err() {
+                return;
+            }
+        }
+    }
+
+    fn run_command(command: &str) -> String {
+        let output = Command::new("sh")
+            .arg("-c")
+            .arg(command)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        // only the first line is shown, failures show nothing
+        match output {
+            Ok(output) if output.status.success() => String::from_utf8_lossy(&output.stdout)
+                .lines()
+                .next()
+                .unwrap_or_default()
+                .trim()
+                .to_string(),
+            _ => String::new(),
+        }
+    }
+
+    /// Fills in the values of `column` of `entries` known for their current modification time,
+    /// starting to compute the others in the background. Returns `true` if an entry was filled
+    /// in.
+    pub fn load<'a>(
+        &mut self,
+        column: &CustomColumn,
+        entries: impl Iterator<Item = &'a mut JoshutoDirEntry>,
+    ) -> bool {
+        let mut filled = false;
+        let mut job = Vec::new();
+        for entry in entries {
+            // the mtime isn't known yet for entries still being loaded
+            if entry.metadata.custom_columns.contains_key(&column.name)
+                || !entry.metadata.is_loaded()
+            {
+                continue;
+            }
+            let modified = entry.metadata.modified();
+            let key = (column.name.clone(), entry.file_path_buf());
+            match self.cache.get(&key) {
+                Some((cached_modified, value)) if *cached_modified == modified => {
+                    entry
+                        .metadata
+                        .custom_columns
+                        .insert(column.name.clone(), value.clone());
+                    filled = true;
+                }
+                _ => {
+                    if self.pending.insert(key) {
+                        job.push((entry.file_path_buf(), modified));
+                    }
+                }
+            }
+        }
+        for chunk in job.chunks(JOB_SIZE) {
+            let job = CustomColumnJob {
+                column: column.clone(),
+                entries: chunk.to_vec(),
+            };
+            if self.job_tx.send(job).is_err() {
+                self.pending.clear();
+                break;
+            }
+        }
+        filled
+    }
+
+    /// Like [`load`](Self::load) for all entries of `list`, unless this was already done for
+    /// the list as it is.
+    pub fn load_list(&mut self, column: &CustomColumn, list: &mut JoshutoDirList) -> bool {
+        let key = (column.name.clone(), list.file_path().to_path_buf());
+        if self.scanned.get(&key) == Some(&list.version()) {
+            return false;
+        }
+        self.scanned.insert(key, list.version());
+        self.load(column, list.iter_mut())
+    }
+
+    /// Records values of the column called `name` computed in the background.
+    pub fn update(&mut self, name: &str, values: &[(PathBuf, time::SystemTime, String)]) {
+        for (path, modified, value) in values {
+            let key = (name.to_string(), path.clone());
+            self.pending.remove(&key);
+            self.cache.insert(key, (*modified, value.clone()));
+        }
+    }
+}
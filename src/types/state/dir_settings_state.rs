@@ -0,0 +1,75 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use crate::types::option::dir_settings::DirSettings;
+use crate::PROGRAM_NAME;
+
+const DIR_SETTINGS_FILE: &str = "dir_settings.toml";
+
+/// View settings remembered per directory across sessions, stored in
+/// `$XDG_STATE_HOME/joshuto/dir_settings.toml`.
+#[derive(Debug, Default)]
+pub struct DirSettingsState {
+    /// where the settings are stored, `None` if not remembering settings
+    file: Option<PathBuf>,
+    /// remembered settings by directory
+    dirs: BTreeMap<String, DirSettings>,
+}
+
+impl DirSettingsState {
+    /// Reads the remembered settings if `remember` is enabled, otherwise remembers nothing.
+    pub fn new(remember: bool) -> Self {
+        let Some(file) = remember.then(state_file).flatten() else {
+            return Self::default();
+        };
+        // a missing or unreadable file starts out empty
+        let dirs = fs::read_to_string(&file)
+            .ok()
+            .and_then(|s| toml::from_str(&s).ok())
+            .unwrap_or_default();
+        Self {
+            file: Some(file),
+            dirs,
+        }
+    }
+
+    /// Returns the remembered settings of `path`.
+    pub fn get(&self, path: &Path) -> Option<&DirSettings> {
+        self.dirs.get(path.to_string_lossy().as_ref())
+    }
+
+    /// Changes the remembered settings of `path` with `f` and writes them to disk. Does nothing
+    /// if not remembering settings.
+    pub fn update<F>(&mut self, path: &Path, f: F) -> io::Result<()>
+    where
+        F: FnOnce(&mut DirSettings),
+    {
+        let Some(file) = self.file.as_ref() else {
+            return Ok(());
+        };
+        let settings = self
+            .dirs
+            .entry(path.to_string_lossy().into_owned())
+            .or_default();
+        f(settings);
+
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string(&self.dirs).map_err(io::Error::other)?;
+        fs::write(file, content)
+    }
+}
+
+fn state_file() -> Option<PathBuf> {
+    let mut path = dirs::state_dir().or_else(|| {
+        let mut home = dirs::home_dir()?;
+        home.push(".local/state");
+        Some(home)
+    })?;
+    path.push(PROGRAM_NAME);
+    path.push(DIR_SETTINGS_FILE);
+    Some(path)
+}
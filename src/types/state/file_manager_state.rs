@@ -3,9 +3,12 @@ use std::path;
 
 use notify::{RecursiveMode, Watcher};
 
+use crate::types::option::display::DisplayOption;
+use crate::types::option::sort::SortMethod;
 use crate::types::state::{
-    CommandLineState, LocalStateState, MatchState, MessageQueue, PreviewState, TabState, UiState,
-    WorkerState,
+    CommandLineState, CumulativeSizeState, CustomColumnState, DirSettingsState, FindState,
+    FsInfoState, GitState, LocalStateState, MatchState, MessageQueue, MimetypeState, PreviewState,
+    TabState, UiState, WorkerState,
 };
 
 use super::ThreadPool;
@@ -27,6 +30,20 @@ pub struct FileManagerState {
     pub thread_pool: ThreadPool,
     /// app_state related to previews
     pub preview_state: PreviewState,
+    /// git status of the repositories being browsed
+    pub git_state: GitState,
+    /// cumulative sizes of directories
+    pub cumulative_size_state: CumulativeSizeState,
+    /// the running `find`, if any
+    pub find_state: FindState,
+    /// values of the user-defined linemode columns
+    pub custom_column_state: CustomColumnState,
+    /// MIME types of entries
+    pub mimetype_state: MimetypeState,
+    /// view settings remembered per directory
+    pub dir_settings_state: DirSettingsState,
+    /// space usage and mount of the current directory's filesystem
+    pub fs_info_state: FsInfoState,
     /// app_state related to command line
     pub commandline_state: CommandLineState,
     /// user interface app_state; data which is input to both, the UI rendering and the app state
@@ -56,6 +73,11 @@ impl FileManagerState {
         for list in watched_lists.iter().flatten() {
             new_paths_to_watch.insert(list.file_path().to_path_buf());
         }
+        // in the tree mode, the contents of expanded directories are shown too
+        if let Some(list) = curr_tab_ref.curr_list_ref() {
+            let expanded = curr_tab_ref.option_ref().expanded_below(list.file_path());
+            new_paths_to_watch.extend(expanded.map(|dir| dir.to_path_buf()));
+        }
 
         // remove paths from watcher which don't need to be watched anymore...
         for old_watched_path in &self.watched_paths {
@@ -75,6 +97,130 @@ impl FileManagerState {
         self.watched_paths = new_paths_to_watch;
     }
 
+    /// Starts loading the git status of the currently shown directories, where not yet known
+    /// or out of date.
+    pub fn load_git_status(&mut self) {
+        let curr_tab_ref = self.tab_state.curr_tab_ref();
+        let dirs: Vec<path::PathBuf> = [
+            curr_tab_ref.parent_list_ref(),
+            curr_tab_ref.curr_list_ref(),
+            curr_tab_ref.child_list_ref(),
+        ]
+        .into_iter()
+        .flatten()
+        .map(|list| list.file_path().to_path_buf())
+        .collect();
+
+        for dir in dirs {
+            self.git_state.load(&dir);
+        }
+    }
+
+    /// Queries the filesystem of the current directory, if not yet known.
+    pub fn load_fs_info(&mut self) {
+        let cwd = self.tab_state.curr_tab_ref().get_cwd().to_path_buf();
+        self.fs_info_state.load(&cwd);
+    }
+
+    /// Fills in the cumulative sizes computed earlier for the entries of the current directory
+    /// that don't have one, e.g. because the listing was read again from scratch.
+    pub fn load_cumulative_sizes(&mut self, display_options: &DisplayOption) {
+        let curr_tab = self.tab_state.curr_tab_mut();
+        let sort_options = curr_tab.option_ref().sort_options_ref().clone();
+        let Some(list) = curr_tab.curr_list_mut() else {
+            return;
+        };
+        if self.cumulative_size_state.load(list) {
+            list.sort(&sort_options, &self.ui_state, display_options);
+        }
+    }
+
+    /// Sorts the listings that had cumulative sizes filled in since they were last sorted.
+    pub fn sort_cumulative_sizes(&mut self, display_options: &DisplayOption) {
+        let unsorted = self.cumulative_size_state.take_unsorted();
+        for (_, tab) in self.tab_state.iter_mut() {
+            let sort_options = tab.option_ref().sort_options_ref().clone();
+            for path in unsorted.iter() {
+                if let Some(list) = tab.history_mut().get_mut(path) {
+                    list.sort(&sort_options, &self.ui_state, display_options);
+                }
+            }
+        }
+    }
+
+    /// Fills in the MIME types of the entries of the shown directories if the current tab shows
+    /// or sorts by them, starting to detect those not yet known or out of date.
+    pub fn load_mimetypes(&mut self, display_options: &DisplayOption) {
+        let curr_tab = self.tab_state.curr_tab_mut();
+        if !curr_tab.option_ref().needs_mimetype() {
+            return;
+        }
+        let sort_options = curr_tab.option_ref().sort_options_ref().clone();
+        let child_path = curr_tab
+            .curr_list_ref()
+            .and_then(|list| list.curr_entry_ref())
+            .map(|entry| entry.file_path_buf());
+        let parent_path = curr_tab.get_cwd().parent().map(|p| p.to_path_buf());
+
+        if let Some(list) = curr_tab.curr_list_mut() {
+            if self.mimetype_state.load(list) {
+                list.sort(&sort_options, &self.ui_state, display_options);
+            }
+        }
+        for path in [parent_path, child_path].into_iter().flatten() {
+            if let Some(list) = curr_tab.history_mut().get_mut(&path) {
+                if self.mimetype_state.load(list) {
+                    list.sort(&sort_options, &self.ui_state, display_options);
+                }
+            }
+        }
+    }
+
+    /// Fills in the values of the custom linemode columns of the current directory's visible
+    /// entries, or of all its entries if it's sorted by one, starting to compute those not yet
+    /// known or out of date.
+    pub fn load_custom_columns(&mut self, display_options: &DisplayOption) {
+        let height = self
+            .ui_state
+            .layout
+            .first()
+            .map_or(0, |r| r.height as usize);
+        let curr_tab = self.tab_state.curr_tab_mut();
+        let tab_options = curr_tab.option_ref().clone();
+        let sort_column = match tab_options.sort_options_ref().sort_methods.list.front() {
+            Some(SortMethod::Custom(name)) => display_options.custom_column(name),
+            _ => None,
+        };
+        let shown_columns: Vec<_> = tab_options
+            .linemode
+            .custom_columns()
+            .filter_map(|name| display_options.custom_column(name))
+            .collect();
+        if sort_column.is_none() && shown_columns.is_empty() {
+            return;
+        }
+        let Some(list) = curr_tab.curr_list_mut() else {
+            return;
+        };
+
+        let mut sort_changed = false;
+        if let Some(column) = sort_column {
+            sort_changed = self.custom_column_state.load_list(column, list);
+        }
+        let viewport_index = list.viewport_index;
+        for column in shown_columns {
+            let visible = list.iter_mut().skip(viewport_index).take(height);
+            self.custom_column_state.load(column, visible);
+        }
+        if sort_changed {
+            list.sort(
+                tab_options.sort_options_ref(),
+                &self.ui_state,
+                display_options,
+            );
+        }
+    }
+
     /// Returns the state of all tabs.
     pub fn tab_state_ref(&self) -> &TabState {
         &self.tab_state
@@ -112,6 +258,52 @@ impl FileManagerState {
         self.search_state = Some(app_state);
     }
 
+    /// Returns the git status cache.
+    pub fn git_state_ref(&self) -> &GitState {
+        &self.git_state
+    }
+    /// Returns a mutable reference to the git status cache.
+    pub fn git_state_mut(&mut self) -> &mut GitState {
+        &mut self.git_state
+    }
+
+    /// Returns the cumulative sizes computed so far.
+    pub fn cumulative_size_state_ref(&self) -> &CumulativeSizeState {
+        &self.cumulative_size_state
+    }
+    /// Returns a mutable reference to the cumulative size state.
+    pub fn cumulative_size_state_mut(&mut self) -> &mut CumulativeSizeState {
+        &mut self.cumulative_size_state
+    }
+
+    /// Returns a mutable reference to the state of the running `find`.
+    pub fn find_state_mut(&mut self) -> &mut FindState {
+        &mut self.find_state
+    }
+
+    /// Returns a mutable reference to the custom linemode column values.
+    pub fn custom_column_state_mut(&mut self) -> &mut CustomColumnState {
+        &mut self.custom_column_state
+    }
+
+    /// Returns the view settings remembered per directory.
+    pub fn dir_settings_state_ref(&self) -> &DirSettingsState {
+        &self.dir_settings_state
+    }
+    /// Returns a mutable reference to the view settings remembered per directory.
+    pub fn dir_settings_state_mut(&mut self) -> &mut DirSettingsState {
+        &mut self.dir_settings_state
+    }
+
+    /// Returns the information about the current directory's filesystem.
+    pub fn fs_info_state_ref(&self) -> &FsInfoState {
+        &self.fs_info_state
+    }
+    /// Returns a mutable reference to the information about the current filesystem.
+    pub fn fs_info_state_mut(&mut self) -> &mut FsInfoState {
+        &mut self.fs_info_state
+    }
+
     /// Returns the current preview state.
     pub fn preview_state_ref(&self) -> &PreviewState {
         &self.preview_state
@@ -0,0 +1,131 @@
+use std::path::PathBuf;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::mpsc::Sender;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant, SystemTime};
+
+use uuid::Uuid;
+use walkdir::WalkDir;
+
+use crate::fs::JoshutoDirEntry;
+use crate::tab::TabDisplayOption;
+use crate::types::event::AppEvent;
+use crate::types::option::display::DisplayOption;
+
+/// How often matches found are sent to the main thread.
+const BATCH_INTERVAL: Duration = Duration::from_millis(100);
+
+/// The `find` running on a background thread, if any.
+pub struct FindState {
+    /// id of the running search, also the `load_id` of the listing its results stream into
+    running: Option<Uuid>,
+    /// set to cancel the running search
+    cancel: Arc<AtomicBool>,
+    // for sending results to the main thread
+    event_tx: Sender<AppEvent>,
+}
+
+impl FindState {
+    /// Creates an idle state that sends results to `event_tx`.
+    pub fn new(event_tx: Sender<AppEvent>) -> Self {
+        Self {
+            running: None,
+            cancel: Arc::new(AtomicBool::new(false)),
+            event_tx,
+        }
+    }
+
+    /// Starts walking the tree below `root` on a background thread, cancelling the running
+    /// search if any, and returns its id. Entries are matched with `is_match`, given the time
+    /// the search started. Hidden entries are skipped, along with their contents, unless
+    /// `display_options` show them. Matches are sent in batches as
+    /// [`AppEvent::FindResults`], read as entries of a listing of `root`, followed by an
+    /// [`AppEvent::FindDone`].
+    pub fn start<F>(
+        &mut self,
+        root: PathBuf,
+        is_match: F,
+        display_options: &DisplayOption,
+        tab_options: &TabDisplayOption,
+    ) -> Uuid
+    where
+        F: Fn(&walkdir::DirEntry, SystemTime) -> bool + Send + 'static,
+    {
+        self.cancel();
+        let id = Uuid::new_v4();
+        self.running = Some(id);
+
+        let show_hidden = display_options.show_hidden;
+        let display_options = display_options.clone();
+        let tab_options = tab_options.clone();
+        let cancel = self.cancel.clone();
+        let event_tx = self.event_tx.clone();
+        thread::spawn(move || {
+            let now = SystemTime::now();
+            let mut entries = Vec::new();
+            let mut last_sent = Instant::now();
+            let walker = WalkDir::new(&root)
+                .min_depth(1)
+                .into_iter()
+                .filter_entry(|e| {
+                    show_hidden
+                        || !e
+                            .file_name()
+                            .to_str()
+                            .is_some_and(|name| display_options.is_hidden(name))
+                });
+            for entry in walker.filter_map(|e| e.ok()) {
+                if cancel.load(Ordering::Relaxed) {
+                    return;
+                }
+                if is_match(&entry, now) {
+                    let entry = JoshutoDirEntry::from_path(
+                        entry.path(),
+                        &root,
+                        &display_options,
+                        &tab_options,
+                    );
+                    entries.extend(entry.ok());
+                }
+                if !entries.is_empty() && last_sent.elapsed() >= BATCH_INTERVAL {
+                    let entries = std::mem::take(&mut entries);
+                    if event_tx
+                        .send(AppEvent::FindResults { id, entries })
+                        .is_err()
+                    {
+                        return;
+                    }
+                    last_sent = Instant::now();
+                }
+            }
+            if !entries.is_empty() {
+                let _ = event_tx.send(AppEvent::FindResults { id, entries });
+            }
+            let _ = event_tx.send(AppEvent::FindDone { id });
+        });
+        id
+    }
+
+    /// Cancels the running search, returning its id if there was one.
+    pub fn cancel(&mut self) -> Option<Uuid> {
+        self.cancel.store(true, Ordering::Relaxed);
+        self.cancel = Arc::new(AtomicBool::new(false));
+        self.running.take()
+    }
+
+    /// Returns `true` if the search `id` is the one running.
+    pub fn is_running(&self, id: Uuid) -> bool {
+        self.running == Some(id)
+    }
+
+    /// Records that the search `id` is done, returning `false` if it had been cancelled.
+    pub fn finish(&mut self, id: Uuid) -> bool {
+        if self.running == Some(id) {
+            self.running = None;
+            true
+        } else {
+            false
+        }
+    }
+}
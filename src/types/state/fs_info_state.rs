@@ -0,0 +1,38 @@
+use std::path::{Path, PathBuf};
+
+use crate::utils::fs_info::FsInfo;
+
+/// Information about the filesystem of the current directory, queried again when changing
+/// directories or after changes to the filesystem's contents.
+#[derive(Debug, Default)]
+pub struct FsInfoState {
+    /// directory the information was queried for
+    dir: Option<PathBuf>,
+    info: Option<FsInfo>,
+}
+
+impl FsInfoState {
+    /// Creates an empty state that doesn't know any filesystem yet.
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Returns the information about the filesystem of the directory last loaded.
+    pub fn info(&self) -> Option<&FsInfo> {
+        self.info.as_ref()
+    }
+
+    /// Queries the filesystem of `dir`, unless that's already known.
+    pub fn load(&mut self, dir: &Path) {
+        if self.dir.as_deref() != Some(dir) {
+            self.info = FsInfo::for_path(dir).ok();
+            self.dir = Some(dir.to_path_buf());
+        }
+    }
+
+    /// Forgets the information, so that it's queried again on the next load, e.g. once an IO
+    /// task changed the space used.
+    pub fn invalidate(&mut self) {
+        self.dir = None;
+    }
+}
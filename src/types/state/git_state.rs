@@ -0,0 +1,98 @@
+use std::collections::{HashMap, HashSet};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc::Sender;
+use std::thread;
+
+use crate::git::GitRepoStatus;
+use crate::types::event::AppEvent;
+
+/// Cached `git status` of the repositories containing the directories being browsed. Status is
+/// read on background threads and refreshed when the filesystem watcher reports changes.
+pub struct GitState {
+    /// the repository root each visited directory belongs to, or `None` if it isn't in one
+    roots: HashMap<PathBuf, Option<PathBuf>>,
+    /// latest status of each known repository, by root
+    repos: HashMap<PathBuf, GitRepoStatus>,
+    /// directories whose status is currently being read
+    pending: HashSet<PathBuf>,
+    /// repository roots whose status is out of date
+    stale: HashSet<PathBuf>,
+    // for telling main thread when a status is ready
+    event_tx: Sender<AppEvent>,
+}
+
+impl GitState {
+    /// Creates an empty git state that sends results to `event_tx`.
+    pub fn new(event_tx: Sender<AppEvent>) -> Self {
+        Self {
+            roots: HashMap::new(),
+            repos: HashMap::new(),
+            pending: HashSet::new(),
+            stale: HashSet::new(),
+            event_tx,
+        }
+    }
+
+    /// Returns the status of the repository containing `dir`, if known.
+    pub fn repo_ref(&self, dir: &Path) -> Option<&GitRepoStatus> {
+        let root = self.roots.get(dir)?.as_ref()?;
+        self.repos.get(root)
+    }
+
+    /// Starts reading the status of the repository containing `dir` in the background, unless
+    /// it is already known and up to date or being read.
+    pub fn load(&mut self, dir: &Path) {
+        let dir = match self.roots.get(dir) {
+            None => dir,
+            Some(Some(root)) if self.stale.contains(root) => root.as_path(),
+            Some(_) => return,
+        };
+        if self.pending.contains(dir) {
+            return;
+        }
+        let dir = dir.to_path_buf();
+        self.stale.remove(&dir);
+        self.pending.insert(dir.clone());
+
+        let event_tx = self.event_tx.clone();
+        thread::spawn(move || {
+            let res = GitRepoStatus::read(&dir).ok().flatten();
+            let _ = event_tx.send(AppEvent::GitStatus {
+                dir,
+                res: Box::new(res),
+            });
+        });
+    }
+
+    /// Records the result of a background status read started for `dir`.
+    pub fn update(&mut self, dir: PathBuf, res: Option<GitRepoStatus>) {
+        self.pending.remove(&dir);
+        match res {
+            Some(status) => {
+                let root = status.root.clone();
+                self.roots.insert(dir, Some(root.clone()));
+                self.roots.insert(root.clone(), Some(root.clone()));
+                self.repos.insert(root, status);
+            }
+            None => {
+                self.roots.insert(dir, None);
+            }
+        }
+    }
+
+    /// Marks the repository containing `path` as out of date.
+    pub fn invalidate(&mut self, path: &Path) {
+        for root in self.repos.keys() {
+            if path.starts_with(root) {
+                self.stale.insert(root.clone());
+            }
+        }
+    }
+
+    /// Marks every known repository as out of date.
+    pub fn invalidate_all(&mut self) {
+        self.stale.extend(self.repos.keys().cloned());
+        // directories may have become repositories too
+        self.roots.retain(|_, root| root.is_some());
+    }
+}
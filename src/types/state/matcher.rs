@@ -51,6 +51,12 @@ impl MatchState {
     /// Compiles `pattern` as a regex, resolving `Smart` case sensitivity by whether the pattern
     /// contains an uppercase letter.
     pub fn new_regex(pattern: &str, case_sensitivity: CaseSensitivity) -> AppResult<Self> {
+        Ok(Self::Regex(Self::build_regex(pattern, case_sensitivity)?))
+    }
+
+    /// Compiles `pattern` as a bare regex, with case sensitivity resolved as for
+    /// [`MatchState::new_regex`].
+    pub fn build_regex(pattern: &str, case_sensitivity: CaseSensitivity) -> AppResult<Regex> {
         let pattern_lower = pattern.to_lowercase();
 
         let (pattern, actual_case_sensitivity) = match case_sensitivity {
@@ -73,7 +79,7 @@ impl MatchState {
             ))
             .build()?;
 
-        Ok(Self::Regex(re))
+        Ok(re)
     }
 
     /// Builds a plain substring matcher, resolving `Smart` case sensitivity by whether the
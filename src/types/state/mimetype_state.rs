@@ -0,0 +1,106 @@
+use std::collections::{HashMap, HashSet};
+use std::path::PathBuf;
+use std::sync::mpsc::{self, Receiver, Sender};
+use std::thread;
+use std::time;
+
+use crate::fs::{JoshutoDirList, VfsPath};
+use crate::types::event::AppEvent;
+use crate::utils::mimetype::{self, MIMETYPES_CHUNK_SIZE};
+
+/// MIME types of entries for the `mime` linemode column and `type` sort method, detected with
+/// `file` on a background thread and cached by path and modification time.
+pub struct MimetypeState {
+    /// detected types by path, with the mtime of the file they were detected for
+    cache: HashMap<PathBuf, (time::SystemTime, Option<String>)>,
+    /// files whose type is currently being detected
+    pending: HashSet<PathBuf>,
+    /// the version of each listing, by path, whose entries were all filled in or requested
+    scanned: HashMap<PathBuf, u64>,
+    job_tx: Sender<Vec<(PathBuf, time::SystemTime)>>,
+}
+
+impl MimetypeState {
+    /// Spawns the worker thread, which sends detected types to `event_tx`.
+    pub fn new(event_tx: Sender<AppEvent>) -> Self {
+        let (job_tx, job_rx) = mpsc::channel();
+        thread::spawn(move || Self::run_worker(job_rx, event_tx));
+        Self {
+            cache: HashMap::new(),
+            pending: HashSet::new(),
+            scanned: HashMap::new(),
+            job_tx,
+        }
+    }
+
+    fn run_worker(job_rx: Receiver<Vec<(PathBuf, time::SystemTime)>>, event_tx: Sender<AppEvent>) {
+        for job in job_rx {
+            for chunk in job.chunks(MIMETYPES_CHUNK_SIZE) {
+                let paths: Vec<_> = chunk.iter().map(|(path, _)| path.as_path()).collect();
+                let mimetypes = mimetype::get_mimetypes(&paths);
+                let results = chunk
+                    .iter()
+                    .cloned()
+                    .zip(mimetypes)
+                    .map(|((path, modified), mimetype)| (path, modified, mimetype))
+                    .collect();
+                if event_tx.send(AppEvent::Mimetypes(results)).is_err() {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Fills in the MIME types of the entries of `list` known for their current modification
+    /// time, starting to detect the others in the background, unless this was already done for
+    /// the list as it is. Returns `true` if an entry was filled in.
+    pub fn load(&mut self, list: &mut JoshutoDirList) -> bool {
+        if self.scanned.get(list.file_path()) == Some(&list.version()) {
+            return false;
+        }
+        self.scanned
+            .insert(list.file_path().to_path_buf(), list.version());
+        // the types of archive entries aren't detected
+        if list.virtual_paths.is_none() && VfsPath::resolve(list.file_path()).is_archive() {
+            return false;
+        }
+
+        let mut filled = false;
+        let mut job = Vec::new();
+        for entry in list.iter_mut() {
+            if entry.metadata.mimetype.is_some() || !entry.metadata.is_loaded() {
+                continue;
+            }
+            if entry.metadata.is_dir() {
+                entry.metadata.mimetype = Some("inode/directory".to_string());
+                filled = true;
+                continue;
+            }
+            let modified = entry.metadata.modified();
+            match self.cache.get(entry.file_path()) {
+                Some((cached_modified, mimetype)) if *cached_modified == modified => {
+                    entry.metadata.mimetype = mimetype.clone();
+                    filled |= mimetype.is_some();
+                }
+                _ => {
+                    if self.pending.insert(entry.file_path_buf()) {
+                        job.push((entry.file_path_buf(), modified));
+                    }
+                }
+            }
+        }
+        if !job.is_empty() && self.job_tx.send(job).is_err() {
+            self.pending.clear();
+        }
+        filled
+    }
+
+    /// Records types detected in the background.
+    pub fn update(&mut self, results: &[(PathBuf, time::SystemTime, Option<String>)]) {
+        for (path, modified, mimetype) in results {
+            self.pending.remove(path);
+            self.cache
+                .insert(path.clone(), (*modified, mimetype.clone()));
+        }
+    }
+}
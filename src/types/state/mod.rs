@@ -3,10 +3,17 @@
 
 mod app_state;
 mod commandline_state;
+mod cumulative_size_state;
+mod custom_column_state;
+mod dir_settings_state;
 mod file_manager_state;
+mod find_state;
+mod fs_info_state;
+mod git_state;
 mod local_state;
 mod matcher;
 mod message_queue;
+mod mimetype_state;
 mod preview_state;
 mod tab_state;
 mod thread_pool;
@@ -15,10 +22,17 @@ mod worker_state;
 
 pub use app_state::*;
 pub use commandline_state::*;
+pub use cumulative_size_state::*;
+pub use custom_column_state::*;
+pub use dir_settings_state::*;
 pub use file_manager_state::*;
+pub use find_state::*;
+pub use fs_info_state::*;
+pub use git_state::*;
 pub use local_state::*;
 pub use matcher::*;
 pub use message_queue::*;
+pub use mimetype_state::*;
 pub use preview_state::*;
 pub use tab_state::*;
 pub use thread_pool::*;
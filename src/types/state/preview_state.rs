@@ -14,7 +14,9 @@ use ratatui_image::protocol::Protocol;
 use ratatui_image::Resize;
 
 use crate::config::app::AppConfig;
+use crate::fs::VfsPath;
 use crate::preview::preview_file::{FilePreview, PreviewFileState};
+use crate::preview::preview_text::text_preview;
 use crate::types::event::{AppEvent, PreviewData};
 use crate::types::option::preview::PreviewOption;
 use crate::ui::{views, AppBackend, PreviewArea};
@@ -29,8 +31,8 @@ lazy_static! {
 /// Cached/in-progress file preview state, keyed by path.
 type FilePreviewMetadata = HashMap<path::PathBuf, PreviewFileState>;
 
-/// Manages background threads that generate script- and image-based file previews, plus the
-/// cache of their results and the external preview hook state.
+/// Manages background threads that generate script- (or built-in text) and image-based file
+/// previews, plus the cache of their results and the external preview hook state.
 pub struct PreviewState {
     // the last preview area (or None if now preview shown) to check if a preview hook script needs
     // to be called
@@ -38,6 +40,8 @@ pub struct PreviewState {
     // hashmap of cached previews
     pub previews: FilePreviewMetadata,
     pub image_preview: Option<(PathBuf, Box<Protocol>)>,
+    // the entry whose cached preview has been replaced by a `git_diff`
+    pub git_diff: Option<PathBuf>,
     pub sender_script: Sender<(PathBuf, Rect)>,
     pub sender_image: Option<Sender<(PathBuf, Rect)>>,
     // for telling main thread when previews are ready
@@ -46,10 +50,12 @@ pub struct PreviewState {
 
 impl PreviewState {
     /// Spawns the script- and (if `picker` is set) image-preview worker threads and returns
-    /// the resulting `PreviewState`.
+    /// the resulting `PreviewState`. Without a `script`, text files are previewed natively,
+    /// highlighted with the `syntax_theme`.
     pub fn new(
         picker: Option<Picker>,
         script: Option<PathBuf>,
+        syntax_theme: String,
         allmytoes: Option<AMT>,
         xdg_thumb_size: ThumbSize,
         event_tx: Sender<AppEvent>,
@@ -57,14 +63,17 @@ impl PreviewState {
         let (sender_script, receiver) = mpsc::channel::<(PathBuf, Rect)>();
         let thread_script_event_tx = event_tx.clone();
         thread::spawn(move || {
-            if let Some(ref script) = script {
-                for (path, rect) in receiver {
-                    PreviewState::spawn_command(
+            for (path, rect) in receiver {
+                match script {
+                    Some(ref script) => PreviewState::spawn_command(
                         path.clone(),
                         script.to_path_buf(),
                         rect,
                         thread_script_event_tx.clone(),
-                    );
+                    ),
+                    None => {
+                        PreviewState::load_text(path, &syntax_theme, thread_script_event_tx.clone())
+                    }
                 }
             }
         });
@@ -79,15 +88,18 @@ impl PreviewState {
                     .last()
                     .or_else(|| receiver.iter().next())
                 {
+                    let Ok(local_path) = VfsPath::resolve(&path).local_path() else {
+                        continue;
+                    };
                     let thumb_path = if let Some(amt) = &allmytoes {
-                        let thumb_result = amt.get(&path, xdg_thumb_size);
+                        let thumb_result = amt.get(&local_path, xdg_thumb_size);
                         if let Ok(thumb) = thumb_result {
                             PathBuf::from(thumb.path)
                         } else {
-                            path.clone()
+                            local_path
                         }
                     } else {
-                        path.clone()
+                        local_path
                     };
                     let proto = image::ImageReader::open(thumb_path.as_path())
                         .and_then(|reader| reader.decode().map_err(Self::map_io_err))
@@ -115,6 +127,7 @@ impl PreviewState {
             preview_area: None,
             previews: HashMap::new(),
             image_preview: None,
+            git_diff: None,
             sender_script,
             sender_image,
             event_tx,
@@ -142,16 +155,18 @@ impl PreviewState {
         rect: Rect,
         thread_event_tx: Sender<AppEvent>,
     ) {
-        let output = Command::new(script)
-            .stdout(Stdio::piped())
-            .stderr(Stdio::null())
-            .arg("--path")
-            .arg(path.as_path())
-            .arg("--preview-width")
-            .arg(rect.width.to_string())
-            .arg("--preview-height")
-            .arg(rect.height.to_string())
-            .output();
+        let output = VfsPath::resolve(&path).local_path().and_then(|local_path| {
+            Command::new(script)
+                .stdout(Stdio::piped())
+                .stderr(Stdio::null())
+                .arg("--path")
+                .arg(local_path)
+                .arg("--preview-width")
+                .arg(rect.width.to_string())
+                .arg("--preview-height")
+                .arg(rect.height.to_string())
+                .output()
+        });
 
         let res = match output {
             Ok(output) => {
@@ -176,6 +191,14 @@ impl PreviewState {
         let _ = thread_event_tx.send(res);
     }
 
+    fn load_text(path: PathBuf, syntax_theme: &str, thread_event_tx: Sender<AppEvent>) {
+        let res = VfsPath::resolve(&path)
+            .local_path()
+            .and_then(|local_path| text_preview(&local_path, syntax_theme))
+            .map(|preview| PreviewData::Script(Box::new(preview)));
+        let _ = thread_event_tx.send(AppEvent::PreviewFile { path, res });
+    }
+
     /// Returns the cache of script-based file preview state.
     pub fn previews_ref(&self) -> &FilePreviewMetadata {
         &self.previews
@@ -191,6 +214,10 @@ impl PreviewState {
             _ => None,
         }
     }
+    /// Returns whether the cached preview of `path` has been replaced by a `git_diff`.
+    pub fn is_git_diff(&self, path: &path::Path) -> bool {
+        self.git_diff.as_deref() == Some(path)
+    }
     /// Sets (or clears) the currently-loaded image preview.
     pub fn set_image_preview(&mut self, preview: Option<(path::PathBuf, Box<Protocol>)>) {
         self.image_preview = preview;
@@ -7,6 +7,7 @@ use ratatui::text::Span;
 use unicode_width::UnicodeWidthStr;
 
 use crate::config::theme::tab::TabTheme;
+use crate::tab::JoshutoTab;
 use crate::utils::string::UnicodeTruncate;
 use crate::HOME_DIR;
 
@@ -32,13 +33,26 @@ use crate::HOME_DIR;
 //               and further shortened with ellipsis
 
 /// A tab's directory path in both long (full path, `~`-abbreviated) and short (base name) form,
-/// used to fit the tab bar into however much width is available.
+/// used to fit the tab bar into however much width is available. Prefixed with `virtual:` for
+/// a tab showing a virtual listing.
 pub struct TabLabel {
     long: String,
     short: String,
 }
 
 impl TabLabel {
+    fn from_tab(tab: &JoshutoTab) -> TabLabel {
+        let label = Self::from_path(tab.get_cwd());
+        if tab.is_virtual() {
+            TabLabel {
+                long: format!("virtual:{}", label.long),
+                short: format!("virtual:{}", label.short),
+            }
+        } else {
+            label
+        }
+    }
+
     fn from_path(path: &Path) -> TabLabel {
         let mut full_path_str = path.as_os_str().to_str().unwrap_or_default().to_string();
         if let Some(home_dir) = HOME_DIR.as_ref() {
@@ -498,15 +512,15 @@ fn factor_tab_bar_spans_from_sequence<'a>(
         .collect()
 }
 
-/// Builds the styled tab-bar spans for `tab_paths`, choosing long/short labels, scroll tags,
+/// Builds the styled tab-bar spans for `tabs`, choosing long/short labels, scroll tags,
 /// and dividers to best fit `available_width` (see the module-level case breakdown above).
 pub fn factor_tab_bar_spans<'a>(
     available_width: usize,
-    tab_paths: &[&'a Path],
+    tabs: &[&JoshutoTab],
     current_index: usize,
     config: &TabTheme,
 ) -> Vec<Span<'a>> {
-    let reps: Vec<TabLabel> = tab_paths.iter().map(|p| TabLabel::from_path(p)).collect();
+    let reps: Vec<TabLabel> = tabs.iter().map(|tab| TabLabel::from_tab(tab)).collect();
     let rep_refs: Vec<&TabLabel> = reps.iter().collect();
     let tab_bar_elements =
         factor_tab_bar_sequence(available_width, &rep_refs, current_index, config);
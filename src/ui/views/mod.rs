@@ -2,7 +2,9 @@
 //! line) that compose the smaller widgets in `ui::widgets` and drive joshuto's layout math.
 
 mod tui_command_menu;
+mod tui_disk_usage_view;
 mod tui_folder_view;
+mod tui_grep_view;
 mod tui_hsplit_view;
 mod tui_minimal_view;
 mod tui_textfield;
@@ -10,7 +12,9 @@ mod tui_view;
 mod tui_worker_view;
 
 pub use self::tui_command_menu::*;
+pub use self::tui_disk_usage_view::*;
 pub use self::tui_folder_view::*;
+pub use self::tui_grep_view::*;
 pub use self::tui_hsplit_view::*;
 pub use self::tui_textfield::*;
 pub use self::tui_view::*;
@@ -0,0 +1,84 @@
+use std::path::Path;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+
+use crate::fs::DiskUsageNode;
+use crate::types::state::AppState;
+use crate::ui::widgets::TuiDiskUsage;
+use crate::utils::format::file_size_to_string;
+
+/// The `disk_usage` screen: the scanned directory with its totals, its entries, and the current
+/// message, or the scan's progress until it's done.
+pub struct TuiDiskUsageView<'a> {
+    app_state: &'a AppState,
+    path: &'a Path,
+    /// the shown directory with the cursor and scroll position, `None` while still scanning
+    node: Option<(&'a DiskUsageNode, usize, usize)>,
+    /// number of files scanned so far
+    progress: u64,
+}
+
+impl<'a> TuiDiskUsageView<'a> {
+    /// Creates the disk-usage screen showing `path`.
+    pub fn new(
+        app_state: &'a AppState,
+        path: &'a Path,
+        node: Option<(&'a DiskUsageNode, usize, usize)>,
+        progress: u64,
+    ) -> Self {
+        Self {
+            app_state,
+            path,
+            node,
+            progress,
+        }
+    }
+}
+
+impl Widget for TuiDiskUsageView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 3 {
+            return;
+        }
+
+        let header_style = Style::default()
+            .fg(Color::LightBlue)
+            .add_modifier(Modifier::BOLD);
+        let header = match self.node {
+            Some((node, _, _)) => format!(
+                "{}  {} in {} files",
+                self.path.to_string_lossy(),
+                file_size_to_string(node.size).trim(),
+                node.files
+            ),
+            None => format!(
+                "Scanning {}... {} files",
+                self.path.to_string_lossy(),
+                self.progress
+            ),
+        };
+        buf.set_stringn(area.x, area.y, header, area.width as usize, header_style);
+
+        if let Some((node, index, skip)) = self.node {
+            let rect = Rect {
+                y: area.y + 1,
+                height: area.height - 2,
+                ..area
+            };
+            TuiDiskUsage::new(node, index, skip).render(rect, buf);
+        }
+
+        if let Some(msg) = self.app_state.state.message_queue_ref().current_message() {
+            buf.set_stringn(
+                area.x,
+                area.bottom() - 1,
+                msg.content.as_str(),
+                area.width as usize,
+                msg.style,
+            );
+        }
+    }
+}
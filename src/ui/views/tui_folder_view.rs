@@ -3,7 +3,7 @@ use ratatui::layout::{Constraint, Direction, Layout, Position, Rect};
 use ratatui::style::{Color, Style};
 use ratatui::symbols::line::{HORIZONTAL_DOWN, HORIZONTAL_UP};
 use ratatui::text::Span;
-use ratatui::widgets::{Block, Borders, Paragraph, Widget, Wrap};
+use ratatui::widgets::{Block, Borders, Clear, Paragraph, Widget, Wrap};
 use ratatui_image::Image;
 
 use crate::fs::FileType;
@@ -136,14 +136,18 @@ impl Widget for TuiFolderView<'_> {
         let display_options = &self.app_state.config.display_options;
 
         let preview_state = self.app_state.state.preview_state_ref();
+        let git_state = self.app_state.state.git_state_ref();
         let curr_tab = self.app_state.state.tab_state_ref().curr_tab_ref();
         let curr_tab_cwd = curr_tab.get_cwd();
 
         let curr_list = curr_tab.curr_list_ref();
-        let child_list = curr_tab.child_list_ref();
-
         let curr_entry = curr_list.and_then(|c| c.curr_entry_ref());
 
+        // a `git_diff` of a directory is shown in place of its listing
+        let child_list = curr_tab
+            .child_list_ref()
+            .filter(|_| !curr_entry.is_some_and(|e| preview_state.is_git_diff(e.file_path())));
+
         let constraints = get_constraints(self.app_state);
 
         let layout_rect = if display_options.show_borders {
@@ -161,7 +165,9 @@ impl Widget for TuiFolderView<'_> {
             Constraint::Ratio(0, _) => {}
             _ => {
                 if let Some(list) = curr_tab.parent_list_ref().as_ref() {
-                    TuiDirList::new(&self.app_state.config, list, true).render(layout_rect[0], buf);
+                    let git_status = git_state.repo_ref(list.file_path());
+                    TuiDirList::new(&self.app_state.config, list, git_status, true)
+                        .render(layout_rect[0], buf);
                 }
             }
         }
@@ -173,6 +179,7 @@ impl Widget for TuiFolderView<'_> {
                 list,
                 display_options,
                 curr_tab.option_ref(),
+                git_state.repo_ref(list.file_path()),
                 true,
             )
             .render(layout_rect[1], buf);
@@ -193,13 +200,33 @@ impl Widget for TuiFolderView<'_> {
                         .wrap(Wrap { trim: true })
                         .render(footer_area, buf);
                 } else {
-                    TuiFooter::new(list, curr_tab.option_ref()).render(footer_area, buf);
+                    TuiFooter::new(
+                        list,
+                        curr_tab.option_ref(),
+                        git_state.repo_ref(list.file_path()),
+                        self.app_state.state.fs_info_state_ref().info(),
+                        self.app_state.config.display_options.fs_usage_warning,
+                    )
+                    .render(footer_area, buf);
+                    if let Some(PreviewDirState::Loading { count, .. }) =
+                        curr_tab.history_metadata_ref().get(curr_tab_cwd)
+                    {
+                        let loading = TuiDirListLoading::new(*count);
+                        let width = loading.width().min(footer_area.width);
+                        let area = Rect {
+                            x: footer_area.right() - width,
+                            width,
+                            ..footer_area
+                        };
+                        Clear.render(area, buf);
+                        loading.render(area, buf);
+                    }
                 }
             }
         } else {
             match curr_tab.history_metadata_ref().get(curr_tab_cwd) {
-                Some(PreviewDirState::Loading) => {
-                    TuiDirListLoading::new().render(layout_rect[1], buf);
+                Some(PreviewDirState::Loading { count, .. }) => {
+                    TuiDirListLoading::new(*count).render(layout_rect[1], buf);
                 }
                 Some(PreviewDirState::Error { message }) => {
                     TuiMessage::new(message, Style::default().fg(Color::Red))
@@ -210,11 +237,13 @@ impl Widget for TuiFolderView<'_> {
         }
 
         if let Some(list) = child_list.as_ref() {
-            TuiDirList::new(&self.app_state.config, list, true).render(layout_rect[2], buf);
+            let git_status = git_state.repo_ref(list.file_path());
+            TuiDirList::new(&self.app_state.config, list, git_status, true)
+                .render(layout_rect[2], buf);
         } else if let Some(entry) = curr_entry {
             match curr_tab.history_metadata_ref().get(entry.file_path()) {
-                Some(PreviewDirState::Loading) => {
-                    TuiDirListLoading::new().render(layout_rect[2], buf);
+                Some(PreviewDirState::Loading { count, .. }) => {
+                    TuiDirListLoading::new(*count).render(layout_rect[2], buf);
                 }
                 Some(PreviewDirState::Error { message }) => {
                     TuiMessage::new(message, Style::default().fg(Color::Red))
@@ -381,11 +410,13 @@ pub fn calculate_preview(
 ) -> Option<PreviewArea> {
     let curr_tab = tab_state.curr_tab_ref();
 
-    let child_list = curr_tab.child_list_ref();
-
     let curr_list = curr_tab.curr_list_ref();
     let curr_entry = curr_list.and_then(|c| c.curr_entry_ref());
 
+    let child_list = curr_tab
+        .child_list_ref()
+        .filter(|_| !curr_entry.is_some_and(|e| preview_state.is_git_diff(e.file_path())));
+
     if child_list.as_ref().is_some() {
         None
     } else if let Some(entry) = curr_entry {
@@ -0,0 +1,108 @@
+use std::path::Path;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::{Constraint, Direction, Layout, Rect};
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::{Block, Borders, Widget};
+use regex::Regex;
+
+use crate::fs::GrepMatch;
+use crate::types::state::AppState;
+use crate::ui::widgets::{TuiGrepContext, TuiGrepResults};
+use crate::THEME_T;
+
+/// The `grep` screen: the search with its progress, the matches found, the lines around the
+/// highlighted match, and the current message.
+pub struct TuiGrepView<'a> {
+    app_state: &'a AppState,
+    root: &'a Path,
+    regex: &'a Regex,
+    matches: &'a [GrepMatch],
+    index: usize,
+    skip: usize,
+    /// the lines around the highlighted match, with their numbers
+    context: &'a [(usize, String)],
+    /// number of files searched so far
+    files: u64,
+    done: bool,
+}
+
+impl<'a> TuiGrepView<'a> {
+    /// Creates the grep screen for the search for `regex` below `root`.
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        app_state: &'a AppState,
+        root: &'a Path,
+        regex: &'a Regex,
+        matches: &'a [GrepMatch],
+        index: usize,
+        skip: usize,
+        context: &'a [(usize, String)],
+        files: u64,
+        done: bool,
+    ) -> Self {
+        Self {
+            app_state,
+            root,
+            regex,
+            matches,
+            index,
+            skip,
+            context,
+            files,
+            done,
+        }
+    }
+}
+
+impl Widget for TuiGrepView<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.height < 3 {
+            return;
+        }
+
+        let header_style = Style::default()
+            .fg(Color::LightBlue)
+            .add_modifier(Modifier::BOLD);
+        let header = format!(
+            "{} /{}/  {} matches in {} files{}",
+            self.root.to_string_lossy(),
+            self.regex.as_str(),
+            self.matches.len(),
+            self.files,
+            if self.done { "" } else { "..." }
+        );
+        buf.set_stringn(area.x, area.y, header, area.width as usize, header_style);
+
+        let body = Rect {
+            y: area.y + 1,
+            height: area.height - 2,
+            ..area
+        };
+        let layout = Layout::default()
+            .direction(Direction::Horizontal)
+            .constraints([Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)])
+            .split(body);
+
+        TuiGrepResults::new(self.root, self.matches, self.index, self.skip).render(layout[0], buf);
+
+        if let Some(m) = self.matches.get(self.index) {
+            let block = Block::default()
+                .borders(Borders::LEFT)
+                .border_style(THEME_T.border.as_style());
+            let inner = block.inner(layout[1]);
+            block.render(layout[1], buf);
+            TuiGrepContext::new(self.context, m.line, self.regex).render(inner, buf);
+        }
+
+        if let Some(msg) = self.app_state.state.message_queue_ref().current_message() {
+            buf.set_stringn(
+                area.x,
+                area.bottom() - 1,
+                msg.content.as_str(),
+                area.width as usize,
+                msg.style,
+            );
+        }
+    }
+}
@@ -30,6 +30,7 @@ impl Widget for TuiHSplitView<'_> {
         let tab_state = self.app_state.state.tab_state_ref();
 
         let display_options = &self.app_state.config.display_options;
+        let git_state = self.app_state.state.git_state_ref();
         let constraints = &[Constraint::Ratio(1, 2), Constraint::Ratio(1, 2)];
 
         let layout_rect = if display_options.show_borders {
@@ -92,6 +93,7 @@ impl Widget for TuiHSplitView<'_> {
                     list,
                     display_options,
                     curr_tab.option_ref(),
+                    git_state.repo_ref(list.file_path()),
                     true,
                 )
                 .render(layout_rect, buf);
@@ -118,7 +120,14 @@ impl Widget for TuiHSplitView<'_> {
                             .wrap(Wrap { trim: true })
                             .render(rect, buf);
                     } else {
-                        TuiFooter::new(list, curr_tab.option_ref()).render(rect, buf);
+                        TuiFooter::new(
+                            list,
+                            curr_tab.option_ref(),
+                            git_state.repo_ref(list.file_path()),
+                            self.app_state.state.fs_info_state_ref().info(),
+                            self.app_state.config.display_options.fs_usage_warning,
+                        )
+                        .render(rect, buf);
                     }
                 }
             }
@@ -156,6 +165,7 @@ impl Widget for TuiHSplitView<'_> {
                         list,
                         display_options,
                         curr_tab.option_ref(),
+                        git_state.repo_ref(list.file_path()),
                         false,
                     )
                     .render(layout_rect, buf);
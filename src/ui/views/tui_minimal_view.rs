@@ -28,6 +28,7 @@ impl Widget for TuiMinimalView<'_> {
         let tab_state = self.app_state.state.tab_state_ref();
 
         let display_options = &self.app_state.config.display_options;
+        let git_state = self.app_state.state.git_state_ref();
         let constraints = &[Constraint::Ratio(1, 1)];
 
         let layout_rect = {
@@ -52,6 +53,7 @@ impl Widget for TuiMinimalView<'_> {
                     list,
                     display_options,
                     curr_tab.option_ref(),
+                    git_state.repo_ref(list.file_path()),
                     true,
                 )
                 .render(layout_rect, buf);
@@ -78,7 +80,14 @@ impl Widget for TuiMinimalView<'_> {
                             .wrap(Wrap { trim: true })
                             .render(rect, buf);
                     } else {
-                        TuiFooter::new(list, curr_tab.option_ref()).render(rect, buf);
+                        TuiFooter::new(
+                            list,
+                            curr_tab.option_ref(),
+                            git_state.repo_ref(list.file_path()),
+                            self.app_state.state.fs_info_state_ref().info(),
+                            self.app_state.config.display_options.fs_usage_warning,
+                        )
+                        .render(rect, buf);
                     }
                 }
             }
@@ -26,7 +26,7 @@ impl Widget for TuiView<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let display_options = &self.app_state.config.display_options;
         match display_options.mode {
-            DisplayMode::Default => {
+            DisplayMode::Default | DisplayMode::Tree => {
                 TuiFolderView::new(self.app_state).render(area, buf);
             }
             DisplayMode::Minimal => {
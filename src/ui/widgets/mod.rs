@@ -4,8 +4,10 @@
 mod tui_dirlist;
 mod tui_dirlist_detailed;
 mod tui_dirlist_loading;
+mod tui_disk_usage;
 mod tui_file_preview;
 mod tui_footer;
+mod tui_grep;
 mod tui_help;
 mod tui_io_tasks;
 mod tui_menu;
@@ -17,8 +19,10 @@ mod tui_topbar;
 pub use self::tui_dirlist::TuiDirList;
 pub use self::tui_dirlist_detailed::{trim_file_label, TuiDirListDetailed};
 pub use self::tui_dirlist_loading::TuiDirListLoading;
+pub use self::tui_disk_usage::TuiDiskUsage;
 pub use self::tui_file_preview::TuiFilePreview;
 pub use self::tui_footer::TuiFooter;
+pub use self::tui_grep::{TuiGrepContext, TuiGrepResults};
 pub use self::tui_help::{get_keymap_table, TuiHelp};
 pub use self::tui_io_tasks::TuiIoTasks;
 pub use self::tui_menu::TuiMenu;
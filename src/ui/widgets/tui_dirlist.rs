@@ -5,25 +5,35 @@ use ratatui::widgets::Widget;
 use unicode_width::UnicodeWidthStr;
 
 use crate::config::app::AppConfig;
+use crate::fs::{tree_name, JoshutoDirEntry, JoshutoDirList};
 use crate::fs::{FileType, JoshutoMetadata};
-use crate::fs::{JoshutoDirEntry, JoshutoDirList};
+use crate::git::GitRepoStatus;
 use crate::ui::widgets::trim_file_label;
 use crate::utils::style;
+use crate::THEME_T;
 
 /// Renders a simple (non-detailed) directory listing: one entry name per line, current entry
 /// reverse-highlighted when `focused`. Used for the parent and preview panes.
 pub struct TuiDirList<'a> {
     pub config: &'a AppConfig,
     pub dirlist: &'a JoshutoDirList,
+    pub git_status: Option<&'a GitRepoStatus>,
     pub focused: bool,
 }
 
 impl<'a> TuiDirList<'a> {
-    /// Creates a directory-list widget for `dirlist`.
-    pub fn new(config: &'a AppConfig, dirlist: &'a JoshutoDirList, focused: bool) -> Self {
+    /// Creates a directory-list widget for `dirlist`, styling entries by `git_status` if the
+    /// directory is in a repository.
+    pub fn new(
+        config: &'a AppConfig,
+        dirlist: &'a JoshutoDirList,
+        git_status: Option<&'a GitRepoStatus>,
+        focused: bool,
+    ) -> Self {
         Self {
             config,
             dirlist,
+            git_status,
             focused,
         }
     }
@@ -58,23 +68,45 @@ impl Widget for TuiDirList<'_> {
             .for_each(|(i, entry)| {
                 let ix = skip_dist + i;
 
+                let git_status = self
+                    .git_status
+                    .and_then(|repo| repo.status_of(entry.file_path()));
                 let style = if !self.focused {
-                    style::entry_style(self.config, entry)
+                    style::entry_style(self.config, entry, git_status)
                 } else if ix == curr_index {
-                    style::entry_style(self.config, entry).add_modifier(Modifier::REVERSED)
+                    style::entry_style(self.config, entry, git_status)
+                        .add_modifier(Modifier::REVERSED)
                 } else {
-                    style::entry_style(self.config, entry)
+                    style::entry_style(self.config, entry, git_status)
                 };
 
                 buf.set_string(x, y + i as u16, space_fill.as_str(), style);
+                if let Some(tag) = entry.tag() {
+                    let tag_style = style.patch(THEME_T.tag.as_style());
+                    buf.set_string(x, y + i as u16, tag.to_string(), tag_style);
+                }
+
+                let guides = if self.config.display_options.mode.is_tree() {
+                    self.dirlist.tree_guides(ix)
+                } else {
+                    ""
+                };
+                let guides_width = guides.width().min(drawing_width - 1);
+                buf.set_stringn(
+                    x + 1,
+                    y + i as u16,
+                    guides,
+                    drawing_width - 1,
+                    Style::default(),
+                );
 
                 print_entry(
                     self.config,
                     buf,
                     entry,
                     style,
-                    (x + 1, y + i as u16),
-                    drawing_width - 1,
+                    (x + 1 + guides_width as u16, y + i as u16),
+                    drawing_width - 1 - guides_width,
                 );
             });
     }
@@ -88,7 +120,11 @@ fn print_entry(
     (x, y): (u16, u16),
     drawing_width: usize,
 ) {
-    let name = entry.file_name();
+    let name = if config.display_options.mode.is_tree() {
+        tree_name(entry)
+    } else {
+        entry.file_name()
+    };
     #[cfg(feature = "devicons")]
     let (label, label_width) = {
         if config.display_options.show_icons {
@@ -6,14 +6,16 @@ use ratatui::style::{Color, Modifier, Style};
 use ratatui::widgets::Widget;
 
 use crate::config::app::AppConfig;
-use crate::fs::{FileType, JoshutoDirEntry, JoshutoDirList, LinkType};
+use crate::fs::{tree_name, FileType, JoshutoDirEntry, JoshutoDirList, LinkType};
+use crate::git::{GitRepoStatus, GitStatus};
 use crate::tab::TabDisplayOption;
 use crate::types::option::display::DisplayOption;
-use crate::types::option::line_mode::{LineMode, LineModeArgs, LineNumberStyle};
+use crate::types::option::line_mode::{ColumnAlignment, LineMode, LineModeArgs, LineNumberStyle};
 use crate::utils::format::time_to_string;
 use crate::utils::string::UnicodeTruncate;
 use crate::utils::style;
 use crate::utils::{format, unix};
+use crate::THEME_T;
 use unicode_width::UnicodeWidthStr;
 
 use super::tui_dirlist::get_entry_icon;
@@ -23,21 +25,25 @@ const MIN_LEFT_LABEL_WIDTH: i32 = 15;
 const ELLIPSIS: &str = "…";
 
 /// Renders the main directory listing pane: entry names with icons, line numbers, and
-/// per-entry linemode metadata (size, times, owner, permissions) right-aligned.
+/// per-entry linemode metadata (size, times, owner, permissions, ...) right-aligned in columns
+/// sized to their content.
 pub struct TuiDirListDetailed<'a> {
     pub config: &'a AppConfig,
     pub dirlist: &'a JoshutoDirList,
     pub display_options: &'a DisplayOption,
     pub tab_display_options: &'a TabDisplayOption,
+    pub git_status: Option<&'a GitRepoStatus>,
     pub focused: bool,
 }
 impl<'a> TuiDirListDetailed<'a> {
-    /// Creates a detailed directory-list widget for `dirlist`.
+    /// Creates a detailed directory-list widget for `dirlist`, showing and styling entries by
+    /// `git_status` if the directory is in a repository.
     pub fn new(
         config: &'a AppConfig,
         dirlist: &'a JoshutoDirList,
         display_options: &'a DisplayOption,
         tab_display_options: &'a TabDisplayOption,
+        git_status: Option<&'a GitRepoStatus>,
         focused: bool,
     ) -> Self {
         Self {
@@ -45,6 +51,7 @@ impl<'a> TuiDirListDetailed<'a> {
             dirlist,
             display_options,
             tab_display_options,
+            git_status,
             focused,
         }
     }
@@ -79,24 +86,64 @@ impl Widget for TuiDirListDetailed<'_> {
 
         let space_fill = " ".repeat(drawing_width);
 
-        // draw every entry
-        self.dirlist
+        let linemode = &self.tab_display_options.linemode;
+        let rows: Vec<_> = self
+            .dirlist
             .iter()
             .skip(skip_dist)
-            .enumerate()
             .take(area.height as usize)
-            .for_each(|(i, entry)| {
-                let ix = skip_dist + i;
+            .map(|entry| {
+                let git_status = self
+                    .git_status
+                    .and_then(|repo| repo.status_of(entry.file_path()));
+                let cells = line_mode_cells(linemode, entry, git_status);
+                (entry, git_status, cells)
+            })
+            .collect();
+        // size every column to its widest value on screen, unless configured otherwise
+        let columns: Vec<(usize, bool)> = linemode
+            .mode
+            .iter()
+            .enumerate()
+            .map(|(col, arg)| {
+                let custom = match arg {
+                    LineModeArgs::Custom(name) => self.display_options.custom_column(name),
+                    _ => None,
+                };
+                let width = custom.and_then(|c| c.width).unwrap_or_else(|| {
+                    rows.iter()
+                        .map(|(_, _, cells)| cells[col].width())
+                        .max()
+                        .unwrap_or(0)
+                });
+                let right_aligned = match custom {
+                    Some(custom) => custom.align == ColumnAlignment::Right,
+                    None => arg.is_numeric(),
+                };
+                (width, right_aligned)
+            })
+            .collect();
 
+        // draw every entry
+        rows.iter()
+            .enumerate()
+            .for_each(|(i, (entry, git_status, cells))| {
+                let ix = skip_dist + i;
+                let git_status = *git_status;
                 let style = if !self.focused {
-                    style::entry_style(self.config, entry)
+                    style::entry_style(self.config, entry, git_status)
                 } else if ix == curr_index {
-                    style::entry_style(self.config, entry).add_modifier(Modifier::REVERSED)
+                    style::entry_style(self.config, entry, git_status)
+                        .add_modifier(Modifier::REVERSED)
                 } else {
-                    style::entry_style(self.config, entry)
+                    style::entry_style(self.config, entry, git_status)
                 };
 
                 buf.set_string(x, y + i as u16, space_fill.as_str(), style);
+                if let Some(tag) = entry.tag() {
+                    let tag_style = style.patch(THEME_T.tag.as_style());
+                    buf.set_string(x, y + i as u16, tag.to_string(), tag_style);
+                }
 
                 let mut prefix = style::entry_prefix(entry).to_string();
                 let line_number_prefix = match line_num_style {
@@ -110,6 +157,9 @@ impl Widget for TuiDirListDetailed<'_> {
                     ),
                 };
                 prefix.push_str(&line_number_prefix);
+                if self.display_options.mode.is_tree() {
+                    prefix.push_str(self.dirlist.tree_guides(ix));
+                }
 
                 print_entry(
                     self.config,
@@ -117,7 +167,7 @@ impl Widget for TuiDirListDetailed<'_> {
                     entry,
                     style,
                     (x + 1, y + i as u16),
-                    self.tab_display_options.linemode,
+                    &format_line_mode(cells, &columns),
                     drawing_width - 1,
                     &prefix,
                 );
@@ -139,22 +189,65 @@ fn get_entry_size_string(entry: &JoshutoDirEntry) -> String {
     }
 }
 
-fn display_line_mode(mode: LineMode, entry: &JoshutoDirEntry) -> String {
+/// Returns the text of each `mode` column for `entry`, all empty until its metadata is loaded.
+fn line_mode_cells(
+    mode: &LineMode,
+    entry: &JoshutoDirEntry,
+    git_status: Option<GitStatus>,
+) -> Vec<String> {
     let metadata = &entry.metadata;
+    if !metadata.is_loaded() {
+        return vec![String::new(); mode.mode.len()];
+    }
 
     mode.mode
         .iter()
-        .take(mode.size)
         .map(|arg| match arg {
             LineModeArgs::Size => get_entry_size_string(entry),
             LineModeArgs::ModifyTime => time_to_string(metadata.modified()),
             LineModeArgs::AccessTime => time_to_string(metadata.accessed()),
+            LineModeArgs::ChangeTime => time_to_string(metadata.changed()),
+            LineModeArgs::BirthTime => metadata.created().map(time_to_string).unwrap_or_default(),
             LineModeArgs::User => unix::uid_to_string(metadata.uid).unwrap_or("unknown".into()),
             LineModeArgs::Group => unix::gid_to_string(metadata.gid).unwrap_or("unknown".into()),
             LineModeArgs::Permission => unix::mode_to_char_array(metadata.mode, metadata.file_type)
                 .iter()
                 .collect(),
-            LineModeArgs::Null => unreachable!(),
+            LineModeArgs::OctalPermission => format!("{:04o}", metadata.mode.bits() & 0o7777),
+            LineModeArgs::Inode => metadata.inode.to_string(),
+            LineModeArgs::Links => metadata.nlink.to_string(),
+            LineModeArgs::ItemCount => metadata
+                .directory_size()
+                .map(|n| n.to_string())
+                .unwrap_or_default(),
+            LineModeArgs::LinkTarget => match metadata.link_type() {
+                LinkType::Symlink { target, .. } => target.clone(),
+                LinkType::Normal => String::new(),
+            },
+            LineModeArgs::MimeType => metadata.mimetype.clone().unwrap_or_default(),
+            LineModeArgs::GitStatus => git_status.map_or(" ", |s| s.symbol()).to_string(),
+            LineModeArgs::Custom(name) => metadata
+                .custom_columns
+                .get(name)
+                .cloned()
+                .unwrap_or_default(),
+        })
+        .collect()
+}
+
+/// Joins `cells` into one line, fitting each to its column's `(width, right_aligned)`.
+fn format_line_mode(cells: &[String], columns: &[(usize, bool)]) -> String {
+    cells
+        .iter()
+        .zip(columns)
+        .map(|(cell, (width, right_aligned))| {
+            let cell = cell.trunc(*width);
+            let padding = " ".repeat(width.saturating_sub(cell.width()));
+            if *right_aligned {
+                padding + &cell
+            } else {
+                cell + &padding
+            }
         })
         .collect::<Vec<String>>()
         .join(" ")
@@ -167,7 +260,7 @@ fn print_entry(
     entry: &JoshutoDirEntry,
     style: Style,
     (x, y): (u16, u16),
-    linemode: LineMode,
+    line_mode_str: &str,
     drawing_width: usize,
     prefix: &str,
 ) {
@@ -176,7 +269,11 @@ fn print_entry(
         LinkType::Symlink { .. } => "-> ",
     };
 
-    let name = entry.file_name();
+    let name = if config.display_options.mode.is_tree() {
+        tree_name(entry)
+    } else {
+        entry.file_name()
+    };
     #[cfg(feature = "devicons")]
     let label = {
         if config.display_options.show_icons {
@@ -191,8 +288,7 @@ fn print_entry(
     let label = name.to_string();
 
     let left_label_original = label;
-    let right_label_original =
-        format!(" {}{} ", symlink_string, display_line_mode(linemode, entry));
+    let right_label_original = format!(" {}{} ", symlink_string, line_mode_str);
 
     // draw prefix first
     let prefix_width = prefix.width();
@@ -411,3 +507,32 @@ mod test_trim_file_label {
         assert_eq!("🌕🌕…".to_string(), trim_file_label(left, 5));
     }
 }
+
+#[cfg(test)]
+mod test_format_line_mode {
+    use super::format_line_mode;
+    use crate::types::option::line_mode::LineMode;
+
+    #[test]
+    fn numeric_columns_are_right_aligned_and_text_columns_left_aligned() {
+        let cells = ["4 KB".to_string(), "me".to_string(), "12".to_string()];
+        let columns = [(6, true), (4, false), (4, true)];
+        assert_eq!("  4 KB me     12", format_line_mode(&cells, &columns));
+    }
+
+    #[test]
+    fn values_wider_than_a_fixed_width_column_are_cut() {
+        let cells = ["1920x1080".to_string()];
+        assert_eq!("1920x", format_line_mode(&cells, &[(5, false)]));
+    }
+
+    #[test]
+    fn any_number_of_columns_can_be_shown() {
+        let mode = LineMode::from_string(
+            "size|mtime|atime|ctime|btime|user|group|perm|octal|inode|links|count|target|mime|git",
+        )
+        .unwrap();
+        assert_eq!(mode.mode.len(), 15);
+        assert_eq!(mode.as_string().split(" | ").count(), 15);
+    }
+}
@@ -3,13 +3,29 @@ use ratatui::layout::Rect;
 use ratatui::style::{Color, Style};
 use ratatui::widgets::Widget;
 
-/// Placeholder widget shown in place of a directory pane while its listing is still loading.
-pub struct TuiDirListLoading;
+/// Indicator shown for a directory listing that is still loading: in place of the directory
+/// pane until its first entries arrive, and in the footer while the rest stream in.
+pub struct TuiDirListLoading {
+    count: usize,
+}
 
 impl TuiDirListLoading {
-    /// Creates the loading-placeholder widget.
-    pub fn new() -> Self {
-        Self {}
+    /// Creates the loading indicator for a listing with `count` entries read so far.
+    pub fn new(count: usize) -> Self {
+        Self { count }
+    }
+
+    fn text(&self) -> String {
+        if self.count == 0 {
+            "loading...".to_string()
+        } else {
+            format!("loading... {} entries", self.count)
+        }
+    }
+
+    /// Returns the number of columns the indicator takes up.
+    pub fn width(&self) -> u16 {
+        self.text().len() as u16
     }
 }
 
@@ -22,6 +38,6 @@ impl Widget for TuiDirListLoading {
         let y = area.top();
 
         let style = Style::default().fg(Color::Yellow);
-        buf.set_stringn(x, y, "loading...", area.width as usize, style);
+        buf.set_stringn(x, y, self.text(), area.width as usize, style);
     }
 }
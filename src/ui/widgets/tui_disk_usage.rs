@@ -0,0 +1,81 @@
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+
+use crate::fs::DiskUsageNode;
+use crate::utils::format::file_size_to_string;
+use crate::THEME_T;
+
+const BAR_WIDTH: usize = 10;
+
+/// The entries of a directory scanned by `disk_usage`: size, percentage bar, file count, and
+/// name of each child, largest first.
+pub struct TuiDiskUsage<'a> {
+    node: &'a DiskUsageNode,
+    index: usize,
+    skip: usize,
+}
+
+impl<'a> TuiDiskUsage<'a> {
+    /// Creates the widget for `node`'s children, scrolled past the first `skip` of them and
+    /// highlighting the one at `index`.
+    pub fn new(node: &'a DiskUsageNode, index: usize, skip: usize) -> Self {
+        Self { node, index, skip }
+    }
+}
+
+impl Widget for TuiDiskUsage<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 1 {
+            return;
+        }
+        let x = area.left();
+        let y = area.top();
+
+        if self.node.children.is_empty() {
+            let style = Style::default().bg(Color::Red).fg(Color::White);
+            buf.set_stringn(x, y, "empty", area.width as usize, style);
+            return;
+        }
+
+        let drawing_width = area.width as usize;
+        let space_fill = " ".repeat(drawing_width);
+        let total = self.node.size.max(1) as f64;
+
+        for (i, child) in self
+            .node
+            .children
+            .iter()
+            .enumerate()
+            .skip(self.skip)
+            .take(area.height as usize)
+        {
+            let ratio = child.size as f64 / total;
+            let filled = (ratio * BAR_WIDTH as f64).round() as usize;
+            let bar = format!("{:<BAR_WIDTH$}", "#".repeat(filled.min(BAR_WIDTH)));
+            let (name, style) = if child.is_dir {
+                (format!("{}/", child.name), THEME_T.directory.as_style())
+            } else {
+                (child.name.clone(), THEME_T.regular.as_style())
+            };
+            let style = if i == self.index {
+                style.add_modifier(Modifier::REVERSED)
+            } else {
+                style
+            };
+            let line = format!(
+                " {:>7} {:>5.1}% [{}] {:>8} {}",
+                file_size_to_string(child.size).trim(),
+                ratio * 100.0,
+                bar,
+                child.files,
+                name
+            );
+
+            let line_y = y + (i - self.skip) as u16;
+            buf.set_string(x, line_y, space_fill.as_str(), style);
+            buf.set_stringn(x, line_y, line, drawing_width, style);
+        }
+    }
+}
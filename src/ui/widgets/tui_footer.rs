@@ -1,32 +1,77 @@
 use ratatui::buffer::Buffer;
-use ratatui::layout::Rect;
+use ratatui::layout::{Alignment, Rect};
 use ratatui::style::{Color, Style};
 use ratatui::text::{Line, Span};
 use ratatui::widgets::{Paragraph, Widget};
 
 use crate::fs::{JoshutoDirList, LinkType};
+use crate::git::GitRepoStatus;
 use crate::tab::TabDisplayOption;
 use crate::utils::format;
+use crate::utils::fs_info::FsInfo;
 use crate::utils::unix;
 use crate::{THEME_T, TIMEZONE_STR};
 
 /// The bottom status line: current entry's permissions, owner, group, position, mtime, size,
-/// active flat/filter indicators, and selection count.
+/// active flat/filter/hidden/ignored indicators, sort order, selection count, and git branch, with the free
+/// space and mount of the current filesystem on the right. Links show every hop to their final
+/// target.
 pub struct TuiFooter<'a> {
     dirlist: &'a JoshutoDirList,
     tab_options: &'a TabDisplayOption,
+    git_status: Option<&'a GitRepoStatus>,
+    fs_info: Option<&'a FsInfo>,
+    fs_usage_warning: u8,
 }
 
 impl<'a> TuiFooter<'a> {
-    /// Creates the footer widget for the given directory listing and tab options.
-    pub fn new(dirlist: &'a JoshutoDirList, tab_options: &'a TabDisplayOption) -> Self {
+    /// Creates the footer widget for the given directory listing and tab options, showing the
+    /// branch of `git_status` if the directory is in a repository. The free space of `fs_info`
+    /// is highlighted once its usage reaches `fs_usage_warning` percent.
+    pub fn new(
+        dirlist: &'a JoshutoDirList,
+        tab_options: &'a TabDisplayOption,
+        git_status: Option<&'a GitRepoStatus>,
+        fs_info: Option<&'a FsInfo>,
+        fs_usage_warning: u8,
+    ) -> Self {
         Self {
             dirlist,
             tab_options,
+            git_status,
+            fs_info,
+            fs_usage_warning,
         }
     }
 }
 
+impl TuiFooter<'_> {
+    /// Formats e.g. `12.3G/100G free  /home btrfs`, with the free space highlighted when low.
+    fn fs_info_line(&self, fs_info: &FsInfo) -> Line<'static> {
+        let space_style = if fs_info.usage_percent() >= self.fs_usage_warning {
+            THEME_T.fs_warning
+        } else {
+            Style::default()
+        };
+        let mut spans = vec![Span::styled(
+            format!(
+                " {}/{} free",
+                format::file_size_to_string(fs_info.available).trim(),
+                format::file_size_to_string(fs_info.total).trim()
+            ),
+            space_style,
+        )];
+        if !fs_info.fs_type.is_empty() {
+            spans.push(Span::raw(format!(
+                "  {} {}",
+                fs_info.mount_point.to_string_lossy(),
+                fs_info.fs_type
+            )));
+        }
+        Line::from(spans)
+    }
+}
+
 impl Widget for TuiFooter<'_> {
     fn render(self, area: Rect, buf: &mut Buffer) {
         let visual_mode_style = Style::default().fg(Color::Black).bg(Color::LightRed);
@@ -42,24 +87,62 @@ impl Widget for TuiFooter<'_> {
             .bg(THEME_T.selection.bg)
             .add_modifier(THEME_T.selection.modifier);
         let selected_count = self.dirlist.selected_count();
+        let branch_style = THEME_T.git.branch;
+
+        // the filesystem is shown even without an entry, e.g. in empty directories
+        let area = match self.fs_info {
+            Some(fs_info) => {
+                let fs_line = self.fs_info_line(fs_info);
+                let width = (fs_line.width() as u16).min(area.width);
+                let fs_area = Rect {
+                    x: area.right() - width,
+                    width,
+                    ..area
+                };
+                Paragraph::new(fs_line)
+                    .alignment(Alignment::Right)
+                    .render(fs_area, buf);
+                Rect {
+                    width: area.width - width,
+                    ..area
+                }
+            }
+            None => area,
+        };
 
         match self.dirlist.get_index() {
             Some(i) if i < self.dirlist.len() => {
                 let entry = &self.dirlist.contents[i];
 
-                let mode_str: String =
-                    unix::mode_to_char_array(entry.metadata.mode, entry.metadata.file_type)
-                        .iter()
-                        .collect();
-
-                let user_str = unix::uid_to_string(entry.metadata.uid).unwrap_or("unknown".into());
-                let group_str = unix::gid_to_string(entry.metadata.gid).unwrap_or("unknown".into());
-
-                let mtime_str = format::time_to_string(entry.metadata.modified());
-                let size_str = format::file_size_to_string(entry.metadata.len());
+                let (mode_str, user_str, group_str, mtime_str, size_str) =
+                    if entry.metadata.is_loaded() {
+                        (
+                            unix::mode_to_char_array(entry.metadata.mode, entry.metadata.file_type)
+                                .iter()
+                                .collect::<String>(),
+                            unix::uid_to_string(entry.metadata.uid).unwrap_or("unknown".into()),
+                            unix::gid_to_string(entry.metadata.gid).unwrap_or("unknown".into()),
+                            format::time_to_string(entry.metadata.modified()),
+                            format::file_size_to_string(entry.metadata.len()),
+                        )
+                    } else {
+                        Default::default()
+                    };
 
                 let path = self.dirlist.file_path();
 
+                let sort_options = self.tab_options.sort_options_ref();
+                let sort_methods: Vec<&str> = sort_options
+                    .sort_methods
+                    .active()
+                    .map(|m| m.as_str())
+                    .collect();
+                let sort_str = format!(
+                    "sort:{}{} ",
+                    sort_methods.join(">"),
+                    if sort_options.reverse { " rev" } else { "" }
+                );
+
                 let mut text = vec![
                     Span::styled(
                         if self.dirlist.get_visual_mode_anchor_index().is_none() {
@@ -102,6 +185,42 @@ impl Widget for TuiFooter<'_> {
                         },
                         indicator_style,
                     ),
+                    Span::styled(
+                        match self
+                            .tab_options
+                            .dirlist_options_ref(&path.to_path_buf())
+                            .and_then(|opt| opt.tag_filter())
+                        {
+                            Some(tag) => format!("tag:{} ", tag),
+                            None => "".to_owned(),
+                        },
+                        indicator_style,
+                    ),
+                    Span::styled(
+                        if self.dirlist.virtual_paths.is_some() && self.dirlist.load_id.is_some() {
+                            format!("find:{}… ", self.dirlist.len())
+                        } else {
+                            "".to_owned()
+                        },
+                        indicator_style,
+                    ),
+                    Span::styled(
+                        if self.dirlist.hidden_count > 0 {
+                            format!("hidden:{} ", self.dirlist.hidden_count)
+                        } else {
+                            "".to_owned()
+                        },
+                        indicator_style,
+                    ),
+                    Span::styled(
+                        if self.tab_options.show_ignored {
+                            ""
+                        } else {
+                            "ignored:off "
+                        },
+                        indicator_style,
+                    ),
+                    Span::styled(sort_str, indicator_style),
                     Span::styled(
                         if selected_count > 0 {
                             format!("{} selected", selected_count)
@@ -112,7 +231,27 @@ impl Widget for TuiFooter<'_> {
                     ),
                 ];
 
-                if let LinkType::Symlink { target, valid } = entry.metadata.link_type() {
+                if let Some(branch) = self.git_status.and_then(|repo| repo.branch.as_deref()) {
+                    let mut branch_str = format!(" {branch}");
+                    if let Some((ahead, behind)) =
+                        self.git_status.and_then(|repo| repo.ahead_behind)
+                    {
+                        if ahead > 0 {
+                            branch_str.push_str(&format!(" ↑{ahead}"));
+                        }
+                        if behind > 0 {
+                            branch_str.push_str(&format!(" ↓{behind}"));
+                        }
+                    }
+                    text.push(Span::styled(branch_str, branch_style));
+                }
+
+                if let LinkType::Symlink {
+                    target,
+                    valid,
+                    hops,
+                } = entry.metadata.link_type()
+                {
                     let link_style = if *valid {
                         Style::default()
                             .fg(THEME_T.link.fg)
@@ -126,6 +265,11 @@ impl Widget for TuiFooter<'_> {
                     };
                     text.push(Span::styled(" -> ", link_style));
                     text.push(Span::styled(target, link_style));
+                    // further hops of links to links
+                    for hop in hops {
+                        text.push(Span::styled(" -> ", link_style));
+                        text.push(Span::styled(hop.to_string_lossy().into_owned(), link_style));
+                    }
                 }
 
                 Paragraph::new(Line::from(text)).render(area, buf);
@@ -0,0 +1,117 @@
+use std::path::Path;
+
+use ratatui::buffer::Buffer;
+use ratatui::layout::Rect;
+use ratatui::style::{Color, Modifier, Style};
+use ratatui::widgets::Widget;
+use regex::Regex;
+use unicode_width::UnicodeWidthStr;
+
+use crate::fs::GrepMatch;
+use crate::THEME_T;
+
+/// The results of `grep`, one `path:line: text` per match, paths relative to the searched
+/// directory.
+pub struct TuiGrepResults<'a> {
+    root: &'a Path,
+    matches: &'a [GrepMatch],
+    index: usize,
+    skip: usize,
+}
+
+impl<'a> TuiGrepResults<'a> {
+    /// Creates the widget for `matches` found below `root`, scrolled past the first `skip` of
+    /// them and highlighting the one at `index`.
+    pub fn new(root: &'a Path, matches: &'a [GrepMatch], index: usize, skip: usize) -> Self {
+        Self {
+            root,
+            matches,
+            index,
+            skip,
+        }
+    }
+}
+
+impl Widget for TuiGrepResults<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 4 || area.height < 1 {
+            return;
+        }
+        let drawing_width = area.width as usize;
+        let space_fill = " ".repeat(drawing_width);
+
+        for (i, m) in self
+            .matches
+            .iter()
+            .enumerate()
+            .skip(self.skip)
+            .take(area.height as usize)
+        {
+            let path = m.path.strip_prefix(self.root).unwrap_or(&m.path);
+            let location = format!("{}:{}:", path.to_string_lossy(), m.line);
+            let (location_style, text_style) = if i == self.index {
+                let style = Style::default().add_modifier(Modifier::REVERSED);
+                (style, style)
+            } else {
+                (THEME_T.directory.as_style(), THEME_T.regular.as_style())
+            };
+
+            let y = area.y + (i - self.skip) as u16;
+            buf.set_string(area.x, y, space_fill.as_str(), text_style);
+            let (x, _) = buf.set_stringn(area.x, y, &location, drawing_width, location_style);
+            let width = area.right().saturating_sub(x + 1) as usize;
+            buf.set_stringn(x + 1, y, &m.text, width, text_style);
+        }
+    }
+}
+
+/// The lines around a `grep` match, numbered, with the matched line in bold and the parts
+/// matching the pattern highlighted.
+pub struct TuiGrepContext<'a> {
+    lines: &'a [(usize, String)],
+    line: usize,
+    regex: &'a Regex,
+}
+
+impl<'a> TuiGrepContext<'a> {
+    /// Creates the widget for `lines` (with their numbers) around the matched `line`.
+    pub fn new(lines: &'a [(usize, String)], line: usize, regex: &'a Regex) -> Self {
+        Self { lines, line, regex }
+    }
+}
+
+impl Widget for TuiGrepContext<'_> {
+    fn render(self, area: Rect, buf: &mut Buffer) {
+        if area.width < 8 || area.height < 1 {
+            return;
+        }
+        let number_width = self.lines.last().map_or(1, |(n, _)| n.to_string().len());
+        let number_style = Style::default().fg(Color::DarkGray);
+        let match_style = Style::default()
+            .fg(Color::Black)
+            .bg(Color::Yellow)
+            .add_modifier(Modifier::BOLD);
+
+        for ((n, text), y) in self.lines.iter().zip(area.top()..area.bottom()) {
+            let number = format!("{:>number_width$} ", n);
+            let (x, _) = buf.set_stringn(area.x, y, &number, area.width as usize, number_style);
+            let text_style = if *n == self.line {
+                THEME_T.regular.as_style().add_modifier(Modifier::BOLD)
+            } else {
+                THEME_T.regular.as_style()
+            };
+            let width = area.right().saturating_sub(x) as usize;
+            buf.set_stringn(x, y, text, width, text_style);
+
+            // highlight the matched parts, as far as they are visible
+            for m in self.regex.find_iter(text) {
+                let start = x as usize + text[..m.start()].width();
+                if start >= area.right() as usize {
+                    break;
+                }
+                let width = area.right() as usize - start;
+                buf.set_stringn(start as u16, y, m.as_str(), width, match_style);
+            }
+        }
+    }
+}
@@ -50,14 +50,10 @@ impl Widget for TuiTopBar<'_> {
         }
 
         let available_tab_width = area.width as usize - name_width;
-        let mut paths = Vec::new();
         let tabs = self.app_state.state.tab_state_ref().tab_refs_in_order();
-        for tab in tabs {
-            paths.push(tab.get_cwd());
-        }
         let tab_bar_spans = factor_tab_bar_spans(
             available_tab_width,
-            &paths,
+            &tabs,
             self.app_state.state.tab_state_ref().index,
             &THEME_T.tabs,
         );
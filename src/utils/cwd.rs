@@ -1,10 +1,13 @@
 use std::path;
 
+use crate::fs::VfsPath;
 use crate::HOSTNAME;
 
 /// Sets the process's current directory to `path` and notifies the terminal via an OSC 7
-/// escape sequence, so external tools (e.g. new terminal tabs) can pick up the cwd.
+/// escape sequence, so external tools (e.g. new terminal tabs) can pick up the cwd. Inside an
+/// archive, the directory containing the archive is used instead.
 pub fn set_current_dir(path: &path::Path) -> std::io::Result<()> {
+    let path = VfsPath::resolve(path).local_dir();
     std::env::set_current_dir(path)?;
     // OSC 7:
     // Escape sequences to advise the terminal of the working directory
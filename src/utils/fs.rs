@@ -3,6 +3,19 @@ use std::fs;
 use std::io;
 use std::path;
 
+use crate::fs::{archive_index, ArchiveKind, FileType, VfsPath};
+use crate::TAGS_T;
+
+/// Moves the stored tags of `from` and everything below it to `to`, after joshuto renamed or
+/// moved it. Failing to save the tags doesn't fail the operation itself.
+pub fn rename_tags(from: &path::Path, to: &path::Path) {
+    if let Ok(mut tags) = TAGS_T.lock() {
+        if tags.rename(from, to) {
+            let _ = tags.save();
+        }
+    }
+}
+
 /// Get total bytes and number of files inside a list of files/folders
 pub fn query_number_of_items(paths: &[path::PathBuf]) -> io::Result<(usize, u64)> {
     let mut total_bytes = 0;
@@ -10,6 +23,19 @@ pub fn query_number_of_items(paths: &[path::PathBuf]) -> io::Result<(usize, u64)
 
     let mut dirs: VecDeque<path::PathBuf> = VecDeque::new();
     for path in paths.iter() {
+        if let VfsPath::Archive {
+            archive,
+            kind,
+            inner,
+        } = VfsPath::resolve(path)
+        {
+            if !inner.as_os_str().is_empty() {
+                let (files, bytes) = query_archive_items(archive, kind, inner)?;
+                total_files += files;
+                total_bytes += bytes;
+                continue;
+            }
+        }
         let metadata = path.symlink_metadata()?;
         if metadata.is_dir() {
             dirs.push_back(path.clone());
@@ -38,3 +64,34 @@ pub fn query_number_of_items(paths: &[path::PathBuf]) -> io::Result<(usize, u64)
     }
     Ok((total_files, total_bytes))
 }
+
+/// Get total bytes and number of files of the entry `inner` of an archive (empty for all of it)
+/// and the entries below it, directories and symlinks counting as 1 byte
+pub fn query_archive_items(
+    archive: &path::Path,
+    kind: ArchiveKind,
+    inner: &path::Path,
+) -> io::Result<(usize, u64)> {
+    let index = archive_index(archive, kind)?;
+    let entry = if inner.as_os_str().is_empty() {
+        None
+    } else {
+        let entry = index
+            .get(inner)
+            .ok_or_else(|| io::Error::from(io::ErrorKind::NotFound))?;
+        Some(entry)
+    };
+    let mut total_bytes = 0;
+    let mut total_files = 0;
+    for entry in entry
+        .into_iter()
+        .chain(index.entries_below(inner, usize::MAX).map(|(_, e)| e))
+    {
+        total_bytes += match entry.file_type {
+            FileType::File => entry.len,
+            _ => 1,
+        };
+        total_files += 1;
+    }
+    Ok((total_files, total_bytes))
+}
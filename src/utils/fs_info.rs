@@ -0,0 +1,139 @@
+//! Space usage, mount point, and type of the filesystem a path is on.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use nix::sys::statvfs::statvfs;
+
+const MOUNTINFO: &str = "/proc/self/mountinfo";
+
+/// Space usage, mount point, and type of a filesystem.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct FsInfo {
+    pub mount_point: PathBuf,
+    pub fs_type: String,
+    /// size in bytes
+    pub total: u64,
+    /// bytes available to unprivileged users
+    pub available: u64,
+    /// bytes in use
+    pub used: u64,
+}
+
+impl FsInfo {
+    /// Queries the filesystem `path` is on. The mount point and type are left empty where
+    /// `/proc/self/mountinfo` isn't available.
+    pub fn for_path(path: &Path) -> io::Result<Self> {
+        let stat = statvfs(path)?;
+        let fragment_size = stat.fragment_size() as u64;
+        let total = stat.blocks() as u64 * fragment_size;
+        let free = stat.blocks_free() as u64 * fragment_size;
+        let available = stat.blocks_available() as u64 * fragment_size;
+
+        let path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        let (mount_point, fs_type) = fs::read_to_string(MOUNTINFO)
+            .ok()
+            .and_then(|s| find_mount(&s, &path))
+            .unwrap_or_default();
+
+        Ok(Self {
+            mount_point,
+            fs_type,
+            total,
+            available,
+            used: total.saturating_sub(free),
+        })
+    }
+
+    /// Returns the percentage of the space usable by unprivileged users that's in use, as
+    /// reported by `df`.
+    pub fn usage_percent(&self) -> u8 {
+        let usable = self.used + self.available;
+        if usable == 0 {
+            return 0;
+        }
+        (self.used as f64 * 100.0 / usable as f64).ceil() as u8
+    }
+}
+
+/// Finds the mount point and filesystem type of the innermost mount containing `path` in the
+/// contents of a `mountinfo` file.
+fn find_mount(mountinfo: &str, path: &Path) -> Option<(PathBuf, String)> {
+    mountinfo
+        .lines()
+        .filter_map(|line| {
+            // fields before the " - " separator are: mount ID, parent ID, major:minor, root,
+            // mount point, options and optional fields; the fs type comes right after it
+            let (mount, fs) = line.split_once(" - ")?;
+            let mount_point = PathBuf::from(unescape(mount.split(' ').nth(4)?));
+            let fs_type = fs.split(' ').next()?.to_string();
+            Some((mount_point, fs_type))
+        })
+        .filter(|(mount_point, _)| path.starts_with(mount_point))
+        // later mounts shadow earlier ones on the same mount point
+        .max_by_key(|(mount_point, _)| mount_point.components().count())
+}
+
+/// Decodes the octal escapes (`\040` for a space) used in `mountinfo` paths.
+fn unescape(s: &str) -> String {
+    let mut result = String::with_capacity(s.len());
+    let mut rest = s;
+    while let Some(i) = rest.find('\\') {
+        result.push_str(&rest[..i]);
+        let code = rest
+            .get(i + 1..i + 4)
+            .and_then(|c| u8::from_str_radix(c, 8).ok());
+        match code {
+            Some(c) => {
+                result.push(c as char);
+                rest = &rest[i + 4..];
+            }
+            None => {
+                result.push('\\');
+                rest = &rest[i + 1..];
+            }
+        }
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MOUNTINFO: &str = "\
+22 1 8:1 / / rw,relatime shared:1 - ext4 /dev/sda1 rw
+23 22 0:5 / /dev rw,nosuid - devtmpfs udev rw
+24 22 8:2 / /home rw,relatime shared:2 master:1 - btrfs /dev/sda2 rw
+25 24 8:3 / /home/user/my\\040disk rw - vfat /dev/sdb1 rw
+26 22 0:30 / /mnt rw - nfs server:/ rw
+27 22 0:31 / /mnt rw - tmpfs tmpfs rw";
+
+    #[test]
+    fn test_find_mount() {
+        let mount = |p: &str| find_mount(MOUNTINFO, Path::new(p));
+        assert_eq!(mount("/etc"), Some(("/".into(), "ext4".into())));
+        assert_eq!(mount("/dev/pts"), Some(("/dev".into(), "devtmpfs".into())));
+        assert_eq!(mount("/devices"), Some(("/".into(), "ext4".into())));
+        assert_eq!(mount("/home/user"), Some(("/home".into(), "btrfs".into())));
+        assert_eq!(mount("/mnt/data"), Some(("/mnt".into(), "tmpfs".into())));
+        assert_eq!(
+            mount("/home/user/my disk/photos"),
+            Some(("/home/user/my disk".into(), "vfat".into()))
+        );
+    }
+
+    #[test]
+    fn test_usage_percent() {
+        let info = FsInfo {
+            total: 100,
+            available: 25,
+            used: 70,
+            ..Default::default()
+        };
+        assert_eq!(info.usage_percent(), 74);
+        assert_eq!(FsInfo::default().usage_percent(), 0);
+    }
+}
@@ -1,5 +1,7 @@
+use std::collections::HashMap;
+use std::os::unix::ffi::OsStrExt;
 use std::path::Path;
-use std::process::Command;
+use std::process::{Command, Stdio};
 
 use crate::error::{AppError, AppErrorKind, AppResult};
 
@@ -54,3 +56,70 @@ pub fn get_mimetype(p: &Path) -> AppResult<Mimetype> {
         }
     }
 }
+
+/// Number of paths passed to a single `file` run, to stay well below the argument size limit.
+pub const MIMETYPES_CHUNK_SIZE: usize = 256;
+
+/// Detects the MIME types of `paths` with `file --mime-type`, run once per chunk of paths, in
+/// order. Paths whose type can't be detected get `None`.
+pub fn get_mimetypes(paths: &[&Path]) -> Vec<Option<String>> {
+    let mut mimetypes = Vec::with_capacity(paths.len());
+    for chunk in paths.chunks(MIMETYPES_CHUNK_SIZE) {
+        let output = Command::new("file")
+            .args([
+                "--mime-type",
+                "--dereference",
+                "--raw",
+                "--no-pad",
+                "--print0",
+            ])
+            .arg("--")
+            .args(chunk)
+            .stdin(Stdio::null())
+            .stderr(Stdio::null())
+            .output();
+        let stdout = output.map(|output| output.stdout).unwrap_or_default();
+        let mut by_name = parse_mimetypes(&stdout);
+        mimetypes.extend(
+            chunk
+                .iter()
+                .map(|path| by_name.remove(path.as_os_str().as_bytes())),
+        );
+    }
+    mimetypes
+}
+
+/// Parses the output of `file --mime-type --raw --print0`, a NUL-terminated file name followed
+/// by `: <type>` and a newline per file, into the type of each file name. The messages `file`
+/// prints instead for files it can't read are left out.
+fn parse_mimetypes(output: &[u8]) -> HashMap<&[u8], String> {
+    let mut mimetypes = HashMap::new();
+    let mut rest = output;
+    while let Some(nul) = rest.iter().position(|b| *b == 0) {
+        let name = &rest[..nul];
+        rest = &rest[nul + 1..];
+        let end = rest.iter().position(|b| *b == b'\n').unwrap_or(rest.len());
+        let description = String::from_utf8_lossy(&rest[..end]);
+        let mimetype = description.trim_start_matches(':').trim();
+        if !mimetype.contains(char::is_whitespace) && mimetype.contains('/') {
+            mimetypes.insert(name, mimetype.to_string());
+        }
+        rest = rest.get(end + 1..).unwrap_or_default();
+    }
+    mimetypes
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_mimetypes() {
+        let output = b"a.txt\0: text/plain\nline\nbreak.png\0: image/png\n\
+            gone\0: cannot open `gone' (No such file or directory)\n";
+        let mimetypes = parse_mimetypes(output);
+        assert_eq!(mimetypes.len(), 2);
+        assert_eq!(mimetypes[&b"a.txt"[..]], "text/plain");
+        assert_eq!(mimetypes[&b"line\nbreak.png"[..]], "image/png");
+    }
+}
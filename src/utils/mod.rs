@@ -4,6 +4,7 @@
 pub mod cwd;
 pub mod format;
 pub mod fs;
+pub mod fs_info;
 pub mod keyparse;
 pub mod mimetype;
 pub mod name_resolution;
@@ -11,4 +12,5 @@ pub mod process;
 pub mod serde;
 pub mod string;
 pub mod style;
+pub mod symlink;
 pub mod unix;
@@ -5,6 +5,7 @@ use std::path::Path;
 
 use crate::config::app::AppConfig;
 use crate::fs::{FileType, JoshutoDirEntry, LinkType};
+use crate::git::GitStatus;
 use crate::utils::unix;
 
 use crate::THEME_T;
@@ -27,8 +28,13 @@ impl PathStyleIfSome for Style {
 }
 
 /// Returns the display style for `entry`: selection highlight takes priority, then LS_COLORS
-/// (if enabled), then the theme's built-in per-type/per-extension styles.
-pub fn entry_style(config: &AppConfig, entry: &JoshutoDirEntry) -> Style {
+/// (if enabled), then the theme's built-in per-type/per-extension styles, with the theme's
+/// style for the entry's git status (if any) and the style of its tag (if any) patched on top.
+pub fn entry_style(
+    config: &AppConfig,
+    entry: &JoshutoDirEntry,
+    git_status: Option<GitStatus>,
+) -> Style {
     let metadata = &entry.metadata;
     let filetype = metadata.file_type();
     let linktype = metadata.link_type();
@@ -40,14 +46,21 @@ pub fn entry_style(config: &AppConfig, entry: &JoshutoDirEntry) -> Style {
         return permanent_selected_style();
     }
 
-    match &THEME_T.lscolors {
+    let style = match &THEME_T.lscolors {
         Some(lscolors) => {
             let path = entry.file_path();
             lscolors_style(lscolors, path)
                 .unwrap_or_else(|| default_style(config, entry, linktype, filetype))
         }
         None => default_style(config, entry, linktype, filetype),
-    }
+    };
+    style
+        .patch_optionally(git_status.map(|status| THEME_T.git.style(status)))
+        .patch_optionally(
+            entry
+                .tag()
+                .and_then(|tag| THEME_T.tag_colors.get(&tag).copied()),
+        )
 }
 
 /// Returns the themed prefix string to display before `entry`'s name, based on its selection state.
@@ -0,0 +1,151 @@
+//! Resolving and rewriting symlink targets.
+
+use std::collections::HashSet;
+use std::fs;
+use std::io;
+use std::os::unix;
+use std::path::{Component, Path, PathBuf};
+
+/// Most links followed by [`link_chain`], as for path resolution in Linux.
+const MAX_HOPS: usize = 40;
+
+/// Returns `path` with `.` and `..` components removed, without touching the filesystem.
+pub fn normalize(path: &Path) -> PathBuf {
+    let mut result = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                if !result.pop() && !result.has_root() {
+                    result.push("..");
+                }
+            }
+            c => result.push(c),
+        }
+    }
+    result
+}
+
+/// Returns `dir` with links resolved, so `..` leads where the kernel takes it, or just
+/// normalized if it can't be resolved.
+fn canonical_dir(dir: &Path) -> PathBuf {
+    fs::canonicalize(dir).unwrap_or_else(|_| normalize(dir))
+}
+
+/// Returns the path a link at `link` pointing to `target` points to, resolving relative targets
+/// against the link's directory, with links in that directory's path resolved.
+pub fn resolve_target(link: &Path, target: &Path) -> PathBuf {
+    match link.parent() {
+        Some(parent) => normalize(&canonical_dir(parent).join(target)),
+        None => normalize(target),
+    }
+}
+
+/// Returns the relative path leading from the directory `dir` to `target`, both absolute. Links
+/// in the path of `dir` are resolved first.
+pub fn relative_path(dir: &Path, target: &Path) -> PathBuf {
+    let dir = canonical_dir(dir);
+    let target = normalize(target);
+    let common = dir
+        .components()
+        .zip(target.components())
+        .take_while(|(a, b)| a == b)
+        .count();
+
+    let mut result = PathBuf::new();
+    for _ in dir.components().skip(common) {
+        result.push("..");
+    }
+    for component in target.components().skip(common) {
+        result.push(component);
+    }
+    if result.as_os_str().is_empty() {
+        result.push(".");
+    }
+    result
+}
+
+/// Returns the paths the link at `path` resolves through, one per hop, ending with the first
+/// path that isn't a link. Stops early at a loop.
+pub fn link_chain(path: &Path) -> Vec<PathBuf> {
+    let mut chain = Vec::new();
+    let mut seen = HashSet::new();
+    let mut curr = path.to_path_buf();
+    while chain.len() < MAX_HOPS && seen.insert(curr.clone()) {
+        let Ok(target) = fs::read_link(&curr) else {
+            break;
+        };
+        curr = resolve_target(&curr, &target);
+        chain.push(curr.clone());
+    }
+    chain
+}
+
+/// Points the link at `link` to `target`, replacing it atomically.
+pub fn retarget(link: &Path, target: &Path) -> io::Result<()> {
+    let file_name = link
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "Not a link"))?;
+    let mut tmp_name = file_name.to_os_string();
+    tmp_name.push(".joshuto-link");
+    let tmp = link.with_file_name(tmp_name);
+
+    unix::fs::symlink(target, &tmp)?;
+    fs::rename(&tmp, link).inspect_err(|_| {
+        let _ = fs::remove_file(&tmp);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_normalize() {
+        assert_eq!(normalize(Path::new("/a/./b/../c")), Path::new("/a/c"));
+        assert_eq!(normalize(Path::new("/../a")), Path::new("/a"));
+        assert_eq!(normalize(Path::new("../a/..")), Path::new(".."));
+    }
+
+    #[test]
+    fn test_resolve_target() {
+        let link = Path::new("/home/user/link");
+        assert_eq!(
+            resolve_target(link, Path::new("../other/file")),
+            Path::new("/home/other/file")
+        );
+        assert_eq!(resolve_target(link, Path::new("/etc")), Path::new("/etc"));
+    }
+
+    #[test]
+    fn test_relative_path() {
+        let rel = |dir: &str, target: &str| relative_path(Path::new(dir), Path::new(target));
+        assert_eq!(rel("/a/b", "/a/c/d"), Path::new("../c/d"));
+        assert_eq!(rel("/a/b", "/a/b/c"), Path::new("c"));
+        assert_eq!(rel("/a/b", "/a"), Path::new(".."));
+        assert_eq!(rel("/a/b", "/a/b"), Path::new("."));
+        assert_eq!(rel("/a", "/x/y"), Path::new("../x/y"));
+    }
+
+    #[test]
+    fn test_resolve_through_linked_dir() {
+        let dir = std::env::temp_dir().join(format!("joshuto-symlink-{}", std::process::id()));
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(dir.join("real/sub")).unwrap();
+        unix::fs::symlink(dir.join("real/sub"), dir.join("alias")).unwrap();
+        let dir = fs::canonicalize(&dir).unwrap();
+
+        // `..` from inside the linked directory leads to the real directory's parent
+        let link = dir.join("alias/link");
+        assert_eq!(
+            resolve_target(&link, Path::new("../file")),
+            dir.join("real/file")
+        );
+        assert_eq!(
+            relative_path(&dir.join("alias"), &dir.join("real/file")),
+            Path::new("../file")
+        );
+
+        let _ = fs::remove_dir_all(&dir);
+    }
+}